@@ -1,44 +1,107 @@
-use std::{fs, path::PathBuf};
-
 fn main() {
-    // // 链接库文件
-    println!("cargo:rustc-link-search=native=../kmbox_libs/lib");
-    println!("cargo:rustc-link-lib=dylib=kmboxAI");
-    println!("cargo:rustc-link-lib=dylib=rknnrt");
-    println!("cargo:rustc-link-lib=dylib=rga");
-
-    // read ../kmbox_libs/include/kmboxAIlib/*
-    let files = fs::read_dir("../kmbox_libs/include/kmboxAIlib").unwrap();
-    for file in files {
-        let file = file.unwrap();
-        let path = file.path();
-        let file_name = path.file_name().unwrap().to_str().unwrap();
-        let bindings = bindgen::Builder::default()
-            .clang_args(&[
-                "-I/usr/include/opencv4/opencv",
-                "-I/usr/include/opencv4",
-                "-I/usr/include/c++/10",
-                "-I/usr/include/x86_64-linux-gnu/c++/10",
-                "-x",
-                "c++",
-                "--std=c++11",
-            ])
-            // The input header we would like to generate
-            // bindings for.
-            .header(format!("../kmbox_libs/include/kmboxAIlib/{}", file_name))
-            // Tell cargo to invalidate the built crate whenever any of the
-            // included header files changed.
-            .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
-            // Finish the builder and generate the bindings.
-            .generate()
-            // Unwrap the Result and panic on failure.
-            .expect("Unable to generate bindings");
-
-        // Write the bindings to the $OUT_DIR/bindings.rs file.
-        fs::create_dir_all("src/bindings").unwrap();
-        let out_path = PathBuf::from(format!("src/bindings/{}.rs", file_name));
-        bindings
-            .write_to_file(out_path)
-            .expect("Couldn't write bindings!");
+    key_table::main();
+
+    // 未启用 `hardware` 特性时不链接厂商库，也不重新生成绑定，
+    // 直接使用 src/bindings 中已提交的文件
+    #[cfg(feature = "hardware")]
+    hardware::main();
+}
+
+/// 由 key_table.txt 生成 keyboard 模块的按键表（`$OUT_DIR/key_table.rs`），
+/// 文件格式见 key_table.txt 开头的说明
+mod key_table {
+    use std::fmt::Write;
+    use std::{env, fs, path::PathBuf};
+
+    pub fn main() {
+        println!("cargo:rerun-if-changed=key_table.txt");
+        println!("cargo:rerun-if-changed=build.rs");
+
+        let source = fs::read_to_string("key_table.txt").expect("无法读取 key_table.txt");
+        let mut out = String::from("key_table! {\n");
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(comment) = line.strip_prefix('#') {
+                writeln!(out, "    //{}", comment).unwrap();
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [variant, constant, value, name] = fields[..] else {
+                panic!("key_table.txt 第 {} 行应有 4 列: {}", index + 1, line);
+            };
+            writeln!(
+                out,
+                "    {} = {} = {}, {:?};",
+                variant, constant, value, name
+            )
+            .unwrap();
+        }
+        out.push_str("}\n");
+
+        let out_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("key_table.rs");
+        fs::write(out_path, out).expect("无法写入 key_table.rs");
+    }
+}
+
+#[cfg(feature = "hardware")]
+mod hardware {
+    use std::{fs, path::PathBuf};
+
+    pub fn main() {
+        // // 链接库文件
+        println!("cargo:rustc-link-search=native=../kmbox_libs/lib");
+        println!("cargo:rustc-link-lib=dylib=kmboxAI");
+        println!("cargo:rustc-link-lib=dylib=rknnrt");
+        println!("cargo:rustc-link-lib=dylib=rga");
+
+        // read ../kmbox_libs/include/kmboxAIlib/*
+        let files = fs::read_dir("../kmbox_libs/include/kmboxAIlib").unwrap();
+        for file in files {
+            let file = file.unwrap();
+            let path = file.path();
+            let file_name = path.file_name().unwrap().to_str().unwrap();
+            let mut builder = bindgen::Builder::default()
+                .clang_args(&[
+                    "-I/usr/include/opencv4/opencv",
+                    "-I/usr/include/opencv4",
+                    "-I/usr/include/c++/10",
+                    "-I/usr/include/x86_64-linux-gnu/c++/10",
+                    "-x",
+                    "c++",
+                    "--std=c++11",
+                ])
+                // The input header we would like to generate
+                // bindings for.
+                .header(format!("../kmbox_libs/include/kmboxAIlib/{}", file_name))
+                // Tell cargo to invalidate the built crate whenever any of the
+                // included header files changed.
+                .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()));
+            if file_name == "kmboxAI.h" {
+                // kmboxAI.h 会引入整个 OpenCV，完整生成的绑定存在重复常量无法编译，
+                // 这里只保留 kmAI_* 函数及其依赖的类型。
+                // 按值传递 cv::Mat 的函数在 ffi 模块中手写声明（见 src/ffi.rs）
+                builder = builder
+                    .allowlist_function("kmAI_.*")
+                    .blocklist_function("kmAI_MiniUI_LCDdisplay")
+                    .blocklist_function("kmAI_YOLO_InterfaceModel")
+                    .blocklist_function("kmAI_YOLO_DrawRectangle")
+                    .blocklist_function("kmAI_YOLO_DrawText");
+            }
+            let bindings = builder
+                // Finish the builder and generate the bindings.
+                .generate()
+                // Unwrap the Result and panic on failure.
+                .expect("Unable to generate bindings");
+
+            // Write the bindings to the $OUT_DIR/bindings.rs file.
+            fs::create_dir_all("src/bindings").unwrap();
+            let out_path = PathBuf::from(format!("src/bindings/{}.rs", file_name));
+            bindings
+                .write_to_file(out_path)
+                .expect("Couldn't write bindings!");
+        }
     }
 }
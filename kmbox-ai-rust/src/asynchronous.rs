@@ -0,0 +1,442 @@
+//! 异步接口模块（需要启用 `async` 特性）
+//!
+//! - [`AsyncKmbox`]: 键鼠调用交给 [`KmboxHandle`] 的写线程执行，调用方 `await` 结果，不阻塞执行器
+//! - [`InferenceWorker`]: 在专用线程上运行 YOLO 检测或 RKNN 推理，尚未开始的任务可以取消
+//! - [`EventStream`]: 以 `Stream` 形式提供物理键鼠事件
+//!
+//! 只依赖 `futures-core` 和 `futures-channel`，不绑定具体运行时，可以在 tokio、async-std 等运行时中使用。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::asynchronous::InferenceWorker;
+//! use kmbox_ai_rust::kmbox_ai::{KmboxAI, MouseAction, MouseButton};
+//! use kmbox_ai_rust::vision::{Image, ImageFormat};
+//! use kmbox_ai_rust::yolo::YoloDetector;
+//!
+//! # async fn run() -> kmbox_ai_rust::KmboxResult<()> {
+//! let kmbox = KmboxAI::new()?;
+//! let input = kmbox.async_handle();
+//! let detector = InferenceWorker::new(|| YoloDetector::new("model.rknn"))?;
+//!
+//! let frame = Image::new(640, 640, ImageFormat::Rgb888);
+//! let result = detector.detect(frame).await?;
+//! if let Some(target) = result.objects.first() {
+//!     input.mouse_move((target.x * 100.0) as i32, (target.y * 100.0) as i32).await?;
+//!     input.mouse_button(MouseButton::Left, MouseAction::Click).await?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::{KmboxError, KmboxResult};
+use crate::handle::{to_short, writer_closed, KmboxHandle, Writer};
+use crate::kmbox_ai::{read_mouse_state, MouseAction, MouseButton, MouseState};
+use crate::monitor::{spawn_sampler, InputEvent, MonitorConfig};
+use crate::report::MouseReport;
+use crate::rknn::{RknnContext, RknnInferenceResult, RknnTensor};
+use crate::vision::Image;
+use crate::yolo::{DetectionResult, YoloDetector};
+use futures_channel::{mpsc as async_mpsc, oneshot};
+use futures_core::Stream;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::thread::{self, JoinHandle};
+
+/// 异步键鼠接口
+///
+/// 可克隆，所有克隆共享同一个写线程。
+#[derive(Clone)]
+pub struct AsyncKmbox {
+    handle: KmboxHandle,
+}
+
+impl AsyncKmbox {
+    /// 使用已有的共享句柄创建异步接口
+    pub fn new(handle: KmboxHandle) -> Self {
+        Self { handle }
+    }
+
+    /// 对应的同步句柄，可查看调用统计
+    pub fn handle(&self) -> &KmboxHandle {
+        &self.handle
+    }
+
+    /// 在写线程中执行调用，完成后唤醒等待的任务
+    async fn call<T, F>(&self, call: F) -> KmboxResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Writer) -> T + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        self.handle.dispatch(call, move |value| {
+            let _ = reply.send(value);
+        })?;
+        result.await.map_err(|_| writer_closed())
+    }
+
+    /// 在写线程中直接使用后端
+    pub async fn with_backend<T, F>(&self, call: F) -> KmboxResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn Backend) -> T + Send + 'static,
+    {
+        self.call(move |writer| call(writer.backend.as_ref())).await
+    }
+
+    /// 鼠标相对移动
+    pub async fn mouse_move(&self, x: i32, y: i32) -> KmboxResult<()> {
+        let (x, y) = (to_short(x)?, to_short(y)?);
+        self.call(move |writer| writer.mouse_move(x, y)).await?
+    }
+
+    /// 鼠标按键操作
+    pub async fn mouse_button(&self, button: MouseButton, action: MouseAction) -> KmboxResult<()> {
+        self.call(move |writer| writer.mouse_button(button, action))
+            .await?
+    }
+
+    /// 以一个报告同时发送按键、位移和滚轮
+    pub async fn send_mouse_report(&self, report: MouseReport) -> KmboxResult<()> {
+        self.call(move |writer| writer.mouse_report(&report))
+            .await?
+    }
+
+    /// 鼠标滚轮操作
+    pub async fn mouse_wheel(&self, wheel: i32) -> KmboxResult<()> {
+        self.call(move |writer| writer.mouse_wheel(wheel)).await?
+    }
+
+    /// 键盘按键按下
+    pub async fn key_down(&self, vkey: i32) -> KmboxResult<()> {
+        self.call(move |writer| writer.key(vkey, true)).await?
+    }
+
+    /// 键盘按键释放
+    pub async fn key_up(&self, vkey: i32) -> KmboxResult<()> {
+        self.call(move |writer| writer.key(vkey, false)).await?
+    }
+
+    /// 初始化键盘
+    pub async fn key_init(&self) -> KmboxResult<()> {
+        self.call(|writer| writer.key_init()).await?
+    }
+
+    /// 监控键盘按键
+    pub async fn monitor_keyboard(&self, vk_key: i16) -> KmboxResult<bool> {
+        self.call(move |writer| writer.monitor_keyboard(vk_key))
+            .await?
+    }
+
+    /// 获取完整鼠标状态
+    pub async fn get_mouse_state(&self) -> KmboxResult<MouseState> {
+        self.call(|writer| read_mouse_state(writer.backend.as_ref()))
+            .await?
+    }
+}
+
+/// 推理线程执行的任务
+type Job<M> = Box<dyn FnOnce(&mut M) + Send>;
+
+/// 推理工作线程
+///
+/// 独占模型（如 [`YoloDetector`]、[`RknnContext`]），按提交顺序逐个执行任务。
+/// 模型在推理线程上创建、使用和释放，不会跨线程移动，因此不要求模型实现 `Send`。
+/// 销毁时不等待正在执行的推理，工作线程执行完已提交且未取消的任务后释放模型并退出。
+pub struct InferenceWorker<M> {
+    sender: Sender<Job<M>>,
+}
+
+impl<M: 'static> InferenceWorker<M> {
+    /// 启动推理线程，在该线程上调用 `create` 创建模型
+    ///
+    /// 等待模型创建完成，`create` 返回的错误原样返回。
+    pub fn new<F>(create: F) -> KmboxResult<Self>
+    where
+        F: FnOnce() -> KmboxResult<M> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel::<Job<M>>();
+        let (created, ready) = mpsc::channel::<KmboxResult<()>>();
+        thread::Builder::new()
+            .name("kmbox-inference".to_string())
+            .spawn(move || {
+                let mut model = match create() {
+                    Ok(model) => model,
+                    Err(e) => {
+                        let _ = created.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = created.send(Ok(()));
+                for job in receiver {
+                    job(&mut model);
+                }
+            })
+            .map_err(|e| KmboxError::io("启动推理线程", e))?;
+
+        ready
+            .recv()
+            .map_err(|_| KmboxError::SystemError("推理线程已退出".to_string()))??;
+        Ok(Self { sender })
+    }
+
+    /// 在推理线程上执行任务
+    ///
+    /// 任务开始前取消（调用 [`InferenceTask::cancel`] 或丢弃 future）时不会执行；
+    /// 已经开始的推理无法中断，结果会被丢弃。
+    pub fn run<T, F>(&self, task: F) -> InferenceTask<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut M) -> KmboxResult<T> + Send + 'static,
+    {
+        let (reply, receiver) = oneshot::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = cancelled.clone();
+
+        // 推理线程已退出时发送失败，receiver 随即关闭，await 时返回错误
+        let _ = self.sender.send(Box::new(move |model: &mut M| {
+            if !flag.load(Ordering::Acquire) {
+                let _ = reply.send(task(model));
+            }
+        }));
+
+        InferenceTask {
+            receiver,
+            cancelled,
+        }
+    }
+}
+
+impl InferenceWorker<YoloDetector> {
+    /// 检测图像中的目标
+    pub fn detect(&self, image: Image) -> InferenceTask<DetectionResult> {
+        self.run(move |detector| detector.detect(&image))
+    }
+}
+
+impl InferenceWorker<RknnContext> {
+    /// 设置输入并运行推理
+    ///
+    /// 设置输入和推理在同一个任务中执行，不会与其他任务交错。
+    pub fn run_inference(&self, inputs: Vec<RknnTensor>) -> InferenceTask<RknnInferenceResult> {
+        self.run(move |context| {
+            context.set_inputs(inputs)?;
+            context.run_inference()
+        })
+    }
+}
+
+/// 已提交的推理任务
+///
+/// 实现了 `Future`，丢弃时自动取消。
+pub struct InferenceTask<T> {
+    receiver: oneshot::Receiver<KmboxResult<T>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<T> InferenceTask<T> {
+    /// 取消任务
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// 是否已取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+impl<T> Future for InferenceTask<T> {
+    type Output = KmboxResult<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.receiver).poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(_)) if self.is_cancelled() => Poll::Ready(Err(
+                KmboxError::InferenceError("推理任务已取消".to_string()),
+            )),
+            Poll::Ready(Err(_)) => {
+                Poll::Ready(Err(KmboxError::SystemError("推理线程已退出".to_string())))
+            }
+        }
+    }
+}
+
+impl<T> Drop for InferenceTask<T> {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// 物理键鼠事件流
+///
+/// 采样方式与 [`InputMonitor`](crate::monitor::InputMonitor) 相同，销毁时停止采样线程。
+pub struct EventStream {
+    receiver: async_mpsc::UnboundedReceiver<InputEvent>,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl EventStream {
+    /// 在指定后端上启动事件监听
+    pub fn start(backend: Arc<dyn Backend>, config: MonitorConfig) -> KmboxResult<Self> {
+        let (sender, receiver) = async_mpsc::unbounded();
+        let (running, handle) = spawn_sampler(backend, config, move |event| {
+            sender.unbounded_send(event).is_ok()
+        })?;
+
+        Ok(Self {
+            receiver,
+            running,
+            handle: Some(handle),
+        })
+    }
+
+    /// 停止采样线程，已产生的事件仍可以从流中取出
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Stream for EventStream {
+    type Item = InputEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
+    use crate::monitor::EventKind;
+    use futures::executor::block_on;
+    use futures::future::join_all;
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[test]
+    fn test_async_calls_go_through_writer() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let input = AsyncKmbox::new(KmboxHandle::new(sim.clone()).unwrap());
+
+        let moves: Vec<_> = (1..=10).map(|i| input.mouse_move(i, 0)).collect();
+        moves.iter().for_each(assert_send);
+        block_on(join_all(moves))
+            .into_iter()
+            .collect::<KmboxResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(sim.reports().len(), 10);
+        assert_eq!(input.handle().stats().calls, 10);
+
+        assert!(block_on(input.mouse_move(0, 40_000)).is_err());
+        block_on(input.key_down(4)).unwrap();
+        drop(input);
+        assert_eq!(
+            sim.reports()[10..],
+            [
+                HidReport::KeyDown(4),
+                HidReport::KeyUp(4),
+                HidReport::KeyInit
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inference_worker_runs_in_order() {
+        let worker = InferenceWorker::new(|| Ok(0u32)).unwrap();
+        let tasks: Vec<_> = (1..=5)
+            .map(|i| {
+                worker.run(move |total: &mut u32| {
+                    *total += i;
+                    Ok(*total)
+                })
+            })
+            .collect();
+        assert_eq!(
+            block_on(join_all(tasks))
+                .into_iter()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>(),
+            [1, 3, 6, 10, 15]
+        );
+
+        let failed = worker
+            .run(|_| -> KmboxResult<()> { Err(KmboxError::InferenceError("失败".to_string())) });
+        assert!(matches!(
+            block_on(failed),
+            Err(KmboxError::InferenceError(_))
+        ));
+    }
+
+    #[test]
+    fn test_model_created_on_worker_thread() {
+        // 模型不需要实现 `Send`
+        let worker = InferenceWorker::new(|| Ok(std::rc::Rc::new(thread::current().id()))).unwrap();
+        let id = block_on(worker.run(|model| Ok(**model))).unwrap();
+        assert_ne!(id, thread::current().id());
+
+        let failed =
+            InferenceWorker::<()>::new(|| Err(KmboxError::ModelError("模型文件无效".to_string())));
+        assert!(matches!(failed, Err(KmboxError::ModelError(_))));
+    }
+
+    #[test]
+    fn test_cancelled_task_is_skipped() {
+        let worker = InferenceWorker::new(|| Ok(())).unwrap();
+        let (release, blocked) = mpsc::channel::<()>();
+        let busy = worker.run(move |_| {
+            let _ = blocked.recv();
+            Ok(1)
+        });
+
+        let ran = Arc::new(AtomicBool::new(false));
+        let flag = ran.clone();
+        let queued = worker.run(move |_| {
+            flag.store(true, Ordering::Release);
+            Ok(2)
+        });
+        queued.cancel();
+        release.send(()).unwrap();
+
+        assert_eq!(block_on(busy).unwrap(), 1);
+        assert!(matches!(
+            block_on(queued),
+            Err(KmboxError::InferenceError(_))
+        ));
+        assert!(!ran.load(Ordering::Acquire));
+        assert_eq!(block_on(worker.run(|_| Ok(3))).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_event_stream() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let config = MonitorConfig::default().with_interval(Duration::from_millis(1));
+        let mut events = EventStream::start(sim.clone(), config).unwrap();
+
+        sim.press_physical_button(MouseButton::Right);
+        let event = block_on(events.next()).unwrap();
+        assert_eq!(event.kind, EventKind::ButtonDown(MouseButton::Right));
+
+        events.stop();
+        while block_on(events.next()).is_some() {}
+    }
+}
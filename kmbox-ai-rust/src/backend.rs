@@ -0,0 +1,302 @@
+//! 后端抽象模块
+//!
+//! 定义了 KmboxAI 设备访问的统一接口 [`Backend`]，
+//! `KmboxAI`、`Keyboard`、`ImageProcessor`、`YoloDetector` 和 `RknnContext` 的所有底层调用都经由该接口完成。
+//!
+//! ## 内置后端
+//!
+//! - `HardwareBackend`：直接调用厂商提供的 `kmAI_*` 等 C 函数，需要在盒子上运行，
+//!   仅在启用 `hardware` 特性（默认启用）时可用
+//! - [`SimulatedBackend`]：纯 Rust 实现的模拟设备，在内存中记录按键、鼠标、掩码等状态，
+//!   可以在任意 Linux 主机上运行 `cargo test`
+//!
+//! ## 使用示例
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use kmbox_ai_rust::backend::SimulatedBackend;
+//! use kmbox_ai_rust::kmbox_ai::{KmboxAI, MouseButton, MouseAction};
+//!
+//! let sim = Arc::new(SimulatedBackend::new());
+//! let kmbox = KmboxAI::with_backend(sim.clone())?;
+//! kmbox.mouse_move(10, 20)?;
+//! kmbox.mouse_button(MouseButton::Left, MouseAction::Press)?;
+//! assert!(sim.state().mouse_buttons.contains(&MouseButton::Left));
+//! ```
+//!
+//! 后端方法的返回值与 C 接口保持一致（0 表示成功），
+//! 由上层封装负责转换为 [`KmboxResult`](crate::error::KmboxResult)。
+
+#[cfg(feature = "hardware")]
+pub mod hardware;
+pub mod simulated;
+
+#[cfg(feature = "hardware")]
+pub use hardware::HardwareBackend;
+pub use simulated::{HidReport, SimulatedBackend, SimulatedDetection, SimulatedState};
+
+use crate::ffi::rknn::{rknn_context, rknn_input, rknn_input_output_num, rknn_output};
+use crate::ffi::{cv_Mat, object_detected_list, rknn_context_t};
+use crate::kmbox_ai::{MouseButton, MouseMask};
+use crate::lcd::LcdFrame;
+use crate::vision::{Image, ImageFormat, ImageRect};
+use std::ffi::CStr;
+use std::io;
+use std::sync::Arc;
+#[cfg(not(feature = "hardware"))]
+use std::sync::OnceLock;
+
+/// KmboxAI 设备后端接口
+///
+/// 每个方法对应一个（或一组）厂商 C 函数，返回值沿用 C 接口的约定。
+pub trait Backend: Send + Sync {
+    // 系统初始化和控制
+
+    /// 初始化硬件（`kmAI_Init`）
+    fn init(&self) -> i32;
+    /// 启动系统（`kmAI_Run`）
+    fn run(&self) -> i32;
+    /// 设置键鼠直通（`kmAI_kmEnable`）
+    fn km_enable(&self, enable: bool) -> i32;
+    /// 获取软件版本号（`kmAI_Version`）
+    fn version(&self) -> i32;
+
+    // 鼠标控制
+
+    /// 鼠标相对移动（`kmAI_mouse_move`）
+    fn mouse_move(&self, x: i16, y: i16) -> i32;
+    /// 鼠标按键控制（`kmAI_mouse_left` 等）
+    fn mouse_button(&self, button: MouseButton, down: bool) -> i32;
+    /// 鼠标滚轮控制（`kmAI_mouse_wheel`）
+    fn mouse_wheel(&self, wheel: i32) -> i32;
+    /// 鼠标按键、移动、滚轮一次性控制（`kmAI_mouse_all`）
+    ///
+    /// `button` 为按键位掩码：bit0 左键、bit1 右键、bit2 中键、bit3 侧键1、bit4 侧键2
+    fn mouse_all(&self, button: i32, x: i32, y: i32, wheel: i32) -> i32;
+
+    // 键盘控制
+
+    /// 键盘按键按下（`kmAI_keydown`）
+    fn key_down(&self, vkey: i32) -> i32;
+    /// 键盘按键释放（`kmAI_keyup`）
+    fn key_up(&self, vkey: i32) -> i32;
+    /// 复位所有软件按下的按键（`kmAI_keyinit`）
+    fn key_init(&self) -> i32;
+
+    // 物理键鼠监控
+
+    /// 查询物理鼠标按键状态（`kmAI_monitor_mouse_left` 等），1 表示按下
+    fn monitor_mouse_button(&self, button: MouseButton) -> i32;
+    /// 查询物理鼠标最近一次移动的值（`kmAI_monitor_mouse_xy`）
+    fn monitor_mouse_xy(&self, x: &mut i32, y: &mut i32) -> i32;
+    /// 查询物理鼠标最近一次滚轮值（`kmAI_monitor_mouse_wheel`）
+    fn monitor_mouse_wheel(&self, wheel: &mut i32) -> i32;
+    /// 查询物理键盘指定按键状态（`kmAI_monitor_keyboard`），1 表示按下
+    fn monitor_keyboard(&self, vkey: i16) -> i32;
+
+    // 物理键鼠屏蔽
+
+    /// 屏蔽/解除屏蔽物理鼠标（`kmAI_mask_mouse_left` 等）
+    fn mask_mouse(&self, mask: MouseMask, enable: bool) -> i32;
+    /// 屏蔽物理键盘指定按键（`kmAI_mask_keyboard`）
+    fn mask_keyboard(&self, vkey: i16) -> i32;
+    /// 解除物理键盘指定按键屏蔽（`kmAI_unmask_keyboard`）
+    fn unmask_keyboard(&self, vkey: i16) -> i32;
+    /// 解除所有物理屏蔽（`kmAI_unmask_all`）
+    fn unmask_all(&self) -> i32;
+
+    // 软件键盘（KeyboardTable.h）
+
+    /// 根据名称获取按键 HID 值（`getKeyHexByName`）
+    fn key_hex_by_name(&self, name: &CStr) -> i32;
+    /// 软件按下按键（`soft_keydown`）
+    fn soft_keydown(&self, value: i32, name: &CStr);
+    /// 软件释放按键（`soft_keyup`）
+    fn soft_keyup(&self, value: i32, name: &CStr);
+    /// 软件点击按键，`duration_ms` 为按下时长（`soft_keypress`）
+    fn soft_keypress(&self, value: i32, name: &CStr, duration_ms: i32);
+    /// 查询按键是否按下（`check_keyisdown`）
+    fn check_keyisdown(&self, value: i32, name: &CStr) -> i32;
+    /// 打印按键表（`showTable`）
+    fn show_table(&self);
+    /// 设置按键掩码（`soft_mask`）
+    fn soft_mask(&self, value: i32, name: &CStr, mask_value: i32) -> i32;
+    /// 查询按键掩码（`soft_ismask`）
+    fn soft_ismask(&self, value: i32, name: &CStr) -> i32;
+    /// 输入字符串（`soft_String`）
+    fn soft_string(&self, text: &CStr) -> i32;
+
+    // MiniUI 控制
+
+    /// 设置 MiniUI 模式（`kmAI_MiniUI_Enable`）
+    fn miniui_enable(&self, mode: i32) -> i32;
+    /// 锁定 MiniUI 用户界面（`kmAI_MiniUI_UserLock`）
+    fn miniui_user_lock(&self) -> i32;
+    /// 解锁 MiniUI 用户界面（`kmAI_MiniUI_UserUnLock`）
+    fn miniui_user_unlock(&self) -> i32;
+    /// 在 LCD 上显示图像（`kmAI_MiniUI_LCDdisplay`）
+    ///
+    /// # Safety
+    ///
+    /// `img` 必须指向一个有效的 `cv::Mat` 对象。
+    unsafe fn miniui_lcd_display(&self, img: *mut cv_Mat) -> i32;
+    /// 把屏幕原生格式的一帧写入 LCD 帧缓冲
+    ///
+    /// 写入的是文件而不是厂商库调用，失败时返回原始的输入输出错误。
+    fn lcd_write(&self, frame: &LcdFrame) -> io::Result<()>;
+
+    // YOLO 模型管理
+
+    /// 加载 YOLO 模型到 `ctx`（`kmAI_YOLO_Loadmodel`）
+    ///
+    /// # Safety
+    ///
+    /// `ctx` 必须指向一个可写的 `rknn_context_t`。
+    unsafe fn yolo_load_model(&self, ctx: *mut rknn_context_t, model_path: &CStr) -> i32;
+    /// 对图像执行推理，结果写入 `out`（`kmAI_YOLO_InterfaceModel`）
+    ///
+    /// # Safety
+    ///
+    /// `ctx` 必须是已成功加载模型的上下文，`img` 必须指向一个有效的 `cv::Mat` 对象。
+    unsafe fn yolo_interface_model(
+        &self,
+        ctx: *mut rknn_context_t,
+        img: *mut cv_Mat,
+        out: &mut object_detected_list,
+    ) -> i32;
+    /// 在图像上绘制矩形（`kmAI_YOLO_DrawRectangle`）
+    ///
+    /// # Safety
+    ///
+    /// `img` 必须指向一个有效的 `cv::Mat` 对象。
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn yolo_draw_rectangle(
+        &self,
+        img: *mut cv_Mat,
+        x: i32,
+        y: i32,
+        w: i32,
+        h: i32,
+        color: u32,
+        thickness: i32,
+    );
+    /// 在图像上绘制文本（`kmAI_YOLO_DrawText`）
+    ///
+    /// # Safety
+    ///
+    /// `img` 必须指向一个有效的 `cv::Mat` 对象。
+    unsafe fn yolo_draw_text(
+        &self,
+        img: *mut cv_Mat,
+        text: &CStr,
+        x: i32,
+        y: i32,
+        color: u32,
+        font_size: i32,
+    );
+    /// 释放 YOLO 上下文（`kmAI_YOLO_Release`）
+    ///
+    /// # Safety
+    ///
+    /// `ctx` 必须是已成功加载模型的上下文，且之后不再使用。
+    unsafe fn yolo_release(&self, ctx: *mut rknn_context_t) -> i32;
+
+    // YOLO 检测（YoloDetector 使用）
+
+    /// 加载 YOLO 检测模型（`yolo_init_v_v`），失败时返回空指针
+    fn yolo_init(&self, model_path: &CStr) -> *mut rknn_context_t;
+    /// 检测图像中的目标，结果写入 `out`（`yolo_detect_v_v`）
+    fn yolo_detect(&self, image: &Image, out: &mut object_detected_list) -> i32;
+    /// 释放 `yolo_init` 返回的上下文（`yolo_destroy_v_v`）
+    ///
+    /// # Safety
+    ///
+    /// `ctx` 必须是本后端 `yolo_init` 返回的非空指针，且之后不再使用。
+    unsafe fn yolo_destroy(&self, ctx: *mut rknn_context_t);
+    /// 设置 NMS 阈值（`yolo_set_nms_thresh_v`）
+    fn yolo_set_nms_thresh(&self, thresh: f32);
+    /// 设置置信度阈值（`yolo_set_box_thresh_v`）
+    fn yolo_set_box_thresh(&self, thresh: f32);
+
+    // RKNN 运行时（kmbox_rknn_api.h）
+
+    /// 从模型文件创建上下文（`rknn_init`，按文件路径加载）
+    fn rknn_init(&self, context: &mut rknn_context, model_path: &CStr) -> i32;
+    /// 查询模型的输入输出数量（`rknn_query`，`RKNN_QUERY_IN_OUT_NUM`）
+    fn rknn_query_io_num(&self, context: rknn_context, io_num: &mut rknn_input_output_num) -> i32;
+    /// 设置输入（`rknn_inputs_set`）
+    ///
+    /// # Safety
+    ///
+    /// 每个输入的 `buf` 必须指向至少 `size` 字节的可读内存。
+    unsafe fn rknn_inputs_set(&self, context: rknn_context, inputs: &mut [rknn_input]) -> i32;
+    /// 执行推理（`rknn_run`）
+    fn rknn_run(&self, context: rknn_context) -> i32;
+    /// 等待推理完成（`rknn_wait`）
+    fn rknn_wait(&self, context: rknn_context) -> i32;
+    /// 获取输出（`rknn_outputs_get`），`is_prealloc` 为 0 时缓冲区由运行时分配
+    ///
+    /// # Safety
+    ///
+    /// `is_prealloc` 非 0 的输出，其 `buf` 必须指向至少 `size` 字节的可写内存。
+    unsafe fn rknn_outputs_get(&self, context: rknn_context, outputs: &mut [rknn_output]) -> i32;
+    /// 释放运行时分配的输出缓冲区（`rknn_outputs_release`）
+    ///
+    /// # Safety
+    ///
+    /// `outputs` 必须是同一上下文上 `rknn_outputs_get` 成功填充的数组，且之后不再使用其中的缓冲区。
+    unsafe fn rknn_outputs_release(
+        &self,
+        context: rknn_context,
+        outputs: &mut [rknn_output],
+    ) -> i32;
+    /// 销毁上下文（`rknn_destroy`）
+    fn rknn_destroy(&self, context: rknn_context) -> i32;
+
+    // 系统操作
+
+    /// 重启盒子（`kmAI_reboot`）
+    fn reboot(&self) -> i32;
+
+    // 图像处理（common.h）
+
+    /// 捕获屏幕图像
+    fn capture_screen(&self) -> Option<Image>;
+    /// 从文件加载图像
+    fn load_image(&self, path: &CStr) -> Option<Image>;
+    /// 保存图像到文件
+    fn save_image(&self, path: &CStr, image: &Image) -> i32;
+    /// 转换图像格式
+    fn convert_image_format(&self, image: &Image, format: ImageFormat) -> Option<Image>;
+    /// 裁剪图像
+    fn crop_image(&self, image: &Image, rect: ImageRect) -> Option<Image>;
+    /// 缩放图像
+    fn resize_image(&self, image: &Image, width: i32, height: i32) -> Option<Image>;
+}
+
+/// 获取默认后端
+///
+/// 启用 `hardware` 特性时返回直接调用厂商 C 函数的 `HardwareBackend`；
+/// 否则返回进程内共享的 [`SimulatedBackend`]，
+/// 这样 `KmboxAI::new()` 与 `Keyboard::new()` 创建的实例操作的是同一个模拟设备。
+pub fn default_backend() -> Arc<dyn Backend> {
+    #[cfg(feature = "hardware")]
+    {
+        Arc::new(HardwareBackend::new())
+    }
+    #[cfg(not(feature = "hardware"))]
+    {
+        shared_simulated_backend()
+    }
+}
+
+/// 获取进程内共享的模拟设备
+///
+/// 未启用 `hardware` 特性时，[`default_backend`] 返回的就是这个实例。
+#[cfg(not(feature = "hardware"))]
+pub fn shared_simulated_backend() -> Arc<SimulatedBackend> {
+    static SHARED: OnceLock<Arc<SimulatedBackend>> = OnceLock::new();
+    SHARED
+        .get_or_init(|| Arc::new(SimulatedBackend::new()))
+        .clone()
+}
@@ -0,0 +1,394 @@
+//! 硬件后端
+//!
+//! 通过厂商提供的动态库（libkmboxAI）直接访问 KmboxAI 设备。
+//! 所有厂商函数都通过 [`crate::ffi`] 调用：`kmAI_*`、RKNN 运行时和软件键盘使用 bindgen 生成的声明，
+//! 没有头文件的图像处理和 YOLO 检测函数使用 `ffi` 中经过修饰名检查的手写声明。
+
+use super::Backend;
+use crate::ffi::keyboard_table::{
+    check_keyisdown, getKeyHexByName, showTable, soft_String, soft_ismask, soft_keydown,
+    soft_keypress, soft_keyup, soft_mask,
+};
+use crate::ffi::rknn::{self, rknn_context, rknn_input, rknn_input_output_num, rknn_output};
+use crate::ffi::{
+    self, capture_screen_v, convert_image_format, crop_image_v, cv_Mat, load_image_v,
+    object_detected_list, release_image_v, resize_image_v, rknn_context_t, save_image_v,
+    yolo_destroy_v_v, yolo_detect_v_v, yolo_init_v_v, yolo_set_box_thresh_v, yolo_set_nms_thresh_v,
+};
+use crate::kmbox_ai::{MouseButton, MouseMask};
+use crate::lcd::LcdFrame;
+use crate::vision::{Image, ImageBufferT, ImageFormat, ImageRect, ImageRectT};
+use std::ffi::CStr;
+use std::io;
+
+/// LCD 帧缓冲设备
+const LCD_FRAMEBUFFER: &str = "/dev/fb0";
+
+/// 硬件后端，所有调用直接转发到厂商 C 函数
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HardwareBackend;
+
+impl HardwareBackend {
+    /// 创建新的硬件后端
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// 将C缓冲区转换为图像并释放C缓冲区
+///
+/// # Safety
+///
+/// `buffer_ptr` 必须为空或指向由厂商库分配的有效缓冲区。
+unsafe fn take_c_buffer(buffer_ptr: *mut ImageBufferT) -> Option<Image> {
+    if buffer_ptr.is_null() {
+        return None;
+    }
+
+    let buffer = *buffer_ptr;
+    let image = Image::from_c_buffer(&buffer);
+
+    // 释放C缓冲区
+    release_image_v(buffer_ptr);
+
+    Some(image)
+}
+
+impl Backend for HardwareBackend {
+    fn init(&self) -> i32 {
+        unsafe { ffi::kmAI_Init() }
+    }
+
+    fn run(&self) -> i32 {
+        unsafe { ffi::kmAI_Run() }
+    }
+
+    fn km_enable(&self, enable: bool) -> i32 {
+        unsafe { ffi::kmAI_kmEnable(if enable { 1 } else { 0 }) }
+    }
+
+    fn version(&self) -> i32 {
+        unsafe { ffi::kmAI_Version() }
+    }
+
+    fn mouse_move(&self, x: i16, y: i16) -> i32 {
+        unsafe { ffi::kmAI_mouse_move(x, y) }
+    }
+
+    fn mouse_button(&self, button: MouseButton, down: bool) -> i32 {
+        let isdown = if down { 1 } else { 0 };
+        unsafe {
+            match button {
+                MouseButton::Left => ffi::kmAI_mouse_left(isdown),
+                MouseButton::Right => ffi::kmAI_mouse_right(isdown),
+                MouseButton::Middle => ffi::kmAI_mouse_middle(isdown),
+                MouseButton::Side1 => ffi::kmAI_mouse_side1(isdown),
+                MouseButton::Side2 => ffi::kmAI_mouse_side2(isdown),
+            }
+        }
+    }
+
+    fn mouse_wheel(&self, wheel: i32) -> i32 {
+        unsafe { ffi::kmAI_mouse_wheel(wheel) }
+    }
+
+    fn mouse_all(&self, button: i32, x: i32, y: i32, wheel: i32) -> i32 {
+        unsafe { ffi::kmAI_mouse_all(button, x, y, wheel) }
+    }
+
+    fn key_down(&self, vkey: i32) -> i32 {
+        unsafe { ffi::kmAI_keydown(vkey) }
+    }
+
+    fn key_up(&self, vkey: i32) -> i32 {
+        unsafe { ffi::kmAI_keyup(vkey) }
+    }
+
+    fn key_init(&self) -> i32 {
+        unsafe { ffi::kmAI_keyinit() }
+    }
+
+    fn monitor_mouse_button(&self, button: MouseButton) -> i32 {
+        unsafe {
+            match button {
+                MouseButton::Left => ffi::kmAI_monitor_mouse_left(),
+                MouseButton::Right => ffi::kmAI_monitor_mouse_right(),
+                MouseButton::Middle => ffi::kmAI_monitor_mouse_middle(),
+                MouseButton::Side1 => ffi::kmAI_monitor_mouse_side1(),
+                MouseButton::Side2 => ffi::kmAI_monitor_mouse_side2(),
+            }
+        }
+    }
+
+    fn monitor_mouse_xy(&self, x: &mut i32, y: &mut i32) -> i32 {
+        unsafe { ffi::kmAI_monitor_mouse_xy(x, y) }
+    }
+
+    fn monitor_mouse_wheel(&self, wheel: &mut i32) -> i32 {
+        unsafe { ffi::kmAI_monitor_mouse_wheel(wheel) }
+    }
+
+    fn monitor_keyboard(&self, vkey: i16) -> i32 {
+        unsafe { ffi::kmAI_monitor_keyboard(vkey) }
+    }
+
+    fn mask_mouse(&self, mask: MouseMask, enable: bool) -> i32 {
+        let enable = if enable { 1 } else { 0 };
+        unsafe {
+            match mask {
+                MouseMask::Left => ffi::kmAI_mask_mouse_left(enable),
+                MouseMask::Right => ffi::kmAI_mask_mouse_right(enable),
+                MouseMask::Middle => ffi::kmAI_mask_mouse_middle(enable),
+                MouseMask::Side1 => ffi::kmAI_mask_mouse_side1(enable),
+                MouseMask::Side2 => ffi::kmAI_mask_mouse_side2(enable),
+                MouseMask::X => ffi::kmAI_mask_mouse_x(enable),
+                MouseMask::Y => ffi::kmAI_mask_mouse_y(enable),
+                MouseMask::XY => ffi::kmAI_mask_mouse_xy(enable),
+                MouseMask::Wheel => ffi::kmAI_mask_mouse_wheel(enable),
+            }
+        }
+    }
+
+    fn mask_keyboard(&self, vkey: i16) -> i32 {
+        unsafe { ffi::kmAI_mask_keyboard(vkey) }
+    }
+
+    fn unmask_keyboard(&self, vkey: i16) -> i32 {
+        unsafe { ffi::kmAI_unmask_keyboard(vkey) }
+    }
+
+    fn unmask_all(&self) -> i32 {
+        unsafe { ffi::kmAI_unmask_all() }
+    }
+
+    fn key_hex_by_name(&self, name: &CStr) -> i32 {
+        unsafe { getKeyHexByName(name.as_ptr()) }
+    }
+
+    fn soft_keydown(&self, value: i32, name: &CStr) {
+        unsafe { soft_keydown(value, name.as_ptr()) }
+    }
+
+    fn soft_keyup(&self, value: i32, name: &CStr) {
+        unsafe { soft_keyup(value, name.as_ptr()) }
+    }
+
+    fn soft_keypress(&self, value: i32, name: &CStr, duration_ms: i32) {
+        unsafe { soft_keypress(value, name.as_ptr(), duration_ms) }
+    }
+
+    fn check_keyisdown(&self, value: i32, name: &CStr) -> i32 {
+        unsafe { check_keyisdown(value, name.as_ptr()) }
+    }
+
+    fn show_table(&self) {
+        unsafe { showTable() }
+    }
+
+    fn soft_mask(&self, value: i32, name: &CStr, mask_value: i32) -> i32 {
+        unsafe { soft_mask(value, name.as_ptr(), mask_value) }
+    }
+
+    fn soft_ismask(&self, value: i32, name: &CStr) -> i32 {
+        unsafe { soft_ismask(value, name.as_ptr()) }
+    }
+
+    fn soft_string(&self, text: &CStr) -> i32 {
+        unsafe { soft_String(text.as_ptr()) }
+    }
+
+    fn miniui_enable(&self, mode: i32) -> i32 {
+        unsafe { ffi::kmAI_MiniUI_Enable(mode) }
+    }
+
+    fn miniui_user_lock(&self) -> i32 {
+        unsafe { ffi::kmAI_MiniUI_UserLock() }
+    }
+
+    fn miniui_user_unlock(&self) -> i32 {
+        unsafe { ffi::kmAI_MiniUI_UserUnLock() }
+    }
+
+    unsafe fn miniui_lcd_display(&self, img: *mut cv_Mat) -> i32 {
+        ffi::kmAI_MiniUI_LCDdisplay(img)
+    }
+
+    fn lcd_write(&self, frame: &LcdFrame) -> io::Result<()> {
+        // 与厂商示例相同，直接写入帧缓冲，不经过 OpenCV
+        std::fs::write(LCD_FRAMEBUFFER, frame.to_bytes())
+    }
+
+    unsafe fn yolo_load_model(&self, ctx: *mut rknn_context_t, model_path: &CStr) -> i32 {
+        // 头文件中的参数是 `char*`，厂商库不会修改路径字符串
+        ffi::kmAI_YOLO_Loadmodel(ctx, model_path.as_ptr() as *mut _)
+    }
+
+    unsafe fn yolo_interface_model(
+        &self,
+        ctx: *mut rknn_context_t,
+        img: *mut cv_Mat,
+        out: &mut object_detected_list,
+    ) -> i32 {
+        ffi::kmAI_YOLO_InterfaceModel(ctx, img, out)
+    }
+
+    unsafe fn yolo_draw_rectangle(
+        &self,
+        img: *mut cv_Mat,
+        x: i32,
+        y: i32,
+        w: i32,
+        h: i32,
+        color: u32,
+        thickness: i32,
+    ) {
+        ffi::kmAI_YOLO_DrawRectangle(img, x, y, w, h, color, thickness)
+    }
+
+    unsafe fn yolo_draw_text(
+        &self,
+        img: *mut cv_Mat,
+        text: &CStr,
+        x: i32,
+        y: i32,
+        color: u32,
+        font_size: i32,
+    ) {
+        ffi::kmAI_YOLO_DrawText(img, text.as_ptr(), x, y, color, font_size)
+    }
+
+    unsafe fn yolo_release(&self, ctx: *mut rknn_context_t) -> i32 {
+        ffi::kmAI_YOLO_Release(ctx)
+    }
+
+    fn yolo_init(&self, model_path: &CStr) -> *mut rknn_context_t {
+        unsafe { yolo_init_v_v(model_path.as_ptr()) }
+    }
+
+    fn yolo_detect(&self, image: &Image, out: &mut object_detected_list) -> i32 {
+        let mut buffer = image.to_c_buffer();
+        unsafe { yolo_detect_v_v(&mut buffer, out) }
+    }
+
+    unsafe fn yolo_destroy(&self, ctx: *mut rknn_context_t) {
+        yolo_destroy_v_v(ctx)
+    }
+
+    fn yolo_set_nms_thresh(&self, thresh: f32) {
+        unsafe { yolo_set_nms_thresh_v(thresh) }
+    }
+
+    fn yolo_set_box_thresh(&self, thresh: f32) {
+        unsafe { yolo_set_box_thresh_v(thresh) }
+    }
+
+    fn rknn_init(&self, context: &mut rknn_context, model_path: &CStr) -> i32 {
+        // size 为 0 时 model 是模型文件路径，运行时不会修改它
+        unsafe {
+            rknn::rknn_init(
+                context,
+                model_path.as_ptr() as *mut _,
+                0,
+                0,
+                std::ptr::null_mut(),
+            )
+        }
+    }
+
+    fn rknn_query_io_num(&self, context: rknn_context, io_num: &mut rknn_input_output_num) -> i32 {
+        unsafe {
+            rknn::rknn_query(
+                context,
+                rknn::_rknn_query_cmd_RKNN_QUERY_IN_OUT_NUM,
+                io_num as *mut rknn_input_output_num as *mut _,
+                std::mem::size_of::<rknn_input_output_num>() as u32,
+            )
+        }
+    }
+
+    unsafe fn rknn_inputs_set(&self, context: rknn_context, inputs: &mut [rknn_input]) -> i32 {
+        rknn::rknn_inputs_set(context, inputs.len() as u32, inputs.as_mut_ptr())
+    }
+
+    fn rknn_run(&self, context: rknn_context) -> i32 {
+        unsafe { rknn::rknn_run(context, std::ptr::null_mut()) }
+    }
+
+    fn rknn_wait(&self, context: rknn_context) -> i32 {
+        unsafe { rknn::rknn_wait(context, std::ptr::null_mut()) }
+    }
+
+    unsafe fn rknn_outputs_get(&self, context: rknn_context, outputs: &mut [rknn_output]) -> i32 {
+        rknn::rknn_outputs_get(
+            context,
+            outputs.len() as u32,
+            outputs.as_mut_ptr(),
+            std::ptr::null_mut(),
+        )
+    }
+
+    unsafe fn rknn_outputs_release(
+        &self,
+        context: rknn_context,
+        outputs: &mut [rknn_output],
+    ) -> i32 {
+        rknn::rknn_outputs_release(context, outputs.len() as u32, outputs.as_mut_ptr())
+    }
+
+    fn rknn_destroy(&self, context: rknn_context) -> i32 {
+        unsafe { rknn::rknn_destroy(context) }
+    }
+
+    fn reboot(&self) -> i32 {
+        unsafe { ffi::kmAI_reboot() }
+    }
+
+    fn capture_screen(&self) -> Option<Image> {
+        unsafe { take_c_buffer(capture_screen_v()) }
+    }
+
+    fn load_image(&self, path: &CStr) -> Option<Image> {
+        unsafe { take_c_buffer(load_image_v(path.as_ptr())) }
+    }
+
+    fn save_image(&self, path: &CStr, image: &Image) -> i32 {
+        let mut buffer = image.to_c_buffer();
+        unsafe { save_image_v(path.as_ptr(), &mut buffer) }
+    }
+
+    fn convert_image_format(&self, image: &Image, format: ImageFormat) -> Option<Image> {
+        let mut src_buffer = image.to_c_buffer();
+        let mut dst_buffer = ImageBufferT {
+            width: image.width,
+            height: image.height,
+            width_stride: image.width,
+            height_stride: image.height,
+            format: format.as_u32(),
+            virt_addr: std::ptr::null_mut(),
+            size: 0,
+            fd: -1,
+        };
+
+        let result = unsafe { convert_image_format(&mut src_buffer, &mut dst_buffer) };
+        if result != 0 {
+            return None;
+        }
+        Some(Image::from_c_buffer(&dst_buffer))
+    }
+
+    fn crop_image(&self, image: &Image, rect: ImageRect) -> Option<Image> {
+        let mut src_buffer = image.to_c_buffer();
+        let c_rect = ImageRectT {
+            left: rect.left,
+            top: rect.top,
+            right: rect.right,
+            bottom: rect.bottom,
+        };
+        unsafe { take_c_buffer(crop_image_v(&mut src_buffer, &c_rect)) }
+    }
+
+    fn resize_image(&self, image: &Image, width: i32, height: i32) -> Option<Image> {
+        let mut src_buffer = image.to_c_buffer();
+        unsafe { take_c_buffer(resize_image_v(&mut src_buffer, width, height)) }
+    }
+}
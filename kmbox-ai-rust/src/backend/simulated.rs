@@ -0,0 +1,966 @@
+//! 模拟后端
+//!
+//! 纯 Rust 实现的 KmboxAI 模拟设备，不依赖任何厂商库。
+//! 所有软件发出的键鼠报告都会被记录下来，物理键鼠输入可以通过
+//! `press_physical_*`/`move_physical` 等方法注入，便于在任意主机上测试上层逻辑。
+//!
+//! 与真实设备的约定：
+//! - `monitor_mouse_xy`/`monitor_mouse_wheel` 返回物理鼠标最近一次移动的值，读取后不清零，
+//!   与 kmboxAI.h 和厂商示例 `03-mouse_test` 的行为一致
+//! - 物理键鼠被屏蔽时依然可以被监控到，只是不会转发给主机
+
+use super::Backend;
+use crate::ffi::rknn::{rknn_context, rknn_input, rknn_input_output_num, rknn_output};
+use crate::ffi::{
+    cv_Mat, image_rect_t, object_detect_result, object_detected_list, rknn_context_t,
+};
+use crate::keyboard::Key;
+use crate::kmbox_ai::{MouseButton, MouseMask, MousePosition};
+use crate::lcd::LcdFrame;
+use crate::vision::{Image, ImageFormat, ImageRect};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ffi::CStr;
+use std::io;
+use std::sync::{Mutex, MutexGuard};
+
+/// 模拟设备记录的一条软件键鼠报告
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HidReport {
+    /// 鼠标相对移动
+    Move { x: i32, y: i32 },
+    /// 鼠标按键按下/释放
+    Button { button: MouseButton, down: bool },
+    /// 鼠标滚轮
+    Wheel(i32),
+    /// 鼠标综合报告，`buttons` 为按键位掩码
+    MouseAll {
+        buttons: i32,
+        x: i32,
+        y: i32,
+        wheel: i32,
+    },
+    /// 键盘按键按下
+    KeyDown(i32),
+    /// 键盘按键释放
+    KeyUp(i32),
+    /// 复位所有软件按键
+    KeyInit,
+    /// 字符串输入
+    Text(String),
+}
+
+/// 模拟 YOLO 检测返回的一个目标
+#[derive(Debug, Clone, Copy)]
+pub struct SimulatedDetection {
+    /// 目标在图像中的像素区域
+    pub rect: ImageRect,
+    /// 置信度
+    pub confidence: f32,
+    /// 类别ID
+    pub class_id: i32,
+}
+
+/// 模拟设备的内部状态快照
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedState {
+    /// 是否已调用 `init`
+    pub initialized: bool,
+    /// 是否已调用 `run`
+    pub running: bool,
+    /// 键鼠直通是否启用
+    pub enabled: bool,
+    /// 返回的版本号
+    pub version: i32,
+
+    /// 软件按下的鼠标按键
+    pub mouse_buttons: HashSet<MouseButton>,
+    /// 软件鼠标移动的累积量
+    pub cursor: MousePosition,
+    /// 软件滚轮的累积量
+    pub wheel: i32,
+    /// 软件按下的键盘按键（HID 值）
+    pub keys_down: BTreeSet<i32>,
+    /// 软件输入的所有字符串
+    pub typed_text: String,
+    /// 按时间顺序记录的软件键鼠报告
+    pub reports: Vec<HidReport>,
+
+    /// 物理鼠标按下的按键
+    pub physical_buttons: HashSet<MouseButton>,
+    /// 物理鼠标最近一次移动的值
+    pub physical_last_move: MousePosition,
+    /// 物理鼠标最近一次滚轮值
+    pub physical_last_wheel: i32,
+    /// 物理键盘按下的按键（HID 值）
+    pub physical_keys: BTreeSet<i16>,
+
+    /// 已屏蔽的物理鼠标输入
+    pub mouse_masks: HashSet<MouseMask>,
+    /// 已屏蔽的物理键盘按键
+    pub keyboard_masks: BTreeSet<i16>,
+    /// `soft_mask` 设置的按键掩码值
+    pub soft_masks: BTreeMap<i32, i32>,
+
+    /// MiniUI 模式
+    pub miniui_mode: i32,
+    /// MiniUI 是否被用户锁定
+    pub miniui_locked: bool,
+    /// LCD 上最后显示的一帧
+    pub lcd: Option<LcdFrame>,
+
+    /// `capture_screen` 返回的屏幕图像
+    pub screen: Option<Image>,
+    /// 通过 `save_image` 保存的图像，按路径索引
+    pub files: HashMap<String, Image>,
+    /// 可以加载的模型路径
+    pub models: BTreeSet<String>,
+    /// 按时间顺序记录的模型加载
+    pub loaded_models: Vec<String>,
+    /// 尚未销毁的 YOLO 检测上下文数量
+    pub yolo_contexts: usize,
+    /// `yolo_detect` 返回的目标
+    pub detections: Vec<SimulatedDetection>,
+    /// 最近一次设置的 YOLO 置信度阈值
+    pub yolo_box_thresh: f32,
+    /// 最近一次设置的 YOLO NMS 阈值
+    pub yolo_nms_thresh: f32,
+    /// 尚未销毁的 RKNN 上下文及其模型路径
+    pub rknn_contexts: BTreeMap<u64, String>,
+    /// 每个 RKNN 上下文最近一次设置的输入数据
+    pub rknn_inputs: BTreeMap<u64, Vec<Vec<u8>>>,
+
+    /// 注入的故障：设置后设备调用都返回该错误码
+    pub fault: Option<i32>,
+}
+
+/// 模拟设备后端
+#[derive(Debug, Default)]
+pub struct SimulatedBackend {
+    state: Mutex<SimulatedState>,
+}
+
+impl SimulatedBackend {
+    /// 创建新的模拟设备
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取内部状态的锁，即使之前有线程在持锁时 panic 也继续使用
+    fn lock(&self) -> MutexGuard<'_, SimulatedState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 获取当前状态快照
+    pub fn state(&self) -> SimulatedState {
+        self.lock().clone()
+    }
+
+    /// 获取已记录的软件键鼠报告
+    pub fn reports(&self) -> Vec<HidReport> {
+        self.lock().reports.clone()
+    }
+
+    /// 取出并清空已记录的软件键鼠报告
+    pub fn take_reports(&self) -> Vec<HidReport> {
+        std::mem::take(&mut self.lock().reports)
+    }
+
+    /// 设置 `version` 返回的版本号
+    pub fn set_version(&self, version: i32) {
+        self.lock().version = version;
+    }
+
+    /// 设置 `capture_screen` 返回的屏幕图像
+    pub fn set_screen(&self, image: Image) {
+        self.lock().screen = Some(image);
+    }
+
+    /// 模拟物理鼠标按键按下
+    pub fn press_physical_button(&self, button: MouseButton) {
+        self.lock().physical_buttons.insert(button);
+    }
+
+    /// 模拟物理鼠标按键释放
+    pub fn release_physical_button(&self, button: MouseButton) {
+        self.lock().physical_buttons.remove(&button);
+    }
+
+    /// 模拟物理鼠标移动，`monitor_mouse_xy` 之后一直返回这次的位移
+    pub fn move_physical(&self, dx: i32, dy: i32) {
+        self.lock().physical_last_move = MousePosition { x: dx, y: dy };
+    }
+
+    /// 模拟物理鼠标滚轮，`monitor_mouse_wheel` 之后一直返回这次的滚轮值
+    pub fn scroll_physical(&self, wheel: i32) {
+        self.lock().physical_last_wheel = wheel;
+    }
+
+    /// 模拟物理键盘按键按下
+    pub fn press_physical_key(&self, vkey: i16) {
+        self.lock().physical_keys.insert(vkey);
+    }
+
+    /// 模拟物理键盘按键释放
+    pub fn release_physical_key(&self, vkey: i16) {
+        self.lock().physical_keys.remove(&vkey);
+    }
+
+    /// 登记一个可以加载的模型路径
+    pub fn add_model(&self, path: &str) {
+        self.lock().models.insert(path.to_string());
+    }
+
+    /// 设置 `yolo_detect` 返回的目标
+    pub fn set_detections(&self, detections: Vec<SimulatedDetection>) {
+        self.lock().detections = detections;
+    }
+
+    /// 模拟 USB 链路或固件故障
+    ///
+    /// 之后的设备调用（包括 `version`）都返回 `code`，直到调用 [`clear_fault`](Self::clear_fault)。
+    /// 设备像重新上电一样丢失初始化、直通、屏蔽和已加载模型等状态。
+    pub fn inject_fault(&self, code: i32) {
+        let mut state = self.lock();
+        state.fault = Some(code);
+        state.initialized = false;
+        state.running = false;
+        state.enabled = false;
+        state.mouse_buttons.clear();
+        state.keys_down.clear();
+        state.mouse_masks.clear();
+        state.keyboard_masks.clear();
+        state.soft_masks.clear();
+        state.loaded_models.clear();
+        state.rknn_contexts.clear();
+        state.rknn_inputs.clear();
+    }
+
+    /// 清除注入的故障，设备恢复响应，但需要重新初始化
+    pub fn clear_fault(&self) {
+        self.lock().fault = None;
+    }
+
+    /// 没有注入故障时在设备状态上执行 `f`，否则返回故障错误码
+    fn device(&self, f: impl FnOnce(&mut SimulatedState) -> i32) -> i32 {
+        let mut state = self.lock();
+        match state.fault {
+            Some(code) => code,
+            None => f(&mut state),
+        }
+    }
+}
+
+impl Backend for SimulatedBackend {
+    fn init(&self) -> i32 {
+        self.device(|state| {
+            state.initialized = true;
+            0
+        })
+    }
+
+    fn run(&self) -> i32 {
+        self.device(|state| {
+            state.running = true;
+            0
+        })
+    }
+
+    fn km_enable(&self, enable: bool) -> i32 {
+        self.device(|state| {
+            state.enabled = enable;
+            0
+        })
+    }
+
+    fn version(&self) -> i32 {
+        self.device(|state| state.version)
+    }
+
+    fn mouse_move(&self, x: i16, y: i16) -> i32 {
+        let (x, y) = (i32::from(x), i32::from(y));
+        self.device(|state| {
+            state.cursor.x += x;
+            state.cursor.y += y;
+            state.reports.push(HidReport::Move { x, y });
+            0
+        })
+    }
+
+    fn mouse_button(&self, button: MouseButton, down: bool) -> i32 {
+        self.device(|state| {
+            if down {
+                state.mouse_buttons.insert(button);
+            } else {
+                state.mouse_buttons.remove(&button);
+            }
+            state.reports.push(HidReport::Button { button, down });
+            0
+        })
+    }
+
+    fn mouse_wheel(&self, wheel: i32) -> i32 {
+        self.device(|state| {
+            state.wheel += wheel;
+            state.reports.push(HidReport::Wheel(wheel));
+            0
+        })
+    }
+
+    fn mouse_all(&self, buttons: i32, x: i32, y: i32, wheel: i32) -> i32 {
+        self.device(|state| {
+            for button in MouseButton::ALL {
+                if buttons & button.mask_bit() != 0 {
+                    state.mouse_buttons.insert(button);
+                } else {
+                    state.mouse_buttons.remove(&button);
+                }
+            }
+            state.cursor.x += x;
+            state.cursor.y += y;
+            state.wheel += wheel;
+            state.reports.push(HidReport::MouseAll {
+                buttons,
+                x,
+                y,
+                wheel,
+            });
+            0
+        })
+    }
+
+    fn key_down(&self, vkey: i32) -> i32 {
+        self.device(|state| {
+            state.keys_down.insert(vkey);
+            state.reports.push(HidReport::KeyDown(vkey));
+            0
+        })
+    }
+
+    fn key_up(&self, vkey: i32) -> i32 {
+        self.device(|state| {
+            state.keys_down.remove(&vkey);
+            state.reports.push(HidReport::KeyUp(vkey));
+            0
+        })
+    }
+
+    fn key_init(&self) -> i32 {
+        self.device(|state| {
+            state.keys_down.clear();
+            state.reports.push(HidReport::KeyInit);
+            0
+        })
+    }
+
+    fn monitor_mouse_button(&self, button: MouseButton) -> i32 {
+        self.device(|state| state.physical_buttons.contains(&button) as i32)
+    }
+
+    fn monitor_mouse_xy(&self, x: &mut i32, y: &mut i32) -> i32 {
+        self.device(|state| {
+            *x = state.physical_last_move.x;
+            *y = state.physical_last_move.y;
+            0
+        })
+    }
+
+    fn monitor_mouse_wheel(&self, wheel: &mut i32) -> i32 {
+        self.device(|state| {
+            *wheel = state.physical_last_wheel;
+            0
+        })
+    }
+
+    fn monitor_keyboard(&self, vkey: i16) -> i32 {
+        self.device(|state| state.physical_keys.contains(&vkey) as i32)
+    }
+
+    fn mask_mouse(&self, mask: MouseMask, enable: bool) -> i32 {
+        self.device(|state| {
+            if enable {
+                state.mouse_masks.insert(mask);
+            } else {
+                state.mouse_masks.remove(&mask);
+            }
+            0
+        })
+    }
+
+    fn mask_keyboard(&self, vkey: i16) -> i32 {
+        self.device(|state| {
+            state.keyboard_masks.insert(vkey);
+            0
+        })
+    }
+
+    fn unmask_keyboard(&self, vkey: i16) -> i32 {
+        self.device(|state| {
+            state.keyboard_masks.remove(&vkey);
+            0
+        })
+    }
+
+    fn unmask_all(&self) -> i32 {
+        self.device(|state| {
+            state.mouse_masks.clear();
+            state.keyboard_masks.clear();
+            0
+        })
+    }
+
+    fn key_hex_by_name(&self, name: &CStr) -> i32 {
+        // 模拟设备使用 Key 的按键表，规则与 `Key::from_str` 相同
+        name.to_str()
+            .ok()
+            .and_then(|name| name.parse::<Key>().ok())
+            .map_or(-1, |key| key.hex_value() as i32)
+    }
+
+    fn soft_keydown(&self, value: i32, _name: &CStr) {
+        self.key_down(value);
+    }
+
+    fn soft_keyup(&self, value: i32, _name: &CStr) {
+        self.key_up(value);
+    }
+
+    fn soft_keypress(&self, value: i32, _name: &CStr, _duration_ms: i32) {
+        self.key_down(value);
+        self.key_up(value);
+    }
+
+    fn check_keyisdown(&self, value: i32, _name: &CStr) -> i32 {
+        self.device(|state| {
+            let physical = i16::try_from(value)
+                .map(|vkey| state.physical_keys.contains(&vkey))
+                .unwrap_or(false);
+            (state.keys_down.contains(&value) || physical) as i32
+        })
+    }
+
+    fn show_table(&self) {}
+
+    fn soft_mask(&self, value: i32, _name: &CStr, mask_value: i32) -> i32 {
+        self.device(|state| {
+            state.soft_masks.insert(value, mask_value);
+            0
+        })
+    }
+
+    fn soft_ismask(&self, value: i32, _name: &CStr) -> i32 {
+        self.device(|state| {
+            state
+                .soft_masks
+                .get(&value)
+                .map_or(0, |&mask_value| (mask_value != 0) as i32)
+        })
+    }
+
+    fn soft_string(&self, text: &CStr) -> i32 {
+        let text = text.to_string_lossy().into_owned();
+        self.device(|state| {
+            state.typed_text.push_str(&text);
+            state.reports.push(HidReport::Text(text));
+            0
+        })
+    }
+
+    fn miniui_enable(&self, mode: i32) -> i32 {
+        self.device(|state| {
+            state.miniui_mode = mode;
+            0
+        })
+    }
+
+    fn miniui_user_lock(&self) -> i32 {
+        self.device(|state| {
+            state.miniui_locked = true;
+            0
+        })
+    }
+
+    fn miniui_user_unlock(&self) -> i32 {
+        self.device(|state| {
+            state.miniui_locked = false;
+            0
+        })
+    }
+
+    unsafe fn miniui_lcd_display(&self, img: *mut cv_Mat) -> i32 {
+        if img.is_null() {
+            -1
+        } else {
+            0
+        }
+    }
+
+    fn lcd_write(&self, frame: &LcdFrame) -> io::Result<()> {
+        let mut state = self.lock();
+        if let Some(code) = state.fault {
+            return Err(io::Error::other(format!("模拟设备故障，错误码: {}", code)));
+        }
+        state.lcd = Some(frame.clone());
+        Ok(())
+    }
+
+    unsafe fn yolo_load_model(&self, _ctx: *mut rknn_context_t, model_path: &CStr) -> i32 {
+        // 模拟设备没有NPU，只能“加载”通过 `add_model` 登记的模型，推理总是失败
+        let path = model_path.to_string_lossy().into_owned();
+        self.device(|state| {
+            if state.models.contains(&path) {
+                state.loaded_models.push(path);
+                0
+            } else {
+                -1
+            }
+        })
+    }
+
+    unsafe fn yolo_interface_model(
+        &self,
+        _ctx: *mut rknn_context_t,
+        _img: *mut cv_Mat,
+        _out: &mut object_detected_list,
+    ) -> i32 {
+        -1
+    }
+
+    unsafe fn yolo_draw_rectangle(
+        &self,
+        _img: *mut cv_Mat,
+        _x: i32,
+        _y: i32,
+        _w: i32,
+        _h: i32,
+        _color: u32,
+        _thickness: i32,
+    ) {
+    }
+
+    unsafe fn yolo_draw_text(
+        &self,
+        _img: *mut cv_Mat,
+        _text: &CStr,
+        _x: i32,
+        _y: i32,
+        _color: u32,
+        _font_size: i32,
+    ) {
+    }
+
+    unsafe fn yolo_release(&self, _ctx: *mut rknn_context_t) -> i32 {
+        0
+    }
+
+    fn yolo_init(&self, model_path: &CStr) -> *mut rknn_context_t {
+        let path = model_path.to_string_lossy().into_owned();
+        let result = self.device(|state| {
+            if state.models.contains(&path) {
+                state.loaded_models.push(path);
+                state.yolo_contexts += 1;
+                0
+            } else {
+                -1
+            }
+        });
+        if result != 0 {
+            return std::ptr::null_mut();
+        }
+        // rknn_context_t 只包含整数、浮点数和指针，全零是合法的初始状态
+        Box::into_raw(Box::new(unsafe { std::mem::zeroed() }))
+    }
+
+    fn yolo_detect(&self, _image: &Image, out: &mut object_detected_list) -> i32 {
+        // 模拟设备没有NPU，直接返回 `set_detections` 设置的目标
+        self.device(|state| {
+            if state.yolo_contexts == 0 {
+                return -1;
+            }
+            let count = state.detections.len().min(out.results.len());
+            for (result, detection) in out.results.iter_mut().zip(&state.detections) {
+                *result = object_detect_result {
+                    box_: image_rect_t {
+                        left: detection.rect.left,
+                        top: detection.rect.top,
+                        right: detection.rect.right,
+                        bottom: detection.rect.bottom,
+                    },
+                    prop: detection.confidence,
+                    cls_id: detection.class_id,
+                };
+            }
+            out.count = count as i32;
+            0
+        })
+    }
+
+    unsafe fn yolo_destroy(&self, ctx: *mut rknn_context_t) {
+        drop(Box::from_raw(ctx));
+        let mut state = self.lock();
+        state.yolo_contexts = state.yolo_contexts.saturating_sub(1);
+    }
+
+    fn yolo_set_nms_thresh(&self, thresh: f32) {
+        self.lock().yolo_nms_thresh = thresh;
+    }
+
+    fn yolo_set_box_thresh(&self, thresh: f32) {
+        self.lock().yolo_box_thresh = thresh;
+    }
+
+    // 模拟的 RKNN 模型只有一个输入和一个输出，输出原样返回输入数据
+
+    fn rknn_init(&self, context: &mut rknn_context, model_path: &CStr) -> i32 {
+        let path = model_path.to_string_lossy().into_owned();
+        self.device(|state| {
+            if !state.models.contains(&path) {
+                return -6; // RKNN_ERR_MODEL_INVALID
+            }
+            let id = state.rknn_contexts.keys().last().map_or(1, |id| id + 1);
+            state.rknn_contexts.insert(id, path.clone());
+            state.loaded_models.push(path);
+            *context = id;
+            0
+        })
+    }
+
+    fn rknn_query_io_num(&self, context: rknn_context, io_num: &mut rknn_input_output_num) -> i32 {
+        self.device(|state| {
+            if !state.rknn_contexts.contains_key(&context) {
+                return -7; // RKNN_ERR_CTX_INVALID
+            }
+            io_num.n_input = 1;
+            io_num.n_output = 1;
+            0
+        })
+    }
+
+    unsafe fn rknn_inputs_set(&self, context: rknn_context, inputs: &mut [rknn_input]) -> i32 {
+        self.device(|state| {
+            if !state.rknn_contexts.contains_key(&context) {
+                return -7; // RKNN_ERR_CTX_INVALID
+            }
+            if inputs.len() != 1 {
+                return -8; // RKNN_ERR_INPUT_INVALID
+            }
+            let data = inputs
+                .iter()
+                .map(|input| {
+                    std::slice::from_raw_parts(input.buf as *const u8, input.size as usize).to_vec()
+                })
+                .collect();
+            state.rknn_inputs.insert(context, data);
+            0
+        })
+    }
+
+    fn rknn_run(&self, context: rknn_context) -> i32 {
+        self.device(|state| {
+            if !state.rknn_contexts.contains_key(&context) {
+                -7 // RKNN_ERR_CTX_INVALID
+            } else if !state.rknn_inputs.contains_key(&context) {
+                -8 // RKNN_ERR_INPUT_INVALID
+            } else {
+                0
+            }
+        })
+    }
+
+    fn rknn_wait(&self, context: rknn_context) -> i32 {
+        self.rknn_run(context)
+    }
+
+    unsafe fn rknn_outputs_get(&self, context: rknn_context, outputs: &mut [rknn_output]) -> i32 {
+        self.device(|state| {
+            let Some(inputs) = state.rknn_inputs.get(&context) else {
+                return -7; // RKNN_ERR_CTX_INVALID
+            };
+            if outputs.len() != inputs.len() {
+                return -9; // RKNN_ERR_OUTPUT_INVALID
+            }
+            for (output, data) in outputs.iter_mut().zip(inputs) {
+                if output.is_prealloc != 0 {
+                    let len = data.len().min(output.size as usize);
+                    std::ptr::copy_nonoverlapping(data.as_ptr(), output.buf as *mut u8, len);
+                } else {
+                    output.size = data.len() as u32;
+                    output.buf = Box::into_raw(data.clone().into_boxed_slice()) as *mut _;
+                }
+            }
+            0
+        })
+    }
+
+    unsafe fn rknn_outputs_release(
+        &self,
+        _context: rknn_context,
+        outputs: &mut [rknn_output],
+    ) -> i32 {
+        for output in outputs.iter_mut() {
+            if output.is_prealloc == 0 && !output.buf.is_null() {
+                let data =
+                    std::ptr::slice_from_raw_parts_mut(output.buf as *mut u8, output.size as usize);
+                drop(Box::from_raw(data));
+                output.buf = std::ptr::null_mut();
+            }
+        }
+        0
+    }
+
+    fn rknn_destroy(&self, context: rknn_context) -> i32 {
+        let mut state = self.lock();
+        state.rknn_inputs.remove(&context);
+        match state.rknn_contexts.remove(&context) {
+            Some(_) => 0,
+            None => -7, // RKNN_ERR_CTX_INVALID
+        }
+    }
+
+    fn reboot(&self) -> i32 {
+        *self.lock() = SimulatedState::default();
+        0
+    }
+
+    fn capture_screen(&self) -> Option<Image> {
+        self.lock().screen.clone()
+    }
+
+    fn load_image(&self, path: &CStr) -> Option<Image> {
+        self.lock()
+            .files
+            .get(path.to_string_lossy().as_ref())
+            .cloned()
+    }
+
+    fn save_image(&self, path: &CStr, image: &Image) -> i32 {
+        self.lock()
+            .files
+            .insert(path.to_string_lossy().into_owned(), image.clone());
+        0
+    }
+
+    fn convert_image_format(&self, image: &Image, format: ImageFormat) -> Option<Image> {
+        let src_bpp = packed_pixel_size(image.format)?;
+        let dst_bpp = packed_pixel_size(format)?;
+        let mut dst = Image::new(image.width, image.height, format);
+        for (src, dst) in image
+            .data
+            .chunks_exact(src_bpp)
+            .zip(dst.data.chunks_exact_mut(dst_bpp))
+        {
+            let (r, g, b, a) = unpack_pixel(src, image.format);
+            pack_pixel(dst, format, r, g, b, a);
+        }
+        Some(dst)
+    }
+
+    fn crop_image(&self, image: &Image, rect: ImageRect) -> Option<Image> {
+        let bpp = packed_pixel_size(image.format)?;
+        if rect.left < 0
+            || rect.top < 0
+            || rect.width() <= 0
+            || rect.height() <= 0
+            || rect.right > image.width
+            || rect.bottom > image.height
+        {
+            return None;
+        }
+
+        let mut dst = Image::new(rect.width(), rect.height(), image.format);
+        let row_len = rect.width() as usize * bpp;
+        for row in 0..rect.height() as usize {
+            let src_start =
+                ((rect.top as usize + row) * image.width as usize + rect.left as usize) * bpp;
+            let dst_start = row * row_len;
+            dst.data[dst_start..dst_start + row_len]
+                .copy_from_slice(image.data.get(src_start..src_start + row_len)?);
+        }
+        Some(dst)
+    }
+
+    fn resize_image(&self, image: &Image, width: i32, height: i32) -> Option<Image> {
+        let bpp = packed_pixel_size(image.format)?;
+        if width <= 0 || height <= 0 || image.width <= 0 || image.height <= 0 {
+            return None;
+        }
+
+        // 最近邻缩放
+        let mut dst = Image::new(width, height, image.format);
+        for y in 0..height as usize {
+            let src_y = y * image.height as usize / height as usize;
+            for x in 0..width as usize {
+                let src_x = x * image.width as usize / width as usize;
+                let src_start = (src_y * image.width as usize + src_x) * bpp;
+                let dst_start = (y * width as usize + x) * bpp;
+                dst.data[dst_start..dst_start + bpp]
+                    .copy_from_slice(image.data.get(src_start..src_start + bpp)?);
+            }
+        }
+        Some(dst)
+    }
+}
+
+/// 打包格式每个像素占用的字节数，YUV 等平面格式返回 `None`
+fn packed_pixel_size(format: ImageFormat) -> Option<usize> {
+    match format {
+        ImageFormat::Gray8 => Some(1),
+        ImageFormat::Rgb888 | ImageFormat::Bgr888 => Some(3),
+        ImageFormat::Rgba8888 => Some(4),
+        ImageFormat::Yuv420spNv21 | ImageFormat::Yuv420spNv12 => None,
+    }
+}
+
+/// 读取一个像素的 RGBA 分量
+fn unpack_pixel(pixel: &[u8], format: ImageFormat) -> (u8, u8, u8, u8) {
+    match format {
+        ImageFormat::Gray8 => (pixel[0], pixel[0], pixel[0], 255),
+        ImageFormat::Rgb888 => (pixel[0], pixel[1], pixel[2], 255),
+        ImageFormat::Bgr888 => (pixel[2], pixel[1], pixel[0], 255),
+        ImageFormat::Rgba8888 => (pixel[0], pixel[1], pixel[2], pixel[3]),
+        ImageFormat::Yuv420spNv21 | ImageFormat::Yuv420spNv12 => unreachable!(),
+    }
+}
+
+/// 按目标格式写入一个像素
+fn pack_pixel(pixel: &mut [u8], format: ImageFormat, r: u8, g: u8, b: u8, a: u8) {
+    match format {
+        ImageFormat::Gray8 => {
+            pixel[0] = ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+        }
+        ImageFormat::Rgb888 => pixel.copy_from_slice(&[r, g, b]),
+        ImageFormat::Bgr888 => pixel.copy_from_slice(&[b, g, r]),
+        ImageFormat::Rgba8888 => pixel.copy_from_slice(&[r, g, b, a]),
+        ImageFormat::Yuv420spNv21 | ImageFormat::Yuv420spNv12 => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_software_reports_are_recorded() {
+        let sim = SimulatedBackend::new();
+        sim.mouse_move(10, -5);
+        sim.mouse_move(3, 2);
+        sim.mouse_button(MouseButton::Left, true);
+        sim.mouse_wheel(-1);
+        sim.key_down(4);
+
+        let state = sim.state();
+        assert_eq!((state.cursor.x, state.cursor.y), (13, -3));
+        assert!(state.mouse_buttons.contains(&MouseButton::Left));
+        assert_eq!(state.wheel, -1);
+        assert!(state.keys_down.contains(&4));
+        assert_eq!(state.reports.len(), 5);
+        assert_eq!(state.reports[0], HidReport::Move { x: 10, y: -5 });
+
+        sim.key_init();
+        assert!(sim.state().keys_down.is_empty());
+    }
+
+    #[test]
+    fn test_physical_input_is_monitored() {
+        let sim = SimulatedBackend::new();
+        sim.press_physical_button(MouseButton::Side1);
+        sim.press_physical_key(41);
+        sim.move_physical(4, 5);
+        sim.move_physical(1, 1);
+        sim.scroll_physical(2);
+
+        assert_eq!(sim.monitor_mouse_button(MouseButton::Side1), 1);
+        assert_eq!(sim.monitor_mouse_button(MouseButton::Left), 0);
+        assert_eq!(sim.monitor_keyboard(41), 1);
+
+        let (mut x, mut y, mut wheel) = (0, 0, 0);
+        sim.monitor_mouse_xy(&mut x, &mut y);
+        sim.monitor_mouse_wheel(&mut wheel);
+        assert_eq!((x, y, wheel), (1, 1, 2));
+
+        // 与真实设备相同，读取后依然返回最近一次移动的值
+        for _ in 0..3 {
+            sim.monitor_mouse_xy(&mut x, &mut y);
+            sim.monitor_mouse_wheel(&mut wheel);
+            assert_eq!((x, y, wheel), (1, 1, 2));
+        }
+    }
+
+    #[test]
+    fn test_masks() {
+        let sim = SimulatedBackend::new();
+        sim.mask_mouse(MouseMask::Left, true);
+        sim.mask_mouse(MouseMask::Wheel, true);
+        sim.mask_mouse(MouseMask::Wheel, false);
+        sim.mask_keyboard(4);
+
+        let state = sim.state();
+        assert!(state.mouse_masks.contains(&MouseMask::Left));
+        assert!(!state.mouse_masks.contains(&MouseMask::Wheel));
+        assert!(state.keyboard_masks.contains(&4));
+
+        sim.unmask_all();
+        let state = sim.state();
+        assert!(state.mouse_masks.is_empty());
+        assert!(state.keyboard_masks.is_empty());
+    }
+
+    #[test]
+    fn test_injected_fault() {
+        let sim = SimulatedBackend::new();
+        sim.set_version(5);
+        sim.init();
+        sim.km_enable(true);
+        sim.mask_keyboard(4);
+
+        sim.press_physical_key(4);
+        sim.move_physical(2, 3);
+
+        sim.inject_fault(-3);
+        assert_eq!(sim.version(), -3);
+        assert_eq!(sim.mouse_move(1, 1), -3);
+        assert_eq!(sim.init(), -3);
+        let name = c"A";
+        let (mut x, mut y) = (0, 0);
+        assert_eq!(sim.monitor_mouse_xy(&mut x, &mut y), -3);
+        assert_eq!((x, y), (0, 0));
+        assert_eq!(sim.monitor_mouse_button(MouseButton::Left), -3);
+        assert_eq!(sim.monitor_keyboard(4), -3);
+        assert_eq!(sim.check_keyisdown(4, name), -3);
+        assert_eq!(sim.soft_mask(4, name, 1), -3);
+        assert_eq!(sim.soft_ismask(4, name), -3);
+        assert_eq!(sim.soft_string(c"abc"), -3);
+        sim.soft_keydown(4, name);
+        let state = sim.state();
+        assert!(!state.initialized && !state.enabled);
+        assert!(state.keyboard_masks.is_empty());
+        assert!(state.reports.is_empty());
+
+        sim.clear_fault();
+        assert_eq!(sim.version(), 5);
+        assert_eq!(sim.init(), 0);
+        assert!(sim.state().initialized);
+    }
+
+    #[test]
+    fn test_image_operations() {
+        let sim = SimulatedBackend::new();
+        let mut image = Image::new(4, 2, ImageFormat::Rgb888);
+        for (i, pixel) in image.data.chunks_exact_mut(3).enumerate() {
+            pixel.copy_from_slice(&[i as u8, 0, 255]);
+        }
+
+        let cropped = sim.crop_image(&image, ImageRect::new(1, 0, 3, 2)).unwrap();
+        assert_eq!(cropped.size(), (2, 2));
+        assert_eq!(&cropped.data[..3], &[1, 0, 255]);
+
+        let bgr = sim
+            .convert_image_format(&image, ImageFormat::Bgr888)
+            .unwrap();
+        assert_eq!(&bgr.data[..3], &[255, 0, 0]);
+
+        let resized = sim.resize_image(&image, 2, 1).unwrap();
+        assert_eq!(resized.data, vec![0, 0, 255, 2, 0, 255]);
+
+        assert!(sim.crop_image(&image, ImageRect::new(0, 0, 5, 2)).is_none());
+    }
+}
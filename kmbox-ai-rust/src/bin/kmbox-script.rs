@@ -0,0 +1,46 @@
+//! 在盒子上执行宏脚本
+//!
+//! 用法：`kmbox-script [--check] <脚本文件>`
+//!
+//! `--check` 只检查脚本语法，不连接设备。
+//! 执行过程中按 Ctrl+C 或收到 SIGTERM 时，会释放脚本按下的所有按键后退出。
+
+use kmbox_ai_rust::script::{Executor, Script};
+use kmbox_ai_rust::{failsafe, Keyboard, KmboxAI, KmboxResult};
+use std::process::ExitCode;
+
+fn run(path: &str, check_only: bool) -> KmboxResult<()> {
+    let script = Script::load(path)?;
+    if check_only {
+        println!("{}: 语法正确，共 {} 条指令", path, script.len());
+        return Ok(());
+    }
+
+    failsafe::install_panic_hook();
+    failsafe::install_signal_handlers()?;
+
+    let mut kmbox = KmboxAI::new()?;
+    kmbox.init()?;
+    let keyboard = Keyboard::new()?;
+    Executor::new(&kmbox, &keyboard).run(&script)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (check_only, path) = match args.as_slice() {
+        [path] if !path.starts_with('-') => (false, path),
+        [flag, path] if flag == "--check" => (true, path),
+        _ => {
+            eprintln!("用法: kmbox-script [--check] <脚本文件>");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(path, check_only) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,497 @@
+//! 虚拟绝对光标模块
+//!
+//! 盒子只能发送和读取相对位移，本模块把软件发送的位移和物理鼠标的位移累加为主机上光标的估计位置，
+//! 并在相对接口之上提供移动到屏幕坐标的 [`VirtualCursor::move_to`]。
+//!
+//! - [`AccelerationCurve`]：主机指针加速的补偿曲线，描述单次报告的位移（计数）与光标移动像素的关系
+//! - [`CursorModel`]：纯计算的光标模型，负责累加位移、限制在屏幕范围内以及规划移动
+//! - [`VirtualCursor`]：在后端上执行移动并跟踪物理位移
+//!
+//! 估计位置会随加速误差和丢失的物理位移逐渐偏离，可以用 [`VirtualCursor::home`]
+//! 把光标推到屏幕角落重新校准。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::cursor::{Corner, CursorModel, VirtualCursor};
+//! use kmbox_ai_rust::kmbox_ai::KmboxAI;
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let kmbox = KmboxAI::new()?;
+//! let cursor = VirtualCursor::new(kmbox.backend().clone(), CursorModel::new(1920, 1080)?);
+//! cursor.home(Corner::TopLeft)?;
+//! cursor.move_to(960, 540)?;
+//! println!("{:?}", cursor.position());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::{check_result, KmboxError, KmboxResult, Operation};
+use crate::kmbox_ai::MousePosition;
+use crate::monitor::{EventKind, InputEvent, LatestValue};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// 规划一次移动时最多生成的报告数量
+const MAX_PLANNED_REPORTS: usize = 100_000;
+
+/// 指针加速补偿曲线
+///
+/// 由若干 `(计数, 像素)` 点组成的分段线性函数，隐含起点 `(0, 0)`，
+/// 超出最后一个点时按最后一段的斜率外推。曲线作用于单次报告位移向量的长度。
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccelerationCurve {
+    points: Vec<(f64, f64)>,
+}
+
+impl Default for AccelerationCurve {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl AccelerationCurve {
+    /// 无加速，1 个计数移动 1 像素
+    pub fn identity() -> Self {
+        Self {
+            points: vec![(1.0, 1.0)],
+        }
+    }
+
+    /// 固定倍率
+    pub fn linear(gain: f64) -> KmboxResult<Self> {
+        Self::from_points(vec![(1.0, gain)])
+    }
+
+    /// 使用 `(计数, 像素)` 点创建曲线，两个坐标都必须严格递增且为正数
+    pub fn from_points(points: Vec<(f64, f64)>) -> KmboxResult<Self> {
+        if points.is_empty() {
+            return Err(KmboxError::ParameterError(
+                "加速曲线至少需要一个点".to_string(),
+            ));
+        }
+        let mut previous = (0.0, 0.0);
+        for &(counts, pixels) in &points {
+            if !counts.is_finite()
+                || !pixels.is_finite()
+                || counts <= previous.0
+                || pixels <= previous.1
+            {
+                return Err(KmboxError::ParameterError(format!(
+                    "加速曲线的点必须严格递增: ({}, {})",
+                    counts, pixels
+                )));
+            }
+            previous = (counts, pixels);
+        }
+        Ok(Self { points })
+    }
+
+    /// 在分段线性函数上插值，`forward` 为 `false` 时交换两个坐标（求反函数）
+    fn interpolate(&self, value: f64, forward: bool) -> f64 {
+        let pick = |&(counts, pixels): &(f64, f64)| {
+            if forward {
+                (counts, pixels)
+            } else {
+                (pixels, counts)
+            }
+        };
+        let mut previous = (0.0, 0.0);
+        for (index, point) in self.points.iter().enumerate() {
+            let (x, y) = pick(point);
+            if value <= x || index == self.points.len() - 1 {
+                return previous.1 + (value - previous.0) * (y - previous.1) / (x - previous.0);
+            }
+            previous = (x, y);
+        }
+        unreachable!("曲线至少有一个点")
+    }
+
+    /// 单次报告位移长度为 `counts` 时光标移动的像素
+    pub fn apply(&self, counts: f64) -> f64 {
+        self.interpolate(counts, true)
+    }
+
+    /// 光标需要移动 `pixels` 像素时单次报告的位移长度
+    pub fn invert(&self, pixels: f64) -> f64 {
+        self.interpolate(pixels, false)
+    }
+}
+
+/// 屏幕角落
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// 光标模型
+#[derive(Debug, Clone, PartialEq)]
+pub struct CursorModel {
+    width: u32,
+    height: u32,
+    x: f64,
+    y: f64,
+    curve: AccelerationCurve,
+}
+
+impl CursorModel {
+    /// 创建指定分辨率的光标模型，初始位置为屏幕中心
+    pub fn new(width: u32, height: u32) -> KmboxResult<Self> {
+        if width == 0 || height == 0 {
+            return Err(KmboxError::ParameterError(format!(
+                "无效的屏幕尺寸: {}x{}",
+                width, height
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            x: (width / 2) as f64,
+            y: (height / 2) as f64,
+            curve: AccelerationCurve::identity(),
+        })
+    }
+
+    /// 设置加速补偿曲线
+    pub fn with_curve(mut self, curve: AccelerationCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// 屏幕尺寸
+    pub fn bounds(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// 估计的光标位置（像素）
+    pub fn position(&self) -> (i32, i32) {
+        (self.x.round() as i32, self.y.round() as i32)
+    }
+
+    /// 设置光标位置，超出屏幕的坐标被限制在屏幕内
+    pub fn set_position(&mut self, x: f64, y: f64) {
+        self.x = x.clamp(0.0, (self.width - 1) as f64);
+        self.y = y.clamp(0.0, (self.height - 1) as f64);
+    }
+
+    /// 角落的坐标
+    pub fn corner(&self, corner: Corner) -> (i32, i32) {
+        let right = self.width as i32 - 1;
+        let bottom = self.height as i32 - 1;
+        match corner {
+            Corner::TopLeft => (0, 0),
+            Corner::TopRight => (right, 0),
+            Corner::BottomLeft => (0, bottom),
+            Corner::BottomRight => (right, bottom),
+        }
+    }
+
+    /// 累加一次报告的位移（计数）
+    pub fn apply_counts(&mut self, dx: i32, dy: i32) {
+        let length = (dx as f64).hypot(dy as f64);
+        if length == 0.0 {
+            return;
+        }
+        let scale = self.curve.apply(length) / length;
+        self.set_position(self.x + dx as f64 * scale, self.y + dy as f64 * scale);
+    }
+
+    /// 规划移动到 `(x, y)` 的报告序列，每次报告在每个方向上不超过 `max_step`
+    ///
+    /// 受计数取整限制，最终位置与目标可能相差不到一个计数对应的像素。
+    pub fn plan_move_to(&self, x: i32, y: i32, max_step: i16) -> Vec<(i16, i16)> {
+        let mut model = self.clone();
+        model.set_position(x as f64, y as f64);
+        let (target_x, target_y) = (model.x, model.y);
+        model.x = self.x;
+        model.y = self.y;
+
+        let max_pixels = self.curve.apply(max_step as f64);
+        let mut reports = Vec::new();
+        while reports.len() < MAX_PLANNED_REPORTS {
+            let (px, py) = (target_x - model.x, target_y - model.y);
+            let distance = px.hypot(py);
+            if distance < 0.5 {
+                break;
+            }
+            let counts = self.curve.invert(distance.min(max_pixels));
+            let limit = max_step as f64;
+            let cx = (px / distance * counts).round().clamp(-limit, limit);
+            let cy = (py / distance * counts).round().clamp(-limit, limit);
+            if cx == 0.0 && cy == 0.0 {
+                break;
+            }
+
+            let mut next = model.clone();
+            next.apply_counts(cx as i32, cy as i32);
+            if (target_x - next.x).hypot(target_y - next.y) >= distance {
+                // 取整后的报告不能让光标更接近目标
+                break;
+            }
+            model = next;
+            reports.push((cx as i16, cy as i16));
+        }
+        reports
+    }
+
+    /// 从任意位置推到 `corner` 所需的报告数量和每次报告的位移
+    fn plan_home(&self, corner: Corner, max_step: i16) -> (usize, (i16, i16)) {
+        let step = match corner {
+            Corner::TopLeft => (-max_step, -max_step),
+            Corner::TopRight => (max_step, -max_step),
+            Corner::BottomLeft => (-max_step, max_step),
+            Corner::BottomRight => (max_step, max_step),
+        };
+        // 从对角开始模拟，直到到达目标角落
+        let mut model = self.clone();
+        let opposite = model.corner(match corner {
+            Corner::TopLeft => Corner::BottomRight,
+            Corner::TopRight => Corner::BottomLeft,
+            Corner::BottomLeft => Corner::TopRight,
+            Corner::BottomRight => Corner::TopLeft,
+        });
+        model.set_position(opposite.0 as f64, opposite.1 as f64);
+        let target = model.corner(corner);
+        let mut count = 0;
+        while model.position() != target {
+            model.apply_counts(step.0 as i32, step.1 as i32);
+            count += 1;
+        }
+        // 留出余量以补偿加速曲线的误差
+        (count + count / 4 + 1, step)
+    }
+}
+
+/// 虚拟绝对光标
+pub struct VirtualCursor {
+    backend: Arc<dyn Backend>,
+    model: Mutex<CursorModel>,
+    max_step: i16,
+    /// `poll` 上一次读到的物理鼠标最近一次移动值
+    physical: Mutex<LatestValue<MousePosition>>,
+}
+
+impl VirtualCursor {
+    /// 在指定后端上创建虚拟光标，单次报告的最大位移默认为 127
+    pub fn new(backend: Arc<dyn Backend>, model: CursorModel) -> Self {
+        Self {
+            backend,
+            model: Mutex::new(model),
+            max_step: 127,
+            physical: Mutex::new(LatestValue::default()),
+        }
+    }
+
+    /// 设置单次报告在每个方向上的最大位移
+    pub fn with_max_step(mut self, max_step: i16) -> KmboxResult<Self> {
+        if max_step <= 0 {
+            return Err(KmboxError::ParameterError(format!(
+                "单次最大位移必须为正数: {}",
+                max_step
+            )));
+        }
+        self.max_step = max_step;
+        Ok(self)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CursorModel> {
+        self.model.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 当前的光标模型
+    pub fn model(&self) -> CursorModel {
+        self.lock().clone()
+    }
+
+    /// 估计的光标位置
+    pub fn position(&self) -> (i32, i32) {
+        self.lock().position()
+    }
+
+    /// 直接设置估计位置，例如通过截图确认了真实位置后
+    pub fn set_position(&self, x: i32, y: i32) {
+        self.lock().set_position(x as f64, y as f64);
+    }
+
+    /// 发送一次报告并累加位移
+    fn send(&self, model: &mut CursorModel, dx: i16, dy: i16) -> KmboxResult<()> {
+        check_result(self.backend.mouse_move(dx, dy), Operation::MouseMove)?;
+        model.apply_counts(dx as i32, dy as i32);
+        Ok(())
+    }
+
+    /// 相对移动，位移按最大位移拆分为多次报告
+    pub fn move_by(&self, mut dx: i32, mut dy: i32) -> KmboxResult<()> {
+        let mut model = self.lock();
+        let limit = self.max_step as i32;
+        while dx != 0 || dy != 0 {
+            let step_x = dx.clamp(-limit, limit);
+            let step_y = dy.clamp(-limit, limit);
+            self.send(&mut model, step_x as i16, step_y as i16)?;
+            dx -= step_x;
+            dy -= step_y;
+        }
+        Ok(())
+    }
+
+    /// 移动到屏幕坐标 `(x, y)`，超出屏幕的坐标被限制在屏幕内
+    pub fn move_to(&self, x: i32, y: i32) -> KmboxResult<()> {
+        let mut model = self.lock();
+        for (dx, dy) in model.plan_move_to(x, y, self.max_step) {
+            self.send(&mut model, dx, dy)?;
+        }
+        Ok(())
+    }
+
+    /// 把光标推到屏幕角落并以该角落重新校准估计位置
+    pub fn home(&self, corner: Corner) -> KmboxResult<()> {
+        let mut model = self.lock();
+        let (count, (dx, dy)) = model.plan_home(corner, self.max_step);
+        for _ in 0..count {
+            self.send(&mut model, dx, dy)?;
+        }
+        let (x, y) = model.corner(corner);
+        model.set_position(x as f64, y as f64);
+        Ok(())
+    }
+
+    /// 读取物理鼠标位移并累加，返回更新后的位置
+    ///
+    /// 设备返回的是最近一次移动的值，只有与上一次读取不同时才累加；
+    /// 第一次调用只记录基准值。需要定期调用，两次调用之间的多次移动只能累加最后一次，
+    /// 已经使用 [`InputMonitor`](crate::monitor::InputMonitor) 时改用 [`VirtualCursor::track`]。
+    pub fn poll(&self) -> KmboxResult<(i32, i32)> {
+        let (mut x, mut y) = (0, 0);
+        let result = self.backend.monitor_mouse_xy(&mut x, &mut y);
+        check_result(result, Operation::MonitorMouseXy)?;
+        let moved = self
+            .physical
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .update(MousePosition { x, y });
+        let mut model = self.lock();
+        if let Some(MousePosition { x, y }) = moved {
+            model.apply_counts(x, y);
+        }
+        Ok(model.position())
+    }
+
+    /// 累加监听到的物理鼠标移动事件
+    pub fn track(&self, event: &InputEvent) {
+        if let EventKind::Moved { dx, dy } = event.kind {
+            self.lock().apply_counts(dx, dy);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
+    use std::time::Instant;
+
+    fn accelerated() -> AccelerationCurve {
+        // 慢速 1:1，快速时倍率逐渐增大到 2
+        AccelerationCurve::from_points(vec![(4.0, 4.0), (20.0, 32.0), (100.0, 192.0)]).unwrap()
+    }
+
+    #[test]
+    fn test_curve_apply_and_invert() {
+        let curve = accelerated();
+        assert_eq!(curve.apply(2.0), 2.0);
+        assert_eq!(curve.apply(12.0), 18.0);
+        assert_eq!(curve.apply(200.0), 392.0);
+        for counts in [0.5, 3.0, 17.0, 80.0, 150.0] {
+            assert!((curve.invert(curve.apply(counts)) - counts).abs() < 1e-9);
+        }
+        assert!(AccelerationCurve::from_points(vec![]).is_err());
+        assert!(AccelerationCurve::from_points(vec![(2.0, 2.0), (1.0, 3.0)]).is_err());
+        assert!(AccelerationCurve::linear(0.0).is_err());
+    }
+
+    #[test]
+    fn test_model_clamps_to_screen() {
+        let mut model = CursorModel::new(1920, 1080).unwrap();
+        assert_eq!(model.position(), (960, 540));
+        model.apply_counts(-5000, 100);
+        assert_eq!(model.position(), (0, 640));
+        model.apply_counts(10_000, 10_000);
+        assert_eq!(model.position(), (1919, 1079));
+        assert!(CursorModel::new(0, 1080).is_err());
+    }
+
+    #[test]
+    fn test_plan_move_to_reaches_target() {
+        for curve in [AccelerationCurve::identity(), accelerated()] {
+            let model = CursorModel::new(1920, 1080).unwrap().with_curve(curve);
+            let plan = model.plan_move_to(100, 1000, 50);
+            assert!(plan.iter().all(|&(x, y)| x.abs() <= 50 && y.abs() <= 50));
+
+            let mut moved = model.clone();
+            for &(dx, dy) in &plan {
+                moved.apply_counts(dx as i32, dy as i32);
+            }
+            let (x, y) = moved.position();
+            assert!(
+                (x - 100).abs() <= 1 && (y - 1000).abs() <= 1,
+                "{:?}",
+                (x, y)
+            );
+        }
+    }
+
+    #[test]
+    fn test_virtual_cursor_moves_and_tracks() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let model = CursorModel::new(800, 600).unwrap();
+        let cursor = VirtualCursor::new(sim.clone(), model)
+            .with_max_step(100)
+            .unwrap();
+
+        cursor.move_to(650, 300).unwrap();
+        assert_eq!(cursor.position(), (650, 300));
+        let sent: i32 = sim
+            .reports()
+            .iter()
+            .map(|report| match report {
+                HidReport::Move { x, .. } => *x,
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(sent, 250);
+
+        // 物理鼠标的位移也会被累加；第一次读取只记录开始前的最近一次移动
+        sim.move_physical(7, 7);
+        assert_eq!(cursor.poll().unwrap(), (650, 300));
+        sim.move_physical(-50, 20);
+        assert_eq!(cursor.poll().unwrap(), (600, 320));
+        // 设备一直返回最近一次的值，重复读取不会让光标漂移
+        for _ in 0..10 {
+            assert_eq!(cursor.poll().unwrap(), (600, 320));
+        }
+        cursor.track(&InputEvent {
+            kind: EventKind::Moved { dx: 0, dy: -20 },
+            timestamp: Instant::now(),
+        });
+        assert_eq!(cursor.position(), (600, 300));
+    }
+
+    #[test]
+    fn test_home_recalibrates() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let model = CursorModel::new(1920, 1080)
+            .unwrap()
+            .with_curve(accelerated());
+        let cursor = VirtualCursor::new(sim.clone(), model);
+        cursor.set_position(1000, 1000);
+        cursor.home(Corner::BottomRight).unwrap();
+        assert_eq!(cursor.position(), (1919, 1079));
+        assert!(sim
+            .reports()
+            .iter()
+            .all(|report| *report == HidReport::Move { x: 127, y: 127 }));
+    }
+}
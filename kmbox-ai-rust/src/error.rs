@@ -1,9 +1,384 @@
 //! 错误处理模块
 //!
 //! 定义了 KmboxAI 库中使用的错误类型和结果类型
+//!
+//! C 接口调用失败时返回 [`KmboxError::Call`]，保留原始返回码、失败的 [`Operation`] 和可选的底层错误。
+//! 每个操作有稳定的英文标识和中英文说明，并属于一个接口族 [`Api`]。
+//! 返回码按接口族解释：只有 RKNN 接口的返回码按 `kmbox_rknn_api.h` 中的 `RKNN_ERR_*` 映射为 [`ErrorCode`]；
+//! `kmAI_*` 等头文件没有定义返回码的接口只保留原始返回码，错误名称为 `KMAI_CALL_FAILED` 等。
+//! 日志和告警应当匹配 [`KmboxError::name`]、[`KmboxError::operation`] 或 [`KmboxError::code`]，
+//! 不要匹配本地化的错误信息。
+//!
+//! ```rust
+//! use kmbox_ai_rust::error::{ErrorCode, KmboxError, Language, Operation};
+//!
+//! let error = KmboxError::call(Operation::RknnRun, -2);
+//! assert_eq!(error.code(), Some(-2));
+//! assert_eq!(error.operation(), Some("rknn_run"));
+//! assert_eq!(error.error_code(), Some(ErrorCode::Timeout));
+//! assert_eq!(error.name(), "RKNN_ERR_TIMEOUT");
+//! assert!(error.is_retryable());
+//! assert_eq!(
+//!     error.message(Language::English),
+//!     "run RKNN inference failed with code -2 (RKNN_ERR_TIMEOUT: execution timed out)"
+//! );
+//!
+//! // kmAI 接口的返回码没有文档，不映射为 RKNN_ERR_*
+//! let error = KmboxError::call(Operation::MouseMove, -1);
+//! assert_eq!(error.error_code(), None);
+//! assert_eq!(error.name(), "KMAI_CALL_FAILED");
+//! assert_eq!(
+//!     error.message(Language::English),
+//!     "move mouse failed with code -1 (KMAI_CALL_FAILED: kmboxAI.h does not define return codes)"
+//! );
+//! ```
 
 use std::error::Error as StdError;
 use std::fmt;
+use std::io;
+
+/// 错误信息的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Language {
+    /// 中文，`Display` 使用的语言
+    #[default]
+    Chinese,
+    /// 英文
+    English,
+}
+
+/// 定义 [`ErrorCode`]，每行依次为：枚举成员、头文件中的宏名、返回码、是否可重试、中文说明、英文说明
+macro_rules! error_codes {
+    ($($variant:ident = $name:ident = $code:literal, $retryable:literal, $zh:literal, $en:literal;)*) => {
+        /// 已知的 C 接口返回码，对应 `kmbox_rknn_api.h` 中的 `RKNN_ERR_*`
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ErrorCode {
+            $(
+                #[doc = $zh]
+                $variant,
+            )*
+            /// 头文件中没有定义的返回码
+            Other(i32),
+        }
+
+        impl ErrorCode {
+            /// 所有已知的返回码
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant),*];
+
+            /// 把 C 接口的返回码映射为错误码
+            pub fn from_raw(code: i32) -> Self {
+                match code {
+                    $($code => ErrorCode::$variant,)*
+                    other => ErrorCode::Other(other),
+                }
+            }
+
+            /// 原始返回码
+            pub fn raw(&self) -> i32 {
+                match self {
+                    $(ErrorCode::$variant => $code,)*
+                    ErrorCode::Other(code) => *code,
+                }
+            }
+
+            /// 稳定的错误码名称，与头文件中的宏名一致；未知返回码为 `"UNKNOWN_ERROR_CODE"`
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => stringify!($name),)*
+                    ErrorCode::Other(_) => "UNKNOWN_ERROR_CODE",
+                }
+            }
+
+            /// 稍后重试是否可能成功，如超时、设备暂时不可用
+            pub fn is_retryable(&self) -> bool {
+                match self {
+                    $(ErrorCode::$variant => $retryable,)*
+                    ErrorCode::Other(_) => false,
+                }
+            }
+
+            /// 指定语言的错误说明
+            pub fn description(&self, language: Language) -> &'static str {
+                match (self, language) {
+                    $(
+                        (ErrorCode::$variant, Language::Chinese) => $zh,
+                        (ErrorCode::$variant, Language::English) => $en,
+                    )*
+                    (ErrorCode::Other(_), Language::Chinese) => "未知错误码",
+                    (ErrorCode::Other(_), Language::English) => "unknown error code",
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    Fail = RKNN_ERR_FAIL = -1, false, "执行失败", "execution failed";
+    Timeout = RKNN_ERR_TIMEOUT = -2, true, "执行超时", "execution timed out";
+    DeviceUnavailable = RKNN_ERR_DEVICE_UNAVAILABLE = -3, true, "设备不可用", "device is unavailable";
+    MallocFail = RKNN_ERR_MALLOC_FAIL = -4, true, "内存分配失败", "memory allocation failed";
+    ParamInvalid = RKNN_ERR_PARAM_INVALID = -5, false, "参数无效", "parameter is invalid";
+    ModelInvalid = RKNN_ERR_MODEL_INVALID = -6, false, "模型无效", "model is invalid";
+    CtxInvalid = RKNN_ERR_CTX_INVALID = -7, false, "上下文无效", "context is invalid";
+    InputInvalid = RKNN_ERR_INPUT_INVALID = -8, false, "输入无效", "input is invalid";
+    OutputInvalid = RKNN_ERR_OUTPUT_INVALID = -9, false, "输出无效", "output is invalid";
+    DeviceUnmatch = RKNN_ERR_DEVICE_UNMATCH = -10, false,
+        "设备不匹配，请更新 RKNN SDK 和 NPU 驱动/固件",
+        "device does not match, update the RKNN SDK and NPU driver/firmware";
+    IncompatiblePreCompileModel = RKNN_ERR_INCOMPATILE_PRE_COMPILE_MODEL = -11, false,
+        "预编译模型与当前驱动不兼容",
+        "pre-compiled model is not compatible with the current driver";
+    IncompatibleOptimizationLevel = RKNN_ERR_INCOMPATILE_OPTIMIZATION_LEVEL_VERSION = -12, false,
+        "模型优化等级与当前驱动不兼容",
+        "model optimization level is not compatible with the current driver";
+    TargetPlatformUnmatch = RKNN_ERR_TARGET_PLATFORM_UNMATCH = -13, false,
+        "模型目标平台与当前平台不匹配",
+        "model target platform does not match the current platform";
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// 返回错误码的 C 接口族，决定返回码如何解释
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Api {
+    /// `kmboxAI.h` 中的 `kmAI_*` 接口（键鼠、MiniUI、系统和 `kmAI_YOLO_*`），头文件没有定义返回码
+    KmAi,
+    /// `KeyboardTable.h` 中的 `soft_*` 软键盘接口，头文件没有定义返回码
+    SoftKeyboard,
+    /// YOLO 检测库的 `yolo_*_v_v` 接口，没有定义返回码
+    Yolo,
+    /// `kmbox_rknn_api.h` 中的 `rknn_*` 接口，返回码为 `RKNN_ERR_*`
+    Rknn,
+}
+
+impl Api {
+    /// 返回码是否有文档，只有有文档的返回码映射为 [`ErrorCode`]
+    pub fn documents_error_codes(&self) -> bool {
+        matches!(self, Api::Rknn)
+    }
+
+    /// 返回码没有文档时的错误名称
+    fn failure_name(&self) -> &'static str {
+        match self {
+            Api::KmAi => "KMAI_CALL_FAILED",
+            Api::SoftKeyboard => "SOFT_KEYBOARD_CALL_FAILED",
+            Api::Yolo => "YOLO_CALL_FAILED",
+            Api::Rknn => "RKNN_CALL_FAILED",
+        }
+    }
+
+    /// 返回码没有文档时的说明
+    fn failure_description(&self, language: Language) -> &'static str {
+        match (self, language) {
+            (Api::KmAi, Language::Chinese) => "kmboxAI.h 没有定义返回码的含义",
+            (Api::KmAi, Language::English) => "kmboxAI.h does not define return codes",
+            (Api::SoftKeyboard, Language::Chinese) => "KeyboardTable.h 没有定义返回码的含义",
+            (Api::SoftKeyboard, Language::English) => {
+                "KeyboardTable.h does not define return codes"
+            }
+            (Api::Yolo, Language::Chinese) => "YOLO 检测库没有定义返回码的含义",
+            (Api::Yolo, Language::English) => {
+                "the YOLO detection library does not define return codes"
+            }
+            (Api::Rknn, Language::Chinese) => "未知错误码",
+            (Api::Rknn, Language::English) => "unknown error code",
+        }
+    }
+}
+
+/// 定义 [`Operation`]，每行依次为：枚举成员、稳定的英文标识、接口族、中文说明、英文说明
+macro_rules! operations {
+    ($($variant:ident = $id:literal, $api:ident, $zh:literal, $en:literal;)*) => {
+        /// 调用 C 接口的操作
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Operation {
+            $(
+                #[doc = $zh]
+                $variant,
+            )*
+        }
+
+        impl Operation {
+            /// 所有操作
+            pub const ALL: &'static [Operation] = &[$(Operation::$variant),*];
+
+            /// 稳定的英文标识，供日志和告警匹配
+            pub fn id(&self) -> &'static str {
+                match self {
+                    $(Operation::$variant => $id,)*
+                }
+            }
+
+            /// 操作调用的接口族
+            pub fn api(&self) -> Api {
+                match self {
+                    $(Operation::$variant => Api::$api,)*
+                }
+            }
+
+            /// 指定语言的操作说明
+            pub fn description(&self, language: Language) -> &'static str {
+                match (self, language) {
+                    $(
+                        (Operation::$variant, Language::Chinese) => $zh,
+                        (Operation::$variant, Language::English) => $en,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+operations! {
+    Init = "init", KmAi, "初始化KmboxAI系统", "initialize KmboxAI";
+    Run = "run", KmAi, "运行KmboxAI系统", "run KmboxAI";
+    Enable = "enable", KmAi, "设置KmboxAI启用状态", "set KmboxAI enable state";
+    Version = "version", KmAi, "查询KmboxAI版本", "query KmboxAI version";
+    Reboot = "reboot", KmAi, "重启系统", "reboot";
+    MouseMove = "mouse_move", KmAi, "移动鼠标", "move mouse";
+    MouseButton = "mouse_button", KmAi, "鼠标按键操作", "mouse button";
+    MouseWheel = "mouse_wheel", KmAi, "鼠标滚轮操作", "mouse wheel";
+    MouseAll = "mouse_all", KmAi, "综合鼠标操作", "combined mouse report";
+    KeyDown = "key_down", KmAi, "键盘按键按下", "key down";
+    KeyUp = "key_up", KmAi, "键盘按键释放", "key up";
+    KeyInit = "key_init", KmAi, "初始化键盘", "initialize keyboard";
+    MonitorMouseLeft = "monitor_mouse_left", KmAi, "监控鼠标左键", "monitor left mouse button";
+    MonitorMouseMiddle = "monitor_mouse_middle", KmAi, "监控鼠标中键", "monitor middle mouse button";
+    MonitorMouseRight = "monitor_mouse_right", KmAi, "监控鼠标右键", "monitor right mouse button";
+    MonitorMouseSide1 = "monitor_mouse_side1", KmAi, "监控鼠标侧键1", "monitor mouse side button 1";
+    MonitorMouseSide2 = "monitor_mouse_side2", KmAi, "监控鼠标侧键2", "monitor mouse side button 2";
+    MonitorMouseXy = "monitor_mouse_xy", KmAi, "监控鼠标位置", "monitor mouse position";
+    MonitorMouseWheel = "monitor_mouse_wheel", KmAi, "监控鼠标滚轮", "monitor mouse wheel";
+    MonitorKeyboard = "monitor_keyboard", KmAi, "监控键盘按键", "monitor keyboard key";
+    MaskMouseLeft = "mask_mouse_left", KmAi, "掩码鼠标左键", "mask left mouse button";
+    MaskMouseRight = "mask_mouse_right", KmAi, "掩码鼠标右键", "mask right mouse button";
+    MaskMouseMiddle = "mask_mouse_middle", KmAi, "掩码鼠标中键", "mask middle mouse button";
+    MaskMouseSide1 = "mask_mouse_side1", KmAi, "掩码鼠标侧键1", "mask mouse side button 1";
+    MaskMouseSide2 = "mask_mouse_side2", KmAi, "掩码鼠标侧键2", "mask mouse side button 2";
+    MaskMouseX = "mask_mouse_x", KmAi, "掩码鼠标X轴移动", "mask mouse X movement";
+    MaskMouseY = "mask_mouse_y", KmAi, "掩码鼠标Y轴移动", "mask mouse Y movement";
+    MaskMouseXy = "mask_mouse_xy", KmAi, "掩码鼠标XY轴移动", "mask mouse XY movement";
+    MaskMouseWheel = "mask_mouse_wheel", KmAi, "掩码鼠标滚轮", "mask mouse wheel";
+    MaskKeyboard = "mask_keyboard", KmAi, "掩码键盘按键", "mask keyboard key";
+    UnmaskKeyboard = "unmask_keyboard", KmAi, "取消掩码键盘按键", "unmask keyboard key";
+    UnmaskAll = "unmask_all", KmAi, "取消所有掩码", "unmask all";
+    MiniuiEnable = "miniui_enable", KmAi, "设置MiniUI状态", "set MiniUI mode";
+    MiniuiUserLock = "miniui_user_lock", KmAi, "锁定MiniUI用户界面", "lock MiniUI";
+    MiniuiUserUnlock = "miniui_user_unlock", KmAi, "解锁MiniUI用户界面", "unlock MiniUI";
+    MiniuiLcdDisplay = "miniui_lcd_display", KmAi, "LCD显示图像", "display image on LCD";
+    YoloLoadModel = "yolo_load_model", KmAi, "加载YOLO模型", "load YOLO model";
+    YoloReloadModel = "yolo_reload_model", KmAi, "重新加载YOLO模型", "reload YOLO model";
+    YoloInference = "yolo_interface_model", KmAi, "YOLO推理", "run YOLO inference";
+    SoftKeyState = "soft_key_state", SoftKeyboard, "查询按键状态", "query key state";
+    SoftMaskState = "soft_mask_state", SoftKeyboard, "查询按键掩码", "query key mask";
+    SoftString = "soft_string", SoftKeyboard, "输入字符串", "type string";
+    YoloDetect = "yolo_detect", Yolo, "YOLO检测", "YOLO detection";
+    RknnLoadModel = "rknn_load_model", Rknn, "加载RKNN模型", "load RKNN model";
+    RknnQuery = "rknn_query", Rknn, "查询RKNN模型信息", "query RKNN model information";
+    RknnSetInputs = "rknn_inputs_set", Rknn, "设置RKNN输入", "set RKNN inputs";
+    RknnRun = "rknn_run", Rknn, "运行RKNN推理", "run RKNN inference";
+    RknnWait = "rknn_wait", Rknn, "等待RKNN推理完成", "wait for RKNN inference";
+    RknnGetOutputs = "rknn_outputs_get", Rknn, "获取RKNN输出", "get RKNN outputs";
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+/// 底层错误
+pub type BoxedError = Box<dyn StdError + Send + Sync + 'static>;
+
+/// C 接口调用失败的详细信息
+#[derive(Debug)]
+pub struct CallError {
+    operation: Operation,
+    /// 调用方通过 [`KmboxError::with_context`] 添加的上下文，为空表示没有
+    context: String,
+    code: i32,
+    source: Option<BoxedError>,
+}
+
+impl CallError {
+    /// 创建调用错误
+    pub fn new(operation: Operation, code: i32) -> Self {
+        Self {
+            operation,
+            context: String::new(),
+            code,
+            source: None,
+        }
+    }
+
+    /// 附加引起该错误的底层错误
+    pub fn with_source(mut self, source: impl Into<BoxedError>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// 失败的操作
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    /// 调用方添加的上下文
+    pub fn context(&self) -> Option<&str> {
+        (!self.context.is_empty()).then_some(self.context.as_str())
+    }
+
+    /// C 接口的原始返回码
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// 映射后的错误码，只有返回码有文档的接口族（见 [`Api::documents_error_codes`]）才有值
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        let api = self.operation.api();
+        api.documents_error_codes()
+            .then(|| ErrorCode::from_raw(self.code))
+    }
+
+    /// 稳定的错误名称
+    fn name(&self) -> &'static str {
+        match self.error_code() {
+            Some(code) => code.name(),
+            None => self.operation.api().failure_name(),
+        }
+    }
+
+    fn message(&self, language: Language) -> String {
+        let description = match self.error_code() {
+            Some(code) => code.description(language),
+            None => self.operation.api().failure_description(language),
+        };
+        let operation = self.operation.description(language);
+        let operation = match self.context() {
+            Some(context) => format!("{}: {}", context, operation),
+            None => operation.to_string(),
+        };
+        match language {
+            Language::Chinese => format!(
+                "{} 失败，错误码: {} ({}: {})",
+                operation,
+                self.code,
+                self.name(),
+                description
+            ),
+            Language::English => format!(
+                "{} failed with code {} ({}: {})",
+                operation,
+                self.code,
+                self.name(),
+                description
+            ),
+        }
+    }
+}
 
 /// KmboxAI 库的错误类型
 #[derive(Debug)]
@@ -28,34 +403,190 @@ pub enum KmboxError {
     SystemError(String),
     /// 未知错误
     Unknown(String),
+    /// C 接口返回了错误码
+    Call(CallError),
+    /// 输入输出错误，`operation` 为空表示没有上下文
+    Io {
+        /// 操作名称
+        operation: String,
+        /// 底层错误
+        source: io::Error,
+    },
 }
 
-impl fmt::Display for KmboxError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl KmboxError {
+    /// C 接口 `operation` 返回错误码 `code`
+    pub fn call(operation: Operation, code: i32) -> Self {
+        KmboxError::Call(CallError::new(operation, code))
+    }
+
+    /// `operation` 发生输入输出错误
+    pub fn io(operation: impl Into<String>, source: io::Error) -> Self {
+        KmboxError::Io {
+            operation: operation.into(),
+            source,
+        }
+    }
+
+    /// 在错误信息前加上上下文，错误类型不变
+    pub fn with_context(self, context: &str) -> Self {
+        let prefix = |msg: String| {
+            if msg.is_empty() {
+                context.to_string()
+            } else {
+                format!("{}: {}", context, msg)
+            }
+        };
         match self {
-            KmboxError::InitializationError(msg) => write!(f, "初始化错误: {}", msg),
-            KmboxError::DeviceError(msg) => write!(f, "设备错误: {}", msg),
-            KmboxError::ParameterError(msg) => write!(f, "参数错误: {}", msg),
-            KmboxError::MemoryError(msg) => write!(f, "内存错误: {}", msg),
-            KmboxError::ModelError(msg) => write!(f, "模型错误: {}", msg),
-            KmboxError::InferenceError(msg) => write!(f, "推理错误: {}", msg),
-            KmboxError::KeyboardError(msg) => write!(f, "键盘错误: {}", msg),
-            KmboxError::ImageError(msg) => write!(f, "图像错误: {}", msg),
-            KmboxError::SystemError(msg) => write!(f, "系统错误: {}", msg),
-            KmboxError::Unknown(msg) => write!(f, "未知错误: {}", msg),
+            KmboxError::InitializationError(msg) => KmboxError::InitializationError(prefix(msg)),
+            KmboxError::DeviceError(msg) => KmboxError::DeviceError(prefix(msg)),
+            KmboxError::ParameterError(msg) => KmboxError::ParameterError(prefix(msg)),
+            KmboxError::MemoryError(msg) => KmboxError::MemoryError(prefix(msg)),
+            KmboxError::ModelError(msg) => KmboxError::ModelError(prefix(msg)),
+            KmboxError::InferenceError(msg) => KmboxError::InferenceError(prefix(msg)),
+            KmboxError::KeyboardError(msg) => KmboxError::KeyboardError(prefix(msg)),
+            KmboxError::ImageError(msg) => KmboxError::ImageError(prefix(msg)),
+            KmboxError::SystemError(msg) => KmboxError::SystemError(prefix(msg)),
+            KmboxError::Unknown(msg) => KmboxError::Unknown(prefix(msg)),
+            KmboxError::Call(mut call) => {
+                call.context = prefix(call.context);
+                KmboxError::Call(call)
+            }
+            KmboxError::Io { operation, source } => KmboxError::Io {
+                operation: prefix(operation),
+                source,
+            },
         }
     }
+
+    /// 稳定的错误名称，供日志和告警匹配
+    ///
+    /// C 接口错误为错误码的宏名（如 `"RKNN_ERR_TIMEOUT"`），返回码没有文档的接口族为
+    /// `"KMAI_CALL_FAILED"` 等，其余为错误类型（如 `"PARAMETER_ERROR"`）。
+    pub fn name(&self) -> &'static str {
+        match self {
+            KmboxError::InitializationError(_) => "INITIALIZATION_ERROR",
+            KmboxError::DeviceError(_) => "DEVICE_ERROR",
+            KmboxError::ParameterError(_) => "PARAMETER_ERROR",
+            KmboxError::MemoryError(_) => "MEMORY_ERROR",
+            KmboxError::ModelError(_) => "MODEL_ERROR",
+            KmboxError::InferenceError(_) => "INFERENCE_ERROR",
+            KmboxError::KeyboardError(_) => "KEYBOARD_ERROR",
+            KmboxError::ImageError(_) => "IMAGE_ERROR",
+            KmboxError::SystemError(_) => "SYSTEM_ERROR",
+            KmboxError::Unknown(_) => "UNKNOWN_ERROR",
+            KmboxError::Call(call) => call.name(),
+            KmboxError::Io { .. } => "IO_ERROR",
+        }
+    }
+
+    /// C 接口的原始返回码
+    pub fn code(&self) -> Option<i32> {
+        match self {
+            KmboxError::Call(call) => Some(call.code()),
+            _ => None,
+        }
+    }
+
+    /// 映射后的错误码，只有 RKNN 接口的返回码有值
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            KmboxError::Call(call) => call.error_code(),
+            _ => None,
+        }
+    }
+
+    /// 失败的操作
+    ///
+    /// C 接口错误为稳定的英文标识 [`Operation::id`]（上下文见 [`CallError::context`]），
+    /// 输入输出错误为操作说明。
+    pub fn operation(&self) -> Option<&str> {
+        match self {
+            KmboxError::Call(call) => Some(call.operation().id()),
+            KmboxError::Io { operation, .. } if !operation.is_empty() => Some(operation),
+            _ => None,
+        }
+    }
+
+    /// 稍后重试是否可能成功
+    ///
+    /// 超时、设备暂时不可用、内存不足和被中断的输入输出可以重试；
+    /// 参数、模型等错误重试不会改变结果，视为致命错误。返回码没有文档的接口族无法判断，视为致命错误。
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            KmboxError::DeviceError(_) => true,
+            KmboxError::Call(call) => call.error_code().is_some_and(|code| code.is_retryable()),
+            KmboxError::Io { source, .. } => matches!(
+                source.kind(),
+                io::ErrorKind::Interrupted | io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+            ),
+            _ => false,
+        }
+    }
+
+    /// 是否为重试也无法恢复的错误
+    pub fn is_fatal(&self) -> bool {
+        !self.is_retryable()
+    }
+
+    /// 指定语言的错误信息
+    ///
+    /// 英文信息翻译错误类型、操作和错误码说明，调用方提供的详细信息和上下文保持原样。
+    pub fn message(&self, language: Language) -> String {
+        let (kind, detail) = match self {
+            KmboxError::Call(call) => return call.message(language),
+            KmboxError::Io { operation, source } => {
+                let kind = match language {
+                    Language::Chinese => "输入输出错误",
+                    Language::English => "I/O error",
+                };
+                return if operation.is_empty() {
+                    format!("{}: {}", kind, source)
+                } else {
+                    format!("{}: {}: {}", kind, operation, source)
+                };
+            }
+            KmboxError::InitializationError(msg) => (("初始化错误", "initialization error"), msg),
+            KmboxError::DeviceError(msg) => (("设备错误", "device error"), msg),
+            KmboxError::ParameterError(msg) => (("参数错误", "invalid parameter"), msg),
+            KmboxError::MemoryError(msg) => (("内存错误", "memory error"), msg),
+            KmboxError::ModelError(msg) => (("模型错误", "model error"), msg),
+            KmboxError::InferenceError(msg) => (("推理错误", "inference error"), msg),
+            KmboxError::KeyboardError(msg) => (("键盘错误", "keyboard error"), msg),
+            KmboxError::ImageError(msg) => (("图像错误", "image error"), msg),
+            KmboxError::SystemError(msg) => (("系统错误", "system error"), msg),
+            KmboxError::Unknown(msg) => (("未知错误", "unknown error"), msg),
+        };
+        let kind = match language {
+            Language::Chinese => kind.0,
+            Language::English => kind.1,
+        };
+        format!("{}: {}", kind, detail)
+    }
+}
+
+impl fmt::Display for KmboxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message(Language::Chinese))
+    }
 }
 
 impl StdError for KmboxError {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
-        None
+        match self {
+            KmboxError::Call(call) => call
+                .source
+                .as_deref()
+                .map(|source| source as &(dyn StdError + 'static)),
+            KmboxError::Io { source, .. } => Some(source),
+            _ => None,
+        }
     }
 }
 
-impl From<std::io::Error> for KmboxError {
-    fn from(err: std::io::Error) -> Self {
-        KmboxError::SystemError(err.to_string())
+impl From<io::Error> for KmboxError {
+    fn from(err: io::Error) -> Self {
+        KmboxError::io(String::new(), err)
     }
 }
 
@@ -74,15 +605,21 @@ impl From<std::string::FromUtf8Error> for KmboxError {
 /// KmboxAI 库的结果类型
 pub type KmboxResult<T> = Result<T, KmboxError>;
 
-/// 检查 C 函数返回值的辅助函数
-pub(crate) fn check_result(result: i32, operation: &str) -> KmboxResult<()> {
+/// 检查 C 函数返回值的辅助函数，非 0 返回值保留为 [`KmboxError::Call`]
+pub(crate) fn check_result(result: i32, operation: Operation) -> KmboxResult<()> {
     if result == 0 {
         Ok(())
     } else {
-        Err(KmboxError::SystemError(format!(
-            "{} 失败，错误码: {}",
-            operation, result
-        )))
+        Err(KmboxError::call(operation, result))
+    }
+}
+
+/// 检查返回状态的 C 函数（1 表示是，0 表示否），负数返回值保留为 [`KmboxError::Call`]
+pub(crate) fn check_state(result: i32, operation: Operation) -> KmboxResult<bool> {
+    if result < 0 {
+        Err(KmboxError::call(operation, result))
+    } else {
+        Ok(result != 0)
     }
 }
 
@@ -108,6 +645,10 @@ mod tests {
         let error = KmboxError::InitializationError("测试错误".to_string());
         assert!(error.to_string().contains("初始化错误"));
         assert!(error.to_string().contains("测试错误"));
+        assert_eq!(
+            error.message(Language::English),
+            "initialization error: 测试错误"
+        );
     }
 
     #[test]
@@ -123,17 +664,75 @@ mod tests {
             KmboxError::ImageError("image".to_string()),
             KmboxError::SystemError("system".to_string()),
             KmboxError::Unknown("unknown".to_string()),
+            KmboxError::call(Operation::MouseMove, -1),
+            KmboxError::io("io", io::Error::other("io")),
         ];
 
         for error in errors {
             assert!(!error.to_string().is_empty());
+            assert!(!error.message(Language::English).is_empty());
+            assert!(!error.name().is_empty());
         }
     }
 
     #[test]
     fn test_check_result() {
-        assert!(check_result(0, "测试操作").is_ok());
-        assert!(check_result(1, "测试操作").is_err());
+        assert!(check_result(0, Operation::RknnRun).is_ok());
+        assert!(check_result(1, Operation::RknnRun).is_err());
+
+        let error = check_result(-6, Operation::RknnLoadModel).unwrap_err();
+        assert_eq!(error.code(), Some(-6));
+        assert_eq!(error.error_code(), Some(ErrorCode::ModelInvalid));
+        assert_eq!(error.operation(), Some("rknn_load_model"));
+        assert_eq!(error.name(), "RKNN_ERR_MODEL_INVALID");
+        assert!(error.is_fatal());
+        assert_eq!(
+            error.to_string(),
+            "加载RKNN模型 失败，错误码: -6 (RKNN_ERR_MODEL_INVALID: 模型无效)"
+        );
+
+        let error = check_result(7, Operation::RknnRun).unwrap_err();
+        assert_eq!(error.error_code(), Some(ErrorCode::Other(7)));
+        assert_eq!(error.name(), "UNKNOWN_ERROR_CODE");
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn test_codes_classified_by_api() {
+        // kmAI 接口的 -1、-2 不是 RKNN_ERR_FAIL、RKNN_ERR_TIMEOUT
+        let error = check_result(-2, Operation::MouseMove).unwrap_err();
+        assert_eq!(error.code(), Some(-2));
+        assert_eq!(error.error_code(), None);
+        assert_eq!(error.name(), "KMAI_CALL_FAILED");
+        assert!(error.is_fatal());
+        assert_eq!(
+            error.to_string(),
+            "移动鼠标 失败，错误码: -2 (KMAI_CALL_FAILED: kmboxAI.h 没有定义返回码的含义)"
+        );
+
+        let error = check_state(-1, Operation::SoftKeyState).unwrap_err();
+        assert_eq!(error.name(), "SOFT_KEYBOARD_CALL_FAILED");
+        assert_eq!(
+            KmboxError::call(Operation::YoloDetect, -1).name(),
+            "YOLO_CALL_FAILED"
+        );
+    }
+
+    #[test]
+    fn test_operations_have_english_ids() {
+        let mut ids = std::collections::BTreeSet::new();
+        for operation in Operation::ALL {
+            let id = operation.id();
+            assert!(
+                id.bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_'),
+                "{}",
+                id
+            );
+            assert!(ids.insert(id), "重复的操作标识: {}", id);
+            assert!(operation.description(Language::English).is_ascii());
+            assert!(!operation.description(Language::Chinese).is_empty());
+        }
     }
 
     #[test]
@@ -149,8 +748,65 @@ mod tests {
     #[test]
     fn test_error_conversions() {
         // 测试 From 实现
-        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "文件未找到");
+        let io_error = io::Error::new(io::ErrorKind::NotFound, "文件未找到");
         let kmbox_error: KmboxError = io_error.into();
-        assert!(matches!(kmbox_error, KmboxError::SystemError(_)));
+        assert!(matches!(kmbox_error, KmboxError::Io { .. }));
+        assert_eq!(kmbox_error.operation(), None);
+        assert_eq!(kmbox_error.source().unwrap().to_string(), "文件未找到");
+
+        let kmbox_error = kmbox_error.with_context("读取录制文件");
+        assert_eq!(kmbox_error.operation(), Some("读取录制文件"));
+        assert!(kmbox_error.is_fatal());
+        assert!(KmboxError::from(io::Error::from(io::ErrorKind::TimedOut)).is_retryable());
+    }
+
+    #[test]
+    fn test_source_chain() {
+        let cause = KmboxError::io("打开设备", io::Error::from(io::ErrorKind::WouldBlock));
+        let error = KmboxError::Call(CallError::new(Operation::RknnRun, -3).with_source(cause))
+            .with_context("启动")
+            .with_context("第 2 行");
+
+        assert_eq!(error.operation(), Some("rknn_run"));
+        match &error {
+            KmboxError::Call(call) => assert_eq!(call.context(), Some("第 2 行: 启动")),
+            _ => unreachable!(),
+        }
+        assert!(error.is_retryable());
+        let cause = error.source().unwrap();
+        assert!(cause.to_string().contains("打开设备"));
+        assert!(cause.source().is_some());
+        assert_eq!(
+            error.message(Language::English),
+            "第 2 行: 启动: run RKNN inference failed with code -3 \
+             (RKNN_ERR_DEVICE_UNAVAILABLE: device is unavailable)"
+        );
+    }
+
+    #[test]
+    fn test_error_codes_match_header() {
+        let header = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../kmbox_libs/include/kmboxAIlib/kmbox_rknn_api.h"
+        ))
+        .expect("无法读取 kmbox_rknn_api.h");
+
+        let mut defines = std::collections::BTreeMap::new();
+        for line in header.lines() {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.len() < 3 || tokens[0] != "#define" || !tokens[1].starts_with("RKNN_ERR_") {
+                continue;
+            }
+            defines.insert(tokens[1].to_string(), tokens[2].parse::<i32>().unwrap());
+        }
+
+        let table: std::collections::BTreeMap<String, i32> = ErrorCode::ALL
+            .iter()
+            .map(|code| (code.name().to_string(), code.raw()))
+            .collect();
+        assert_eq!(table, defines);
+        for code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::from_raw(code.raw()), *code);
+        }
     }
 }
@@ -0,0 +1,397 @@
+//! 故障保护模块
+//!
+//! 记录 [`KmboxAI`](crate::kmbox_ai::KmboxAI) 和 [`Keyboard`](crate::keyboard::Keyboard)
+//! 通过软件按下、尚未释放的鼠标按键和键盘按键，在以下情况统一释放：
+//!
+//! - 实例销毁（`Drop`）：释放该实例按下的按键，并调用 `key_init` 和 `unmask_all`
+//! - panic：通过 [`install_panic_hook`] 安装钩子后，释放所有实例按下的按键，
+//!   并对每个后端调用 `key_init` 和 `unmask_all`
+//! - SIGINT/SIGTERM：通过 [`install_signal_handlers`] 安装处理程序后，处理方式与 panic 相同，
+//!   完成后按信号的默认行为结束进程
+//!
+//! panic 和信号处理不会无限等待其他线程持有的锁：最多等待 [`RELEASE_LOCK_TIMEOUT`]，
+//! 超时的记录不再逐个释放，但仍会对其后端调用 `key_init` 和 `unmask_all`。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::failsafe;
+//! use kmbox_ai_rust::kmbox_ai::{KmboxAI, MouseAction, MouseButton};
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! failsafe::install_panic_hook();
+//! failsafe::install_signal_handlers()?;
+//!
+//! let kmbox = KmboxAI::new()?;
+//! kmbox.mouse_button(MouseButton::Left, MouseAction::Press)?;
+//! // 即使这里 panic 或者进程被 Ctrl+C 结束，左键也会被释放
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::KmboxResult;
+use crate::keyboard::{resolve_key, Key};
+use crate::kmbox_ai::MouseButton;
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex, MutexGuard, Once, TryLockError, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 紧急释放时等待其他线程持有的锁的最长时间
+pub const RELEASE_LOCK_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// 在 [`RELEASE_LOCK_TIMEOUT`] 内反复尝试加锁，超时返回 `None`
+///
+/// 持有锁的线程 panic 后锁中的数据仍然可用。
+pub(crate) fn lock_for_release<T>(mutex: &Mutex<T>) -> Option<MutexGuard<'_, T>> {
+    let deadline = Instant::now() + RELEASE_LOCK_TIMEOUT;
+    loop {
+        match mutex.try_lock() {
+            Ok(guard) => return Some(guard),
+            Err(TryLockError::Poisoned(e)) => return Some(e.into_inner()),
+            Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                thread::sleep(Duration::from_millis(1));
+            }
+            Err(TryLockError::WouldBlock) => return None,
+        }
+    }
+}
+
+/// 软件按下、尚未释放的输入
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeldInputs {
+    /// 鼠标按键
+    pub buttons: BTreeSet<MouseButton>,
+    /// 通过 `kmAI_keydown` 按下的键值
+    pub keys: BTreeSet<i32>,
+    /// 通过软件键盘（`soft_keydown`）按下的按键
+    pub soft_keys: Vec<Key>,
+}
+
+impl HeldInputs {
+    /// 是否没有任何按下的输入
+    pub fn is_empty(&self) -> bool {
+        self.buttons.is_empty() && self.keys.is_empty() && self.soft_keys.is_empty()
+    }
+}
+
+/// 单个实例的按键记录
+pub(crate) struct HeldTracker {
+    backend: Arc<dyn Backend>,
+    held: Mutex<HeldInputs>,
+}
+
+/// 所有存活实例的按键记录
+static TRACKERS: Mutex<Vec<Weak<HeldTracker>>> = Mutex::new(Vec::new());
+
+impl HeldTracker {
+    /// 创建按键记录并登记到全局列表
+    pub(crate) fn new(backend: Arc<dyn Backend>) -> Arc<Self> {
+        let tracker = Arc::new(Self {
+            backend,
+            held: Mutex::new(HeldInputs::default()),
+        });
+        let mut trackers = TRACKERS.lock().unwrap_or_else(|e| e.into_inner());
+        trackers.retain(|weak| weak.strong_count() > 0);
+        trackers.push(Arc::downgrade(&tracker));
+        tracker
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HeldInputs> {
+        self.held.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 记录鼠标按键状态
+    pub(crate) fn set_button(&self, button: MouseButton, down: bool) {
+        let mut held = self.lock();
+        if down {
+            held.buttons.insert(button);
+        } else {
+            held.buttons.remove(&button);
+        }
+    }
+
+    /// 记录 `kmAI_keydown`/`kmAI_keyup` 按键状态
+    pub(crate) fn set_key(&self, vkey: i32, down: bool) {
+        let mut held = self.lock();
+        if down {
+            held.keys.insert(vkey);
+        } else {
+            held.keys.remove(&vkey);
+        }
+    }
+
+    /// 记录软件键盘按键状态
+    pub(crate) fn set_soft_key(&self, key: &Key, down: bool) {
+        let mut held = self.lock();
+        held.soft_keys.retain(|held_key| held_key != key);
+        if down {
+            held.soft_keys.push(key.clone());
+        }
+    }
+
+    /// 所有 `kmAI_keydown` 按下的按键已经被 `key_init` 复位
+    pub(crate) fn clear_keys(&self) {
+        self.lock().keys.clear();
+    }
+
+    /// 当前按下的输入
+    pub(crate) fn held(&self) -> HeldInputs {
+        self.lock().clone()
+    }
+
+    /// 释放记录的所有输入，返回释放前的记录
+    pub(crate) fn release_all(&self) -> HeldInputs {
+        let held = std::mem::take(&mut *self.lock());
+        release(self.backend.as_ref(), &held);
+        held
+    }
+
+    /// 紧急释放，最多等待 [`RELEASE_LOCK_TIMEOUT`]
+    fn release_now(&self) {
+        if let Some(mut held) = lock_for_release(&self.held) {
+            let held = std::mem::take(&mut *held);
+            release(self.backend.as_ref(), &held);
+        }
+    }
+}
+
+/// 在后端上释放输入
+fn release(backend: &dyn Backend, held: &HeldInputs) {
+    for &button in &held.buttons {
+        backend.mouse_button(button, false);
+    }
+    for &vkey in &held.keys {
+        backend.key_up(vkey);
+    }
+    for key in &held.soft_keys {
+        if let Ok((value, name)) = resolve_key(backend, key) {
+            backend.soft_keyup(value, &name);
+        }
+    }
+}
+
+/// 释放所有实例按下的输入，并对每个后端调用 `key_init` 和 `unmask_all`
+///
+/// 由 panic 钩子和信号处理调用，也可以在程序退出前手动调用。
+/// 实例列表被其他线程占用超过 [`RELEASE_LOCK_TIMEOUT`] 时放弃释放。
+pub fn release_all() {
+    let Some(trackers) = lock_for_release(&TRACKERS) else {
+        return;
+    };
+    let trackers: Vec<Arc<HeldTracker>> = trackers.iter().filter_map(Weak::upgrade).collect();
+    release_trackers(&trackers);
+}
+
+/// 释放指定记录中的输入，每个后端只复位一次
+fn release_trackers(trackers: &[Arc<HeldTracker>]) {
+    let mut backends: Vec<&Arc<dyn Backend>> = Vec::new();
+    for tracker in trackers {
+        tracker.release_now();
+        if !backends
+            .iter()
+            .any(|backend| Arc::ptr_eq(backend, &tracker.backend))
+        {
+            backends.push(&tracker.backend);
+        }
+    }
+    for backend in backends {
+        backend.key_init();
+        backend.unmask_all();
+    }
+}
+
+/// 安装 panic 钩子，panic 时调用 [`release_all`]
+///
+/// 只会安装一次，原有的钩子会在之后继续执行。
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            release_all();
+            previous(info);
+        }));
+    });
+}
+
+/// 安装 SIGINT/SIGTERM 处理程序，收到信号时调用 [`release_all`] 后按默认行为结束进程
+///
+/// 信号处理函数只向管道写入信号编号，释放操作在专门的线程中执行。
+#[cfg(target_os = "linux")]
+pub fn install_signal_handlers() -> KmboxResult<()> {
+    signal::install()
+}
+
+/// 当前平台不支持信号处理，直接返回成功
+#[cfg(not(target_os = "linux"))]
+pub fn install_signal_handlers() -> KmboxResult<()> {
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+mod signal {
+    use super::release_all;
+    use crate::error::{KmboxError, KmboxResult};
+    use std::sync::atomic::{AtomicI32, Ordering};
+    use std::sync::OnceLock;
+
+    /// 信号管道的写端
+    static WRITE_FD: AtomicI32 = AtomicI32::new(-1);
+    static INSTALLED: OnceLock<Result<(), String>> = OnceLock::new();
+
+    extern "C" fn on_signal(signum: libc::c_int) {
+        // 只调用异步信号安全的 write
+        let byte = signum as u8;
+        let fd = WRITE_FD.load(Ordering::Relaxed);
+        unsafe {
+            libc::write(fd, &byte as *const u8 as *const libc::c_void, 1);
+        }
+    }
+
+    fn watch(read_fd: libc::c_int) {
+        loop {
+            let mut byte = 0u8;
+            let n = unsafe { libc::read(read_fd, &mut byte as *mut u8 as *mut libc::c_void, 1) };
+            if n == 1 {
+                release_all();
+                // 恢复默认处理并重新发送信号，让进程按原本的方式结束
+                unsafe {
+                    libc::signal(byte as libc::c_int, libc::SIG_DFL);
+                    libc::raise(byte as libc::c_int);
+                }
+            } else if n < 0
+                && std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted
+            {
+                continue;
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn install_once() -> Result<(), String> {
+        let mut fds = [0 as libc::c_int; 2];
+        if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+            return Err(format!(
+                "创建信号管道失败: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        let [read_fd, write_fd] = fds;
+        WRITE_FD.store(write_fd, Ordering::Relaxed);
+
+        std::thread::Builder::new()
+            .name("kmbox-failsafe".to_string())
+            .spawn(move || watch(read_fd))
+            .map_err(|e| format!("启动信号处理线程失败: {}", e))?;
+
+        for signum in [libc::SIGINT, libc::SIGTERM] {
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+                action.sa_flags = libc::SA_RESTART;
+                libc::sigemptyset(&mut action.sa_mask);
+                if libc::sigaction(signum, &action, std::ptr::null_mut()) != 0 {
+                    return Err(format!(
+                        "安装信号处理程序失败: {}",
+                        std::io::Error::last_os_error()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn install() -> KmboxResult<()> {
+        INSTALLED
+            .get_or_init(install_once)
+            .clone()
+            .map_err(KmboxError::SystemError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
+
+    #[test]
+    fn test_tracker_records_and_releases() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let tracker = HeldTracker::new(sim.clone());
+        tracker.set_button(MouseButton::Left, true);
+        tracker.set_key(4, true);
+        tracker.set_key(5, true);
+        tracker.set_key(5, false);
+        tracker.set_soft_key(&Key::LeftControl, true);
+
+        let held = tracker.held();
+        assert_eq!(held.buttons, BTreeSet::from([MouseButton::Left]));
+        assert_eq!(held.keys, BTreeSet::from([4]));
+        assert_eq!(held.soft_keys, vec![Key::LeftControl]);
+
+        assert_eq!(tracker.release_all(), held);
+        assert!(tracker.held().is_empty());
+        assert_eq!(
+            sim.reports(),
+            vec![
+                HidReport::Button {
+                    button: MouseButton::Left,
+                    down: false
+                },
+                HidReport::KeyUp(4),
+                HidReport::KeyUp(Key::LeftControl.hex_value() as i32),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_release_waits_for_busy_lock() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let tracker = HeldTracker::new(sim.clone());
+        tracker.set_key(4, true);
+
+        // 其他线程短暂持有记录的锁时，紧急释放会等待而不是直接放弃
+        let (locked, wait) = std::sync::mpsc::channel();
+        let busy = tracker.clone();
+        let holder = thread::spawn(move || {
+            let _held = busy.lock();
+            locked.send(()).unwrap();
+            thread::sleep(Duration::from_millis(20));
+        });
+        wait.recv().unwrap();
+        release_trackers(std::slice::from_ref(&tracker));
+        holder.join().unwrap();
+
+        assert!(tracker.held().is_empty());
+        assert_eq!(sim.reports(), vec![HidReport::KeyUp(4), HidReport::KeyInit]);
+    }
+
+    #[test]
+    fn test_global_release_all() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let tracker = HeldTracker::new(sim.clone());
+        tracker.set_button(MouseButton::Right, true);
+        sim.mask_keyboard(4);
+
+        // 同一后端上的多个实例只复位一次
+        let other = HeldTracker::new(sim.clone());
+        other.set_key(4, true);
+
+        release_trackers(&[tracker.clone(), other.clone()]);
+        assert!(tracker.held().is_empty());
+        assert!(other.held().is_empty());
+        let state = sim.state();
+        assert!(state.keyboard_masks.is_empty());
+        assert_eq!(
+            state
+                .reports
+                .iter()
+                .filter(|report| **report == HidReport::KeyInit)
+                .count(),
+            1
+        );
+    }
+}
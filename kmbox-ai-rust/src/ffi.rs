@@ -0,0 +1,451 @@
+//! 厂商库的 FFI 声明
+//!
+//! `kmAI_*` 函数和类型来自 bindgen 生成的 `src/bindings/kmboxAI.h.rs`（构建时只保留 `kmAI_*` 函数及其依赖的类型），
+//! 硬件后端只通过本模块调用厂商库。
+//!
+//! ## 按值传递的 `cv::Mat`
+//!
+//! `kmAI_MiniUI_LCDdisplay`、`kmAI_YOLO_InterfaceModel`、`kmAI_YOLO_DrawRectangle` 和
+//! `kmAI_YOLO_DrawText` 按值接收 `cv::Mat`。`cv::Mat` 有自定义的拷贝构造和析构函数，
+//! 按照 Itanium C++ ABI，这类参数由调用方构造临时对象并传递其地址。
+//! bindgen 会把它们生成为按值传递的结构体，调用约定是错误的，
+//! 因此这几个函数在构建时被排除，在这里以 `*mut cv_Mat` 手写声明。
+//!
+//! ## 其他头文件
+//!
+//! RKNN 运行时（kmbox_rknn_api.h）和软件键盘（KeyboardTable.h）同样使用 bindgen 生成的绑定，
+//! 分别位于 [`rknn`] 和 [`keyboard_table`] 子模块。
+//!
+//! 图像处理（`capture_screen_v` 等）和 YOLO 检测（`yolo_init_v_v` 等）函数没有在任何随附的头文件中声明，
+//! 无法由 bindgen 生成，在这里按 C++ 修饰名手写声明。
+//!
+//! ## 签名检查
+//!
+//! `KMAI_SIGNATURES` 和 `KEYBOARD_TABLE_SIGNATURES` 列出了每个被封装函数的 C 签名，
+//! 并在编译期确认绑定中的 Rust 声明与之一致；单元测试会再解析 `kmboxAI.h` 和 `KeyboardTable.h`，
+//! 确认头文件中的原型与表完全相同。
+//!
+//! 没有头文件的函数记录在 `MANGLED_SIGNATURES` 中，单元测试解析每个修饰名，
+//! 确认其中的函数名和参数类型与 Rust 声明一致。修饰名不包含返回类型，返回类型无法检查。
+
+#![allow(
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    dead_code
+)]
+
+use crate::vision::{ImageBufferT, ImageRectT};
+use std::os::raw::{c_char, c_int, c_short, c_uint};
+
+include!("bindings/kmboxAI.h.rs");
+
+/// kmbox_rknn_api.h 的 FFI 声明
+///
+/// 头文件以 `extern "C"` 声明 RKNN 运行时接口，直接使用 bindgen 生成的 `src/bindings/kmbox_rknn_api.h.rs`。
+/// 其中的类型与上层 kmboxAI.h 的绑定相互独立，只在 RKNN 调用中使用。
+pub mod rknn {
+    include!("bindings/kmbox_rknn_api.h.rs");
+}
+
+/// KeyboardTable.h 的 FFI 声明
+///
+/// 头文件中的函数是 C++ 函数，bindgen 生成的声明带有修饰后的链接名。
+pub mod keyboard_table {
+    include!("bindings/KeyboardTable.h.rs");
+}
+
+/// OpenCV 的 `cv::Mat`，只以指针形式使用
+#[repr(C)]
+#[derive(Debug)]
+pub struct cv_Mat {
+    _unused: [u8; 0],
+}
+
+// 按值接收 cv::Mat 的函数，参数按 Itanium C++ ABI 以指针传递
+unsafe extern "C" {
+    pub fn kmAI_MiniUI_LCDdisplay(img: *mut cv_Mat) -> c_int;
+
+    pub fn kmAI_YOLO_InterfaceModel(
+        ctx: *mut rknn_context_t,
+        img: *mut cv_Mat,
+        out: *mut object_detected_list,
+    ) -> c_int;
+
+    pub fn kmAI_YOLO_DrawRectangle(
+        img: *mut cv_Mat,
+        rx: c_int,
+        ry: c_int,
+        rw: c_int,
+        rh: c_int,
+        color: c_uint,
+        thickness: c_int,
+    );
+
+    pub fn kmAI_YOLO_DrawText(
+        img: *mut cv_Mat,
+        text: *const c_char,
+        rx: c_int,
+        ry: c_int,
+        color: c_uint,
+        fontsize: c_int,
+    );
+}
+
+/// 可以在签名表中使用的参数类型及其在头文件中的写法
+pub(crate) trait CType {
+    const NAME: &'static str;
+}
+
+macro_rules! c_types {
+    ($($ty:ty => $name:literal,)*) => {
+        $(impl CType for $ty {
+            const NAME: &'static str = $name;
+        })*
+    };
+}
+
+c_types! {
+    () => "void",
+    c_int => "int",
+    f32 => "float",
+    c_short => "short",
+    c_uint => "unsigned int",
+    *mut c_int => "int*",
+    *mut c_char => "char*",
+    *const c_char => "const char*",
+    *mut rknn_context_t => "rknn_context_t*",
+    *mut object_detected_list => "object_detected_list*",
+    *mut ImageBufferT => "image_buffer_t*",
+    *const ImageRectT => "const image_rect_t*",
+    // 头文件中按值传递，ABI 上是指针
+    *mut cv_Mat => "cv::Mat",
+}
+
+/// 被封装函数的签名：函数名、返回类型、参数类型（头文件写法）
+pub(crate) type Signature = (&'static str, &'static str, &'static [&'static str]);
+
+macro_rules! header_signatures {
+    ($(#[$meta:meta])* $table:ident = $module:ident {
+        $($name:ident($($arg:ty),*) -> $ret:ty;)*
+    }) => {
+        // 编译期检查：绑定中的声明必须与表中的 Rust 类型完全一致
+        $(const _: unsafe extern "C" fn($($arg),*) -> $ret = $module::$name;)*
+
+        $(#[$meta])*
+        pub(crate) const $table: &[Signature] = &[
+            $((stringify!($name), <$ret as CType>::NAME, &[$(<$arg as CType>::NAME),*]),)*
+        ];
+    };
+}
+
+header_signatures! {
+    /// kmboxAI.h 中所有被封装函数的签名
+    KMAI_SIGNATURES = self {
+        kmAI_Init() -> c_int;
+        kmAI_Run() -> c_int;
+        kmAI_kmEnable(c_int) -> c_int;
+        kmAI_Version() -> c_int;
+        kmAI_mouse_move(c_short, c_short) -> c_int;
+        kmAI_mouse_left(c_int) -> c_int;
+        kmAI_mouse_right(c_int) -> c_int;
+        kmAI_mouse_middle(c_int) -> c_int;
+        kmAI_mouse_wheel(c_int) -> c_int;
+        kmAI_mouse_side1(c_int) -> c_int;
+        kmAI_mouse_side2(c_int) -> c_int;
+        kmAI_mouse_all(c_int, c_int, c_int, c_int) -> c_int;
+        kmAI_keydown(c_int) -> c_int;
+        kmAI_keyup(c_int) -> c_int;
+        kmAI_keyinit() -> c_int;
+        kmAI_monitor_mouse_left() -> c_int;
+        kmAI_monitor_mouse_middle() -> c_int;
+        kmAI_monitor_mouse_right() -> c_int;
+        kmAI_monitor_mouse_side1() -> c_int;
+        kmAI_monitor_mouse_side2() -> c_int;
+        kmAI_monitor_mouse_xy(*mut c_int, *mut c_int) -> c_int;
+        kmAI_monitor_mouse_wheel(*mut c_int) -> c_int;
+        kmAI_monitor_keyboard(c_short) -> c_int;
+        kmAI_mask_mouse_left(c_int) -> c_int;
+        kmAI_mask_mouse_right(c_int) -> c_int;
+        kmAI_mask_mouse_middle(c_int) -> c_int;
+        kmAI_mask_mouse_side1(c_int) -> c_int;
+        kmAI_mask_mouse_side2(c_int) -> c_int;
+        kmAI_mask_mouse_x(c_int) -> c_int;
+        kmAI_mask_mouse_y(c_int) -> c_int;
+        kmAI_mask_mouse_xy(c_int) -> c_int;
+        kmAI_mask_mouse_wheel(c_int) -> c_int;
+        kmAI_mask_keyboard(c_short) -> c_int;
+        kmAI_unmask_keyboard(c_short) -> c_int;
+        kmAI_unmask_all() -> c_int;
+        kmAI_MiniUI_Enable(c_int) -> c_int;
+        kmAI_MiniUI_UserLock() -> c_int;
+        kmAI_MiniUI_UserUnLock() -> c_int;
+        kmAI_MiniUI_LCDdisplay(*mut cv_Mat) -> c_int;
+        kmAI_YOLO_Loadmodel(*mut rknn_context_t, *mut c_char) -> c_int;
+        kmAI_YOLO_InterfaceModel(*mut rknn_context_t, *mut cv_Mat, *mut object_detected_list) -> c_int;
+        kmAI_YOLO_DrawRectangle(*mut cv_Mat, c_int, c_int, c_int, c_int, c_uint, c_int) -> ();
+        kmAI_YOLO_DrawText(*mut cv_Mat, *const c_char, c_int, c_int, c_uint, c_int) -> ();
+        kmAI_YOLO_Release(*mut rknn_context_t) -> c_int;
+        kmAI_reboot() -> c_int;
+    }
+}
+
+header_signatures! {
+    /// KeyboardTable.h 中所有被封装函数的签名
+    KEYBOARD_TABLE_SIGNATURES = keyboard_table {
+        getKeyHexByName(*const c_char) -> c_int;
+        soft_keydown(c_int, *const c_char) -> ();
+        soft_keyup(c_int, *const c_char) -> ();
+        soft_keypress(c_int, *const c_char, c_int) -> ();
+        check_keyisdown(c_int, *const c_char) -> c_int;
+        showTable() -> ();
+        soft_mask(c_int, *const c_char, c_int) -> c_int;
+        soft_ismask(c_int, *const c_char) -> c_int;
+        soft_String(*const c_char) -> c_int;
+    }
+}
+
+/// 没有头文件的函数：修饰名、函数名、参数类型（C++ 写法）
+pub(crate) type MangledSignature = (&'static str, &'static str, &'static [&'static str]);
+
+macro_rules! mangled_externs {
+    ($($link_name:literal => fn $name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?;)*) => {
+        unsafe extern "C" {
+            $(
+                #[link_name = $link_name]
+                pub fn $name($($arg: $ty),*) $(-> $ret)?;
+            )*
+        }
+
+        /// 手写声明的函数签名，由单元测试与修饰名对照
+        pub(crate) const MANGLED_SIGNATURES: &[MangledSignature] = &[
+            $(($link_name, stringify!($name), &[$(<$ty as CType>::NAME),*]),)*
+        ];
+    };
+}
+
+// 修饰名中的参数必须与声明一致：C++ 中没有 const 的指针参数在这里也声明为 `*mut`
+mangled_externs! {
+    // 图像处理
+    "\u{1}_Z16capture_screen_vv" => fn capture_screen_v() -> *mut ImageBufferT;
+    "\u{1}_Z12load_image_vPKc" => fn load_image_v(path: *const c_char) -> *mut ImageBufferT;
+    "\u{1}_Z12save_image_vPKcP14image_buffer_t" =>
+        fn save_image_v(path: *const c_char, buffer: *mut ImageBufferT) -> c_int;
+    "\u{1}_Z20convert_image_formatP14image_buffer_tS0_" =>
+        fn convert_image_format(src: *mut ImageBufferT, dst: *mut ImageBufferT) -> c_int;
+    "\u{1}_Z12crop_image_vP14image_buffer_tPK12image_rect_t" =>
+        fn crop_image_v(src: *mut ImageBufferT, rect: *const ImageRectT) -> *mut ImageBufferT;
+    "\u{1}_Z14resize_image_vP14image_buffer_tii" =>
+        fn resize_image_v(src: *mut ImageBufferT, width: c_int, height: c_int) -> *mut ImageBufferT;
+    "\u{1}_Z15release_image_vP14image_buffer_t" => fn release_image_v(buffer: *mut ImageBufferT);
+
+    // YOLO 检测
+    "\u{1}_Z15yolo_detect_v_vP14image_buffer_tP20object_detected_list" =>
+        fn yolo_detect_v_v(src: *mut ImageBufferT, result: *mut object_detected_list) -> c_int;
+    "\u{1}_Z13yolo_init_v_vPKc" => fn yolo_init_v_v(model_path: *const c_char) -> *mut rknn_context_t;
+    "\u{1}_Z16yolo_destroy_v_vP14rknn_context_t" => fn yolo_destroy_v_v(ctx: *mut rknn_context_t);
+    "\u{1}_Z21yolo_set_nms_thresh_vf" => fn yolo_set_nms_thresh_v(nms_thresh: f32);
+    "\u{1}_Z21yolo_set_box_thresh_vf" => fn yolo_set_box_thresh_v(box_thresh: f32);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    const INCLUDE_DIR: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../kmbox_libs/include/kmboxAIlib"
+    );
+
+    fn read_header(name: &str) -> String {
+        std::fs::read_to_string(format!("{}/{}", INCLUDE_DIR, name))
+            .unwrap_or_else(|e| panic!("无法读取 {}: {}", name, e))
+    }
+
+    fn to_map(signatures: &[Signature]) -> BTreeMap<String, (String, Vec<String>)> {
+        signatures
+            .iter()
+            .map(|(name, ret, params)| {
+                let params = params.iter().map(|p| p.to_string()).collect();
+                (name.to_string(), (ret.to_string(), params))
+            })
+            .collect()
+    }
+
+    /// 把 `const char* text`、`rknn_context_t *ctx` 这样的参数声明规整为不含参数名的类型
+    fn normalize_param(param: &str) -> String {
+        let param = param.replace('*', " * ");
+        let mut tokens: Vec<&str> = param.split_whitespace().collect();
+        // 去掉参数名（最后一个标识符）
+        if tokens.len() > 1 && tokens.last() != Some(&"*") {
+            tokens.pop();
+        }
+        tokens.join(" ").replace(" *", "*")
+    }
+
+    /// 解析头文件中名称满足 `filter` 的单行函数原型
+    fn parse_header(
+        source: &str,
+        filter: impl Fn(&str) -> bool,
+    ) -> BTreeMap<String, (String, Vec<String>)> {
+        let mut prototypes = BTreeMap::new();
+        for line in source.lines() {
+            let code = line.split("//").next().unwrap_or("").trim();
+            if !code.ends_with(';') {
+                continue;
+            }
+            let Some(open) = code.find('(') else { continue };
+            let (head, rest) = code.split_at(open);
+            let Some(name) = head.split_whitespace().last() else {
+                continue;
+            };
+            if !filter(name) {
+                continue;
+            }
+            let ret = head[..head.len() - name.len()].trim().to_string();
+            let params = rest[1..rest.find(')').unwrap()].trim();
+            let params = if params.is_empty() || params == "void" {
+                Vec::new()
+            } else {
+                params.split(',').map(normalize_param).collect()
+            };
+            prototypes.insert(name.to_string(), (ret, params));
+        }
+        prototypes
+    }
+
+    #[test]
+    fn test_normalize_param() {
+        assert_eq!(normalize_param("short  vk_key"), "short");
+        assert_eq!(normalize_param("rknn_context_t *ctx"), "rknn_context_t*");
+        assert_eq!(normalize_param(" const char* text"), "const char*");
+        assert_eq!(normalize_param("unsigned int color"), "unsigned int");
+        assert_eq!(normalize_param("cv::Mat img"), "cv::Mat");
+    }
+
+    /// 解析一个参数类型，非内置类型按出现顺序加入替换表
+    fn demangle_type(
+        chars: &mut std::iter::Peekable<std::str::Chars>,
+        subs: &mut Vec<String>,
+    ) -> String {
+        let ty = match chars.next().expect("修饰名不完整") {
+            'P' => format!("{}*", demangle_type(chars, subs)),
+            'K' => format!("const {}", demangle_type(chars, subs)),
+            'S' => {
+                let mut index = 0;
+                let mut next = chars.next();
+                if next != Some('_') {
+                    index = next.and_then(|c| c.to_digit(36)).expect("无效的替换") as usize + 1;
+                    next = chars.next();
+                }
+                assert_eq!(next, Some('_'), "无效的替换");
+                return subs[index].clone();
+            }
+            c @ '0'..='9' => source_name(c, chars),
+            builtin => {
+                return match builtin {
+                    'v' => "void",
+                    'b' => "bool",
+                    'c' => "char",
+                    's' => "short",
+                    'i' => "int",
+                    'j' => "unsigned int",
+                    'f' => "float",
+                    other => panic!("不支持的类型编码: {}", other),
+                }
+                .to_string()
+            }
+        };
+        subs.push(ty.clone());
+        ty
+    }
+
+    /// 解析 `<长度><名称>`，`first` 是长度的第一位
+    fn source_name(first: char, chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+        let mut len = first.to_digit(10).unwrap() as usize;
+        while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+            len = len * 10 + digit as usize;
+            chars.next();
+        }
+        chars.by_ref().take(len).collect()
+    }
+
+    /// 解析 Itanium C++ ABI 修饰名中的函数名和参数类型（只支持这里用到的子集）
+    fn demangle(link_name: &str) -> (String, Vec<String>) {
+        let mangled = link_name.trim_start_matches('\u{1}');
+        let mut chars = mangled
+            .strip_prefix("_Z")
+            .expect("不是修饰名")
+            .chars()
+            .peekable();
+        let first = chars.next().expect("修饰名不完整");
+        let name = source_name(first, &mut chars);
+        let mut subs = Vec::new();
+        let mut params = Vec::new();
+        while chars.peek().is_some() {
+            params.push(demangle_type(&mut chars, &mut subs));
+        }
+        if params == ["void"] {
+            params.clear();
+        }
+        (name, params)
+    }
+
+    #[test]
+    fn test_signatures_match_header() {
+        let header = parse_header(&read_header("kmboxAI.h"), |name| name.starts_with("kmAI_"));
+        assert_eq!(to_map(KMAI_SIGNATURES), header);
+    }
+
+    #[test]
+    fn test_keyboard_table_signatures_match_header() {
+        let header = parse_header(&read_header("KeyboardTable.h"), |_| true);
+        assert_eq!(to_map(KEYBOARD_TABLE_SIGNATURES), header);
+    }
+
+    #[test]
+    fn test_demangle() {
+        assert_eq!(demangle("_Z9showTablev"), ("showTable".to_string(), vec![]));
+        assert_eq!(
+            demangle("\u{1}_Z13soft_keypressiPKci"),
+            (
+                "soft_keypress".to_string(),
+                vec![
+                    "int".to_string(),
+                    "const char*".to_string(),
+                    "int".to_string()
+                ]
+            )
+        );
+        // S0_ 引用第二个可替换类型 `image_buffer_t*`
+        let (_, params) = demangle("_Z20convert_image_formatP14image_buffer_tS0_");
+        assert_eq!(params, vec!["image_buffer_t*", "image_buffer_t*"]);
+    }
+
+    #[test]
+    fn test_mangled_signatures() {
+        for (link_name, name, params) in MANGLED_SIGNATURES {
+            let (mangled_name, mangled_params) = demangle(link_name);
+            assert_eq!(&mangled_name, name, "{} 的修饰名", name);
+            assert_eq!(mangled_params, *params, "{} 的参数", name);
+        }
+    }
+
+    #[test]
+    fn test_mangled_functions_are_not_in_headers() {
+        // 厂商在头文件中声明这些函数后，应改为由 bindgen 生成
+        let headers: Vec<String> = std::fs::read_dir(INCLUDE_DIR)
+            .unwrap()
+            .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        for (_, name, _) in MANGLED_SIGNATURES {
+            let call = format!("{}(", name);
+            assert!(
+                headers.iter().all(|header| !header.contains(&call)),
+                "{} 已在头文件中声明",
+                name
+            );
+        }
+    }
+}
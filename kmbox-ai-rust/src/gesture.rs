@@ -0,0 +1,380 @@
+//! 手势模块
+//!
+//! 在 `mouse_button`、`mouse_move`、`mouse_wheel` 之上组合出常用的高层操作：
+//! 拖放、双击/三击、长按和平滑滚动。
+//!
+//! [`GesturePlanner`] 先把手势规划为带发送时刻的报告序列（[`GestureStep`]），
+//! 再由 [`KmboxAI::perform_gesture`](crate::kmbox_ai::KmboxAI::perform_gesture) 按时刻逐个发送，
+//! 报告之间的间隔由规划器的配置决定，主机端的 GUI 测试可以稳定识别。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::gesture::GesturePlanner;
+//! use kmbox_ai_rust::kmbox_ai::{KmboxAI, MouseButton};
+//! use std::time::Duration;
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let kmbox = KmboxAI::new()?;
+//! let gestures = GesturePlanner::new().with_click_interval(Duration::from_millis(60));
+//!
+//! kmbox.drag((10, 10), (300, 10), MouseButton::Left, &gestures)?;
+//! kmbox.multi_click(MouseButton::Left, 2, &gestures)?;
+//! kmbox.long_press(MouseButton::Right, &gestures)?;
+//! kmbox.scroll_lines(-9, &gestures)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::{KmboxError, KmboxResult};
+use crate::failsafe::HeldTracker;
+use crate::kmbox_ai::MouseButton;
+use crate::motion::MotionPlanner;
+use crate::scheduler::OutputReport;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 手势中的一个报告
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GestureStep {
+    /// 相对手势开始的发送时间
+    pub at: Duration,
+    /// 发送的报告
+    pub report: OutputReport,
+}
+
+/// 手势规划器
+#[derive(Debug, Clone, PartialEq)]
+pub struct GesturePlanner {
+    press_duration: Duration,
+    click_interval: Duration,
+    long_press: Duration,
+    settle: Duration,
+    wheel_interval: Duration,
+    lines_per_notch: u32,
+    motion: MotionPlanner,
+}
+
+impl Default for GesturePlanner {
+    fn default() -> Self {
+        Self {
+            press_duration: Duration::from_millis(50),
+            click_interval: Duration::from_millis(80),
+            long_press: Duration::from_millis(800),
+            settle: Duration::from_millis(50),
+            wheel_interval: Duration::from_millis(15),
+            lines_per_notch: 3,
+            motion: MotionPlanner::new().with_duration(Duration::from_millis(200)),
+        }
+    }
+}
+
+/// 规划结果的构造器，维护当前时刻
+struct Timeline {
+    now: Duration,
+    steps: Vec<GestureStep>,
+}
+
+impl Timeline {
+    fn new() -> Self {
+        Self {
+            now: Duration::ZERO,
+            steps: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, report: OutputReport) {
+        self.steps.push(GestureStep {
+            at: self.now,
+            report,
+        });
+    }
+
+    fn wait(&mut self, duration: Duration) {
+        self.now += duration;
+    }
+
+    fn button(&mut self, button: MouseButton, down: bool) {
+        self.push(OutputReport::Button { button, down });
+    }
+
+    /// 按移动规划追加位移，结束时刻为最后一个位移报告的时刻
+    fn motion(&mut self, planner: &MotionPlanner, dx: i32, dy: i32) {
+        let start = self.now;
+        for step in planner.plan(dx, dy) {
+            self.now = start + step.at;
+            self.push(OutputReport::Move {
+                dx: step.dx as i32,
+                dy: step.dy as i32,
+            });
+        }
+    }
+}
+
+impl GesturePlanner {
+    /// 创建规划器：按下 50ms、连击间隔 80ms、长按 800ms、拖放停顿 50ms，
+    /// 滚轮每格间隔 15ms、每格 3 行，拖动用时 200ms
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置单次点击按下的时长
+    pub fn with_press_duration(mut self, duration: Duration) -> Self {
+        self.press_duration = duration;
+        self
+    }
+
+    /// 设置连击时上一次释放到下一次按下的间隔
+    pub fn with_click_interval(mut self, interval: Duration) -> Self {
+        self.click_interval = interval;
+        self
+    }
+
+    /// 设置长按的时长
+    pub fn with_long_press(mut self, duration: Duration) -> Self {
+        self.long_press = duration;
+        self
+    }
+
+    /// 设置拖放时按下前后、释放前的停顿
+    pub fn with_settle(mut self, settle: Duration) -> Self {
+        self.settle = settle;
+        self
+    }
+
+    /// 设置平滑滚动时相邻两格之间的间隔
+    pub fn with_wheel_interval(mut self, interval: Duration) -> Self {
+        self.wheel_interval = interval;
+        self
+    }
+
+    /// 设置滚轮每格滚动的行数
+    pub fn with_lines_per_notch(mut self, lines: u32) -> KmboxResult<Self> {
+        if lines == 0 {
+            return Err(KmboxError::ParameterError(
+                "每格滚动行数不能为0".to_string(),
+            ));
+        }
+        self.lines_per_notch = lines;
+        Ok(self)
+    }
+
+    /// 设置拖动和移动到起点时使用的移动规划
+    pub fn with_motion(mut self, motion: MotionPlanner) -> Self {
+        self.motion = motion;
+        self
+    }
+
+    /// 连续点击 `count` 次，`count` 为 0 时不产生报告
+    pub fn click(&self, button: MouseButton, count: u32) -> Vec<GestureStep> {
+        let mut timeline = Timeline::new();
+        for index in 0..count {
+            if index > 0 {
+                timeline.wait(self.click_interval);
+            }
+            timeline.button(button, true);
+            timeline.wait(self.press_duration);
+            timeline.button(button, false);
+        }
+        timeline.steps
+    }
+
+    /// 长按
+    pub fn long_press(&self, button: MouseButton) -> Vec<GestureStep> {
+        let mut timeline = Timeline::new();
+        timeline.button(button, true);
+        timeline.wait(self.long_press);
+        timeline.button(button, false);
+        timeline.steps
+    }
+
+    /// 拖放
+    ///
+    /// `from` 和 `to` 都是相对当前光标位置的位移：先移动到 `from` 按下按键，
+    /// 拖动到 `to` 后释放。
+    pub fn drag(&self, from: (i32, i32), to: (i32, i32), button: MouseButton) -> Vec<GestureStep> {
+        let mut timeline = Timeline::new();
+        timeline.motion(&self.motion, from.0, from.1);
+        if !timeline.steps.is_empty() {
+            timeline.wait(self.settle);
+        }
+        timeline.button(button, true);
+        timeline.wait(self.settle);
+        timeline.motion(&self.motion, to.0 - from.0, to.1 - from.1);
+        timeline.wait(self.settle);
+        timeline.button(button, false);
+        timeline.steps
+    }
+
+    /// 平滑滚动 `notches` 格，每格一个报告，正数向上
+    pub fn scroll(&self, notches: i32) -> Vec<GestureStep> {
+        let mut timeline = Timeline::new();
+        for index in 0..notches.unsigned_abs() {
+            if index > 0 {
+                timeline.wait(self.wheel_interval);
+            }
+            timeline.push(OutputReport::Wheel(notches.signum()));
+        }
+        timeline.steps
+    }
+
+    /// 按行数滚动，不足一格的部分按一格计算
+    pub fn scroll_lines(&self, lines: i32) -> Vec<GestureStep> {
+        let per_notch = self.lines_per_notch;
+        let notches = lines.unsigned_abs().div_ceil(per_notch) as i32;
+        self.scroll(notches * lines.signum())
+    }
+}
+
+/// 按发送时刻依次发送手势报告
+///
+/// 发送失败时释放本次手势按下但尚未释放的按键。
+pub(crate) fn perform(
+    backend: &dyn Backend,
+    held: &HeldTracker,
+    steps: &[GestureStep],
+) -> KmboxResult<()> {
+    let start = Instant::now();
+    let mut pressed = Vec::new();
+    for step in steps {
+        if let Some(delay) = (start + step.at).checked_duration_since(Instant::now()) {
+            thread::sleep(delay);
+        }
+        if let Err(e) = step.report.send(backend) {
+            for &button in &pressed {
+                if backend.mouse_button(button, false) == 0 {
+                    held.set_button(button, false);
+                }
+            }
+            return Err(e);
+        }
+        match step.report {
+            OutputReport::Button { button, down } => {
+                held.set_button(button, down);
+                pressed.retain(|&held| held != button);
+                if down {
+                    pressed.push(button);
+                }
+            }
+            OutputReport::Key { vkey, down } => held.set_key(vkey, down),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
+    use crate::kmbox_ai::KmboxAI;
+    use std::sync::Arc;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn button(at: u64, down: bool) -> GestureStep {
+        GestureStep {
+            at: ms(at),
+            report: OutputReport::Button {
+                button: MouseButton::Left,
+                down,
+            },
+        }
+    }
+
+    #[test]
+    fn test_click_timing() {
+        let planner = GesturePlanner::new()
+            .with_press_duration(ms(20))
+            .with_click_interval(ms(100));
+        assert_eq!(
+            planner.click(MouseButton::Left, 2),
+            [
+                button(0, true),
+                button(20, false),
+                button(120, true),
+                button(140, false)
+            ]
+        );
+        assert_eq!(planner.click(MouseButton::Left, 3).len(), 6);
+        assert!(planner.click(MouseButton::Left, 0).is_empty());
+
+        let long = planner
+            .with_long_press(ms(1000))
+            .long_press(MouseButton::Left);
+        assert_eq!(long, [button(0, true), button(1000, false)]);
+    }
+
+    #[test]
+    fn test_drag_plan() {
+        let motion = MotionPlanner::new().with_duration(ms(10));
+        let planner = GesturePlanner::new()
+            .with_settle(ms(30))
+            .with_motion(motion);
+        let steps = planner.drag((5, 0), (105, -20), MouseButton::Left);
+
+        let sum = |steps: &[GestureStep]| {
+            steps.iter().fold((0, 0), |(x, y), step| match step.report {
+                OutputReport::Move { dx, dy } => (x + dx, y + dy),
+                _ => (x, y),
+            })
+        };
+        let down = steps
+            .iter()
+            .position(|step| matches!(step.report, OutputReport::Button { down: true, .. }))
+            .unwrap();
+        let up = steps.len() - 1;
+        assert_eq!(sum(&steps[..down]), (5, 0));
+        assert_eq!(sum(&steps[down..]), (100, -20));
+        assert!(matches!(
+            steps[up].report,
+            OutputReport::Button { down: false, .. }
+        ));
+        // 按下前后、释放前都有停顿，时刻单调不减
+        assert_eq!(steps[down].at, steps[down - 1].at + ms(30));
+        assert_eq!(steps[down + 1].at, steps[down].at + ms(30));
+        assert_eq!(steps[up].at, steps[up - 1].at + ms(30));
+        assert!(steps.windows(2).all(|pair| pair[0].at <= pair[1].at));
+    }
+
+    #[test]
+    fn test_scroll_lines() {
+        let planner = GesturePlanner::new()
+            .with_wheel_interval(ms(10))
+            .with_lines_per_notch(3)
+            .unwrap();
+        let steps = planner.scroll_lines(-7);
+        assert_eq!(steps.len(), 3);
+        assert!(steps
+            .iter()
+            .all(|step| step.report == OutputReport::Wheel(-1)));
+        assert_eq!(steps[2].at, ms(20));
+        assert!(planner.scroll_lines(0).is_empty());
+        assert!(GesturePlanner::new().with_lines_per_notch(0).is_err());
+    }
+
+    #[test]
+    fn test_perform_sends_in_order() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        let planner = GesturePlanner::new()
+            .with_press_duration(ms(1))
+            .with_click_interval(ms(1));
+
+        let start = Instant::now();
+        kmbox.multi_click(MouseButton::Right, 2, &planner).unwrap();
+        assert!(start.elapsed() >= ms(3));
+        let right = |down| HidReport::Button {
+            button: MouseButton::Right,
+            down,
+        };
+        assert_eq!(
+            sim.reports(),
+            [right(true), right(false), right(true), right(false)]
+        );
+        assert!(kmbox.held_inputs().is_empty());
+    }
+}
@@ -0,0 +1,528 @@
+//! 共享设备句柄模块
+//!
+//! [`KmboxHandle`] 可以克隆并在多个线程间共享（`Send + Sync`），
+//! 所有调用都通过队列交给同一个写线程按顺序执行，避免多个线程同时调用厂商库的 HID 接口。
+//! 句柄记录每次调用的排队与执行耗时以及队列深度，见 [`HandleStats`]。
+//!
+//! 最后一个句柄销毁时，写线程执行完队列中剩余的调用，
+//! 释放通过句柄按下但尚未释放的按键后退出。
+//!
+//! [`KmboxAI`](crate::kmbox_ai::KmboxAI) 创建时启动自己的写线程，键鼠调用都通过这个线程执行；
+//! [`KmboxAI::handle`](crate::kmbox_ai::KmboxAI::handle) 返回的句柄与 `KmboxAI` 共用同一个写线程、
+//! 按键记录和屏蔽记录，句柄设置的屏蔽同样会在 `KmboxAI` 销毁或 panic 时解除，并在设备重新初始化后重新设置。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::kmbox_ai::{KmboxAI, MouseButton, MouseAction};
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let kmbox = KmboxAI::new()?;
+//! let handle = kmbox.handle();
+//!
+//! let vision = handle.clone();
+//! std::thread::spawn(move || vision.mouse_move(10, 0));
+//! handle.mouse_button(MouseButton::Left, MouseAction::Click)?;
+//!
+//! println!("{:?}", handle.stats());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::{check_result, check_state, KmboxError, KmboxResult, Operation};
+use crate::failsafe::HeldTracker;
+use crate::kmbox_ai::{read_mouse_state, MouseAction, MouseButton, MouseMask, MouseState};
+use crate::mask::{MaskTarget, MaskTracker};
+use crate::report::MouseReport;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 点击时按下与释放之间的间隔
+const CLICK_DELAY: Duration = Duration::from_millis(50);
+
+/// 写线程执行的调用
+type Job = Box<dyn FnOnce(&Writer) + Send>;
+
+/// 写线程持有的状态
+pub(crate) struct Writer {
+    pub(crate) backend: Arc<dyn Backend>,
+    pub(crate) held: Arc<HeldTracker>,
+    pub(crate) masks: Arc<MaskTracker>,
+}
+
+/// 调用统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HandleStats {
+    /// 已完成的调用次数
+    pub calls: u64,
+    /// 当前排队等待执行的调用数量
+    pub queue_depth: usize,
+    /// 出现过的最大队列深度
+    pub max_queue_depth: usize,
+    /// 最近一次调用从提交到完成的耗时
+    pub last_latency: Duration,
+    /// 所有调用的平均耗时
+    pub mean_latency: Duration,
+    /// 单次调用的最大耗时
+    pub max_latency: Duration,
+    /// 最近一次调用在队列中等待的时间
+    pub last_queue_wait: Duration,
+}
+
+/// 耗时统计的累计值
+#[derive(Debug, Default)]
+struct Latency {
+    calls: u64,
+    total: Duration,
+    last: Duration,
+    max: Duration,
+    last_queue_wait: Duration,
+    max_queue_depth: usize,
+}
+
+struct Inner {
+    sender: Option<Sender<Job>>,
+    thread: Option<JoinHandle<()>>,
+    queue_depth: Arc<AtomicUsize>,
+    latency: Arc<Mutex<Latency>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        // 关闭队列，写线程处理完剩余调用后退出
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// 可在线程间共享的设备句柄
+#[derive(Clone)]
+pub struct KmboxHandle {
+    inner: Arc<Inner>,
+}
+
+impl KmboxHandle {
+    /// 在指定后端上启动写线程并创建句柄，按键记录和屏蔽记录只属于该句柄
+    pub fn new(backend: Arc<dyn Backend>) -> KmboxResult<Self> {
+        let held = HeldTracker::new(backend.clone());
+        let masks = Arc::new(MaskTracker::new(backend.clone()));
+        Self::with_trackers(backend, held, masks)
+    }
+
+    /// 启动写线程并创建通过 `held` 记录按键、通过 `masks` 设置屏蔽的句柄
+    pub(crate) fn with_trackers(
+        backend: Arc<dyn Backend>,
+        held: Arc<HeldTracker>,
+        masks: Arc<MaskTracker>,
+    ) -> KmboxResult<Self> {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let queue_depth = Arc::new(AtomicUsize::new(0));
+        let latency = Arc::new(Mutex::new(Latency::default()));
+
+        let depth = queue_depth.clone();
+        let thread = thread::Builder::new()
+            .name("kmbox-writer".to_string())
+            .spawn(move || {
+                let writer = Writer {
+                    held,
+                    backend,
+                    masks,
+                };
+                for job in receiver {
+                    depth.fetch_sub(1, Ordering::AcqRel);
+                    job(&writer);
+                }
+                if !writer.held.release_all().is_empty() {
+                    writer.backend.key_init();
+                }
+            })
+            .map_err(|e| KmboxError::io("启动写线程", e))?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                sender: Some(sender),
+                thread: Some(thread),
+                queue_depth,
+                latency,
+            }),
+        })
+    }
+
+    fn latency(&self) -> MutexGuard<'_, Latency> {
+        self.inner.latency.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 当前调用统计
+    pub fn stats(&self) -> HandleStats {
+        let latency = self.latency();
+        HandleStats {
+            calls: latency.calls,
+            queue_depth: self.inner.queue_depth.load(Ordering::Acquire),
+            max_queue_depth: latency.max_queue_depth,
+            last_latency: latency.last,
+            mean_latency: latency
+                .total
+                .checked_div(latency.calls.max(1) as u32)
+                .unwrap_or_default(),
+            max_latency: latency.max,
+            last_queue_wait: latency.last_queue_wait,
+        }
+    }
+
+    /// 清零调用统计
+    pub fn reset_stats(&self) {
+        *self.latency() = Latency::default();
+    }
+
+    /// 把调用放入写线程的队列，执行完成后把结果交给 `reply`
+    pub(crate) fn dispatch<T, F, R>(&self, call: F, reply: R) -> KmboxResult<()>
+    where
+        F: FnOnce(&Writer) -> T + Send + 'static,
+        R: FnOnce(T) + Send + 'static,
+    {
+        let sender = self.inner.sender.as_ref().ok_or_else(writer_closed)?;
+        let latency = self.inner.latency.clone();
+        let submitted = Instant::now();
+
+        let job: Job = Box::new(move |writer| {
+            let started = Instant::now();
+            let value = call(writer);
+            let finished = Instant::now();
+            {
+                let mut latency = latency.lock().unwrap_or_else(|e| e.into_inner());
+                let elapsed = finished - submitted;
+                latency.calls += 1;
+                latency.total += elapsed;
+                latency.last = elapsed;
+                latency.max = latency.max.max(elapsed);
+                latency.last_queue_wait = started - submitted;
+            }
+            reply(value);
+        });
+
+        let depth = self.inner.queue_depth.fetch_add(1, Ordering::AcqRel) + 1;
+        {
+            let mut latency = self.latency();
+            latency.max_queue_depth = latency.max_queue_depth.max(depth);
+        }
+        if sender.send(job).is_err() {
+            self.inner.queue_depth.fetch_sub(1, Ordering::AcqRel);
+            return Err(writer_closed());
+        }
+        Ok(())
+    }
+
+    /// 在写线程中执行调用并等待结果
+    pub(crate) fn submit<T, F>(&self, call: F) -> KmboxResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Writer) -> T + Send + 'static,
+    {
+        let (reply, result) = mpsc::sync_channel(1);
+        self.dispatch(call, move |value| {
+            let _ = reply.send(value);
+        })?;
+        result.recv().map_err(|_| writer_closed())
+    }
+
+    /// 在写线程中直接使用后端
+    ///
+    /// 适用于句柄没有封装的接口；调用期间其他线程的调用会排队等待。
+    pub fn with_backend<T, F>(&self, call: F) -> KmboxResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn Backend) -> T + Send + 'static,
+    {
+        self.submit(move |writer| call(writer.backend.as_ref()))
+    }
+
+    /// 鼠标相对移动
+    pub fn mouse_move(&self, x: i32, y: i32) -> KmboxResult<()> {
+        let (x, y) = (to_short(x)?, to_short(y)?);
+        self.submit(move |writer| writer.mouse_move(x, y))?
+    }
+
+    /// 鼠标按键操作
+    ///
+    /// 点击时按下和释放作为一次调用执行，中间不会插入其他线程的调用。
+    pub fn mouse_button(&self, button: MouseButton, action: MouseAction) -> KmboxResult<()> {
+        self.submit(move |writer| writer.mouse_button(button, action))?
+    }
+
+    /// 以一个报告同时发送按键、位移和滚轮
+    pub fn send_mouse_report(&self, report: MouseReport) -> KmboxResult<()> {
+        self.submit(move |writer| writer.mouse_report(&report))?
+    }
+
+    /// 鼠标滚轮操作
+    pub fn mouse_wheel(&self, wheel: i32) -> KmboxResult<()> {
+        self.submit(move |writer| writer.mouse_wheel(wheel))?
+    }
+
+    /// 键盘按键按下
+    pub fn key_down(&self, vkey: i32) -> KmboxResult<()> {
+        self.submit(move |writer| writer.key(vkey, true))?
+    }
+
+    /// 键盘按键释放
+    pub fn key_up(&self, vkey: i32) -> KmboxResult<()> {
+        self.submit(move |writer| writer.key(vkey, false))?
+    }
+
+    /// 初始化键盘
+    pub fn key_init(&self) -> KmboxResult<()> {
+        self.submit(|writer| writer.key_init())?
+    }
+
+    /// 监控键盘按键
+    pub fn monitor_keyboard(&self, vk_key: i16) -> KmboxResult<bool> {
+        self.submit(move |writer| writer.monitor_keyboard(vk_key))?
+    }
+
+    /// 获取完整鼠标状态
+    pub fn get_mouse_state(&self) -> KmboxResult<MouseState> {
+        self.submit(|writer| read_mouse_state(writer.backend.as_ref()))?
+    }
+
+    /// 掩码鼠标输入
+    pub fn mask_mouse(&self, mask: MouseMask, enable: bool) -> KmboxResult<()> {
+        self.submit(move |writer| writer.masks.set_manual(MaskTarget::Mouse(mask), enable))?
+    }
+
+    /// 掩码键盘按键
+    pub fn mask_keyboard(&self, vkey: i16) -> KmboxResult<()> {
+        self.submit(move |writer| writer.masks.set_manual(MaskTarget::Keyboard(vkey), true))?
+    }
+
+    /// 取消掩码键盘按键
+    pub fn unmask_keyboard(&self, vkey: i16) -> KmboxResult<()> {
+        self.submit(move |writer| writer.masks.set_manual(MaskTarget::Keyboard(vkey), false))?
+    }
+
+    /// 取消所有掩码
+    pub fn unmask_all(&self) -> KmboxResult<()> {
+        self.submit(|writer| {
+            check_result(writer.backend.unmask_all(), Operation::UnmaskAll)?;
+            writer.masks.clear();
+            Ok(())
+        })?
+    }
+}
+
+pub(crate) fn writer_closed() -> KmboxError {
+    KmboxError::SystemError("写线程已退出".to_string())
+}
+
+/// 检查鼠标位移是否在 `short` 范围内
+pub(crate) fn to_short(value: i32) -> KmboxResult<i16> {
+    i16::try_from(value)
+        .map_err(|_| KmboxError::ParameterError(format!("鼠标位移超出范围: {}", value)))
+}
+
+impl Writer {
+    pub(crate) fn mouse_move(&self, x: i16, y: i16) -> KmboxResult<()> {
+        check_result(self.backend.mouse_move(x, y), Operation::MouseMove)
+    }
+
+    pub(crate) fn mouse_button(&self, button: MouseButton, action: MouseAction) -> KmboxResult<()> {
+        let press = |down: bool| {
+            let result = self.backend.mouse_button(button, down);
+            check_result(result, Operation::MouseButton)?;
+            self.held.set_button(button, down);
+            Ok(())
+        };
+        match action {
+            MouseAction::Press => press(true),
+            MouseAction::Release => press(false),
+            MouseAction::Click => {
+                press(true)?;
+                thread::sleep(CLICK_DELAY);
+                press(false)
+            }
+        }
+    }
+
+    pub(crate) fn mouse_report(&self, report: &MouseReport) -> KmboxResult<()> {
+        let (buttons, x, y, wheel) = report.to_args();
+        check_result(
+            self.backend.mouse_all(buttons, x, y, wheel),
+            Operation::MouseAll,
+        )?;
+        for button in MouseButton::ALL {
+            self.held
+                .set_button(button, report.buttons.contains(button));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn mouse_wheel(&self, wheel: i32) -> KmboxResult<()> {
+        check_result(self.backend.mouse_wheel(wheel), Operation::MouseWheel)
+    }
+
+    pub(crate) fn key(&self, vkey: i32, down: bool) -> KmboxResult<()> {
+        if down {
+            check_result(self.backend.key_down(vkey), Operation::KeyDown)?;
+        } else {
+            check_result(self.backend.key_up(vkey), Operation::KeyUp)?;
+        }
+        self.held.set_key(vkey, down);
+        Ok(())
+    }
+
+    pub(crate) fn key_init(&self) -> KmboxResult<()> {
+        check_result(self.backend.key_init(), Operation::KeyInit)?;
+        self.held.clear_keys();
+        Ok(())
+    }
+
+    pub(crate) fn monitor_keyboard(&self, vk_key: i16) -> KmboxResult<bool> {
+        check_state(
+            self.backend.monitor_keyboard(vk_key),
+            Operation::MonitorKeyboard,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
+    use crate::kmbox_ai::KmboxAI;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_handle_and_kmbox_are_send_sync() {
+        assert_send_sync::<KmboxHandle>();
+        assert_send_sync::<KmboxAI>();
+    }
+
+    #[test]
+    fn test_concurrent_calls_are_serialized() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let handle = KmboxHandle::new(sim.clone()).unwrap();
+
+        let threads: Vec<_> = (1..=4)
+            .map(|id| {
+                let handle = handle.clone();
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        handle.mouse_move(id, -id).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let reports = sim.reports();
+        assert_eq!(reports.len(), 400);
+        for id in 1..=4 {
+            let count = reports
+                .iter()
+                .filter(|report| **report == HidReport::Move { x: id, y: -id })
+                .count();
+            assert_eq!(count, 100);
+        }
+
+        let stats = handle.stats();
+        assert_eq!(stats.calls, 400);
+        assert_eq!(stats.queue_depth, 0);
+        assert!(stats.max_queue_depth >= 1);
+        assert!(stats.max_latency >= stats.mean_latency);
+
+        handle.reset_stats();
+        assert_eq!(handle.stats(), HandleStats::default());
+    }
+
+    #[test]
+    fn test_masks_shared_with_kmbox() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        let handle = kmbox.handle();
+
+        handle.mask_keyboard(4).unwrap();
+        handle.mask_mouse(MouseMask::Left, true).unwrap();
+        assert_eq!(
+            kmbox.active_masks(),
+            vec![MaskTarget::Mouse(MouseMask::Left), MaskTarget::Keyboard(4)]
+        );
+        handle.unmask_keyboard(4).unwrap();
+        assert_eq!(
+            kmbox.active_masks(),
+            vec![MaskTarget::Mouse(MouseMask::Left)]
+        );
+
+        sim.inject_fault(-4);
+        assert_eq!(handle.mask_keyboard(5).unwrap_err().code(), Some(-4));
+        sim.clear_fault();
+
+        // 句柄设置的屏蔽在 KmboxAI 销毁时解除
+        handle.mask_keyboard(6).unwrap();
+        drop(kmbox);
+        let state = sim.state();
+        assert!(state.mouse_masks.is_empty());
+        assert!(state.keyboard_masks.is_empty());
+    }
+
+    #[test]
+    fn test_kmbox_calls_share_writer() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        let handle = kmbox.handle();
+
+        kmbox.mouse_move(1, 0).unwrap();
+        kmbox.key_down(4).unwrap();
+        kmbox.monitor_mouse_left().unwrap();
+        handle.mouse_move(2, 0).unwrap();
+        assert_eq!(handle.stats().calls, 4);
+        assert_eq!(kmbox.handle().stats().calls, 4);
+
+        // 按键记录也是共用的
+        assert_eq!(
+            kmbox.held_inputs().keys.into_iter().collect::<Vec<_>>(),
+            vec![4]
+        );
+        handle.key_up(4).unwrap();
+        assert!(kmbox.held_inputs().keys.is_empty());
+    }
+
+    #[test]
+    fn test_errors_and_held_inputs_released() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let handle = KmboxHandle::new(sim.clone()).unwrap();
+        assert!(handle.mouse_move(40_000, 0).is_err());
+        assert_eq!(
+            handle.with_backend(|backend| backend.version()).unwrap(),
+            sim.version()
+        );
+
+        handle
+            .mouse_button(MouseButton::Middle, MouseAction::Press)
+            .unwrap();
+        handle.key_down(4).unwrap();
+        let clone = handle.clone();
+        drop(handle);
+        // 仍有句柄存活时不释放
+        assert_eq!(sim.reports().len(), 2);
+        drop(clone);
+        assert_eq!(
+            sim.reports()[2..],
+            [
+                HidReport::Button {
+                    button: MouseButton::Middle,
+                    down: false
+                },
+                HidReport::KeyUp(4),
+                HidReport::KeyInit,
+            ]
+        );
+    }
+}
@@ -0,0 +1,739 @@
+//! 设备健康监控模块
+//!
+//! USB 链路或固件出现故障时，所有 `kmAI_*` 调用都会开始失败。
+//! [`HealthSupervisor`] 定期调用 `kmAI_Version` 探测设备，连续失败达到阈值后判定设备丢失，
+//! 按退避间隔重新执行 `kmAI_Init`，成功后依次恢复：
+//!
+//! 1. `run` 和 `enable` 的状态；
+//! 2. 通过 `KmboxAI` 设置的物理键鼠屏蔽；
+//! 3. 已加载的YOLO模型；
+//! 4. 通过 [`HealthSupervisor::on_reinit`] 注册的恢复函数。
+//!
+//! 恢复过程中设备调用返回的错误（包括 `-1`）使本次重新初始化失败，按退避间隔从 `kmAI_Init` 开始重试；
+//! 只有重试也无法解决的配置错误（如模型文件无效、参数无效）以 [`HealthEvent::RestoreFailed`] 报告，
+//! 不影响其余设置的恢复。
+//!
+//! 状态变化以 [`HealthEvent`] 通知。状态机由 [`HealthSupervisor::step`] 驱动，
+//! 后台线程只负责按间隔调用它，测试中可以配合
+//! [`SimulatedBackend::inject_fault`](crate::backend::SimulatedBackend::inject_fault) 手动单步执行。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::health::{HealthConfig, HealthEvent};
+//! use kmbox_ai_rust::kmbox_ai::KmboxAI;
+//! use std::time::Duration;
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let mut kmbox = KmboxAI::new()?;
+//! kmbox.init()?;
+//! kmbox.enable(true)?;
+//!
+//! let config = HealthConfig::new()
+//!     .with_probe_interval(Duration::from_millis(500))
+//!     .with_failure_threshold(3)?;
+//! let mut supervisor = kmbox.health_supervisor(config);
+//! supervisor.on_event(|event| match event {
+//!     HealthEvent::Lost { error } => eprintln!("设备丢失: {} ({})", error, error.name()),
+//!     HealthEvent::Recovered { attempts, .. } => eprintln!("设备已恢复，重试 {} 次", attempts),
+//!     _ => {}
+//! });
+//! supervisor.start()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::{check_result, ErrorCode, KmboxError, KmboxResult, Operation};
+use crate::kmbox_ai::DeviceSettings;
+use crate::mask::MaskTracker;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// 健康监控参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthConfig {
+    /// 设备正常时的探测间隔
+    pub probe_interval: Duration,
+    /// 连续探测失败多少次判定设备丢失
+    pub failure_threshold: u32,
+    /// 第一次重新初始化失败后的等待时间，之后每次翻倍
+    pub reinit_interval: Duration,
+    /// 重新初始化的最长等待时间
+    pub max_reinit_interval: Duration,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            probe_interval: Duration::from_secs(1),
+            failure_threshold: 3,
+            reinit_interval: Duration::from_millis(500),
+            max_reinit_interval: Duration::from_secs(10),
+        }
+    }
+}
+
+impl HealthConfig {
+    /// 创建默认参数：每秒探测一次，连续失败 3 次判定丢失，重新初始化间隔 0.5s 起、最长 10s
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置探测间隔
+    pub fn with_probe_interval(mut self, interval: Duration) -> Self {
+        self.probe_interval = interval;
+        self
+    }
+
+    /// 设置判定设备丢失的连续失败次数
+    pub fn with_failure_threshold(mut self, threshold: u32) -> KmboxResult<Self> {
+        if threshold == 0 {
+            return Err(KmboxError::ParameterError(
+                "连续失败次数阈值不能为0".to_string(),
+            ));
+        }
+        self.failure_threshold = threshold;
+        Ok(self)
+    }
+
+    /// 设置重新初始化的退避间隔
+    pub fn with_reinit_backoff(mut self, initial: Duration, max: Duration) -> KmboxResult<Self> {
+        if max < initial {
+            return Err(KmboxError::ParameterError(
+                "最长重新初始化间隔不能小于初始间隔".to_string(),
+            ));
+        }
+        self.reinit_interval = initial;
+        self.max_reinit_interval = max;
+        Ok(self)
+    }
+
+    /// 处于 `state` 时距离下一次 [`HealthSupervisor::step`] 的等待时间
+    ///
+    /// 判定丢失后立即重新初始化，第 n 次失败后等待 `reinit_interval * 2^(n-1)`，不超过最长间隔。
+    pub fn delay(&self, state: HealthState) -> Duration {
+        match state {
+            HealthState::Healthy | HealthState::Degraded { .. } => self.probe_interval,
+            HealthState::Recovering { attempts: 0 } => Duration::ZERO,
+            HealthState::Recovering { attempts } => {
+                let factor = 1u32.checked_shl(attempts - 1).unwrap_or(u32::MAX);
+                self.reinit_interval
+                    .saturating_mul(factor)
+                    .min(self.max_reinit_interval)
+            }
+        }
+    }
+}
+
+/// 设备健康状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthState {
+    /// 设备正常
+    Healthy,
+    /// 探测失败，尚未达到阈值
+    Degraded {
+        /// 连续失败次数
+        failures: u32,
+    },
+    /// 设备丢失，正在重新初始化
+    Recovering {
+        /// 已经失败的重新初始化次数
+        attempts: u32,
+    },
+}
+
+impl HealthState {
+    /// 设备是否正常
+    pub fn is_healthy(&self) -> bool {
+        *self == HealthState::Healthy
+    }
+}
+
+/// 健康状态变化事件
+#[derive(Debug, Clone)]
+pub enum HealthEvent {
+    /// 探测失败，尚未达到阈值
+    Degraded {
+        /// 连续失败次数
+        failures: u32,
+        /// 探测错误
+        error: Arc<KmboxError>,
+    },
+    /// 连续失败达到阈值，开始重新初始化
+    Lost {
+        /// 最后一次探测错误
+        error: Arc<KmboxError>,
+    },
+    /// 一次重新初始化失败，将在退避间隔后重试
+    ReinitFailed {
+        /// 第几次重新初始化
+        attempt: u32,
+        /// 失败原因
+        error: Arc<KmboxError>,
+    },
+    /// 设备已恢复，但某项设置无法恢复（重试也不会成功的错误，如模型文件无效）
+    RestoreFailed {
+        /// 失败原因
+        error: Arc<KmboxError>,
+    },
+    /// 设备恢复正常
+    Recovered {
+        /// 重新初始化的次数，探测失败后未经重新初始化就恢复时为 0
+        attempts: u32,
+        /// 设备版本号
+        version: i32,
+    },
+}
+
+type EventCallback = Box<dyn FnMut(&HealthEvent) + Send>;
+type ReinitHook = Box<dyn FnMut() -> KmboxResult<()> + Send>;
+
+/// 事件的接收方
+enum Sink {
+    Callback(EventCallback),
+    Channel(Sender<HealthEvent>),
+}
+
+/// 探测设备，返回版本号；负数返回值视为错误码
+fn probe(backend: &dyn Backend) -> KmboxResult<i32> {
+    let version = backend.version();
+    if version < 0 {
+        return Err(KmboxError::call(Operation::Version, version));
+    }
+    Ok(version)
+}
+
+/// 监控与后台线程共享的状态
+struct Shared {
+    backend: Arc<dyn Backend>,
+    masks: Arc<MaskTracker>,
+    settings: Arc<Mutex<DeviceSettings>>,
+    config: HealthConfig,
+    state: Mutex<HealthState>,
+    hooks: Mutex<Vec<ReinitHook>>,
+    sinks: Mutex<Vec<Sink>>,
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, HealthState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn step(&self) -> HealthState {
+        let mut events = Vec::new();
+        let mut state = self.state();
+        *state = match *state {
+            HealthState::Healthy | HealthState::Degraded { .. } => {
+                let failures = match *state {
+                    HealthState::Degraded { failures } => failures,
+                    _ => 0,
+                };
+                match probe(self.backend.as_ref()) {
+                    Ok(version) => {
+                        if failures > 0 {
+                            events.push(HealthEvent::Recovered {
+                                attempts: 0,
+                                version,
+                            });
+                        }
+                        HealthState::Healthy
+                    }
+                    Err(error) => {
+                        let error = Arc::new(error);
+                        let failures = failures + 1;
+                        if failures >= self.config.failure_threshold {
+                            events.push(HealthEvent::Lost { error });
+                            HealthState::Recovering { attempts: 0 }
+                        } else {
+                            events.push(HealthEvent::Degraded { failures, error });
+                            HealthState::Degraded { failures }
+                        }
+                    }
+                }
+            }
+            HealthState::Recovering { attempts } => {
+                let attempt = attempts + 1;
+                match self.reinit(&mut events) {
+                    Ok(version) => {
+                        events.push(HealthEvent::Recovered {
+                            attempts: attempt,
+                            version,
+                        });
+                        HealthState::Healthy
+                    }
+                    Err(error) => {
+                        events.push(HealthEvent::ReinitFailed {
+                            attempt,
+                            error: Arc::new(error),
+                        });
+                        HealthState::Recovering { attempts: attempt }
+                    }
+                }
+            }
+        };
+        let next = *state;
+        drop(state);
+
+        self.dispatch(events);
+        next
+    }
+
+    /// 重新初始化设备并恢复设置
+    ///
+    /// 配置错误记为 `RestoreFailed`，不影响其余设置的恢复；其他错误使本次重新初始化失败。
+    fn reinit(&self, events: &mut Vec<HealthEvent>) -> KmboxResult<i32> {
+        let backend = self.backend.as_ref();
+        check_result(backend.init(), Operation::Init)?;
+        let version = probe(backend)?;
+
+        let mut restore = |result: KmboxResult<()>| match result {
+            Err(error) if !is_configuration_error(&error) => Err(error),
+            Err(error) => {
+                events.push(HealthEvent::RestoreFailed {
+                    error: Arc::new(error),
+                });
+                Ok(())
+            }
+            Ok(()) => Ok(()),
+        };
+        restore(
+            self.settings
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .restore(backend),
+        )?;
+        restore(self.masks.reapply())?;
+        for hook in self
+            .hooks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter_mut()
+        {
+            restore(hook())?;
+        }
+        Ok(version)
+    }
+
+    fn dispatch(&self, events: Vec<HealthEvent>) {
+        if events.is_empty() {
+            return;
+        }
+        let mut sinks = self.sinks.lock().unwrap_or_else(|e| e.into_inner());
+        for event in events {
+            // 接收端已经关闭的通道不再发送
+            sinks.retain_mut(|sink| match sink {
+                Sink::Callback(callback) => {
+                    callback(&event);
+                    true
+                }
+                Sink::Channel(sender) => sender.send(event.clone()).is_ok(),
+            });
+        }
+    }
+}
+
+/// 恢复设置时重新初始化设备也无法解决的错误
+///
+/// 只有 RKNN 接口的返回码有文档；`kmAI_*` 等接口返回的错误码（包括 `-1`）都视为设备尚未恢复。
+fn is_configuration_error(error: &KmboxError) -> bool {
+    match error {
+        KmboxError::Call(call) => matches!(
+            call.error_code(),
+            Some(
+                ErrorCode::ParamInvalid
+                    | ErrorCode::ModelInvalid
+                    | ErrorCode::InputInvalid
+                    | ErrorCode::OutputInvalid
+                    | ErrorCode::DeviceUnmatch
+                    | ErrorCode::IncompatiblePreCompileModel
+                    | ErrorCode::IncompatibleOptimizationLevel
+                    | ErrorCode::TargetPlatformUnmatch
+            )
+        ),
+        KmboxError::ParameterError(_)
+        | KmboxError::ModelError(_)
+        | KmboxError::KeyboardError(_)
+        | KmboxError::ImageError(_) => true,
+        _ => false,
+    }
+}
+
+/// 设备健康监控
+///
+/// 由 [`KmboxAI::health_supervisor`](crate::kmbox_ai::KmboxAI::health_supervisor) 创建。
+/// 可以通过 [`HealthSupervisor::start`] 在后台线程中定期探测，
+/// 也可以手动调用 [`HealthSupervisor::step`]。
+pub struct HealthSupervisor {
+    shared: Arc<Shared>,
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl HealthSupervisor {
+    pub(crate) fn new(
+        backend: Arc<dyn Backend>,
+        masks: Arc<MaskTracker>,
+        settings: Arc<Mutex<DeviceSettings>>,
+        config: HealthConfig,
+    ) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                backend,
+                masks,
+                settings,
+                config,
+                state: Mutex::new(HealthState::Healthy),
+                hooks: Mutex::new(Vec::new()),
+                sinks: Mutex::new(Vec::new()),
+            }),
+            stop: None,
+            handle: None,
+        }
+    }
+
+    /// 监控参数
+    pub fn config(&self) -> &HealthConfig {
+        &self.shared.config
+    }
+
+    /// 当前健康状态
+    pub fn state(&self) -> HealthState {
+        *self.shared.state()
+    }
+
+    /// 注册事件回调
+    ///
+    /// 回调在监控线程（或调用 [`HealthSupervisor::step`] 的线程）中执行。
+    pub fn on_event<F>(&self, callback: F)
+    where
+        F: FnMut(&HealthEvent) + Send + 'static,
+    {
+        self.shared
+            .sinks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Sink::Callback(Box::new(callback)));
+    }
+
+    /// 通过通道接收事件
+    pub fn subscribe(&self) -> Receiver<HealthEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.shared
+            .sinks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Sink::Channel(sender));
+        receiver
+    }
+
+    /// 注册重新初始化后执行的恢复函数，按注册顺序执行
+    ///
+    /// 用于恢复 `KmboxAI` 之外的状态。函数在监控线程上执行，必须实现 `Send`，
+    /// 并且在监控状态锁内执行，不能调用本监控的方法。返回的错误按 [模块文档](self) 的规则分类。
+    ///
+    /// [`RknnContext`](crate::rknn::RknnContext) 和 [`YoloDetector`](crate::yolo::YoloDetector)
+    /// 不实现 `Send`，不能被恢复函数持有。需要在设备恢复后重新加载模型时，
+    /// 启用 `async` 特性，把模型放在 `InferenceWorker` 中，恢复函数向推理线程提交重新创建模型的任务并等待结果：
+    ///
+    /// ```rust,ignore
+    /// let worker = Arc::new(InferenceWorker::new(|| YoloDetector::new("model.rknn"))?);
+    /// let reload = worker.clone();
+    /// supervisor.on_reinit(move || {
+    ///     futures::executor::block_on(reload.run(|detector| {
+    ///         *detector = YoloDetector::new("model.rknn")?;
+    ///         Ok(())
+    ///     }))
+    /// });
+    /// ```
+    pub fn on_reinit<F>(&self, hook: F)
+    where
+        F: FnMut() -> KmboxResult<()> + Send + 'static,
+    {
+        self.shared
+            .hooks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Box::new(hook));
+    }
+
+    /// 执行一步状态机，返回新的状态
+    ///
+    /// 正常或探测失败时探测一次设备；设备丢失时尝试一次重新初始化。
+    pub fn step(&self) -> HealthState {
+        self.shared.step()
+    }
+
+    /// 启动后台监控线程
+    pub fn start(&mut self) -> KmboxResult<()> {
+        if self.handle.is_some() {
+            return Err(KmboxError::SystemError("健康监控已经启动".to_string()));
+        }
+
+        let (stop, stopped) = mpsc::channel::<()>();
+        let shared = self.shared.clone();
+        let handle = thread::Builder::new()
+            .name("kmbox-health".to_string())
+            .spawn(move || loop {
+                let delay = shared.config.delay(*shared.state());
+                // 收到停止信号或监控被丢弃时退出
+                match stopped.recv_timeout(delay) {
+                    Err(RecvTimeoutError::Timeout) => {
+                        shared.step();
+                    }
+                    _ => break,
+                }
+            })
+            .map_err(|e| KmboxError::io("启动健康监控线程", e))?;
+        self.stop = Some(stop);
+        self.handle = Some(handle);
+        Ok(())
+    }
+
+    /// 停止后台监控线程
+    pub fn stop(&mut self) {
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for HealthSupervisor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::SimulatedBackend;
+    use crate::kmbox_ai::KmboxAI;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const DEVICE_UNAVAILABLE: i32 = -3;
+
+    fn ms(value: u64) -> Duration {
+        Duration::from_millis(value)
+    }
+
+    #[test]
+    fn test_config() {
+        assert!(HealthConfig::new().with_failure_threshold(0).is_err());
+        assert!(HealthConfig::new()
+            .with_reinit_backoff(ms(100), ms(50))
+            .is_err());
+
+        let config = HealthConfig::new()
+            .with_probe_interval(ms(200))
+            .with_reinit_backoff(ms(100), ms(500))
+            .unwrap();
+        assert_eq!(config.delay(HealthState::Healthy), ms(200));
+        assert_eq!(config.delay(HealthState::Degraded { failures: 1 }), ms(200));
+        assert_eq!(config.delay(HealthState::Recovering { attempts: 0 }), ms(0));
+        assert_eq!(
+            config.delay(HealthState::Recovering { attempts: 1 }),
+            ms(100)
+        );
+        assert_eq!(
+            config.delay(HealthState::Recovering { attempts: 3 }),
+            ms(400)
+        );
+        assert_eq!(
+            config.delay(HealthState::Recovering { attempts: 4 }),
+            ms(500)
+        );
+        assert_eq!(
+            config.delay(HealthState::Recovering { attempts: 40 }),
+            ms(500)
+        );
+    }
+
+    #[test]
+    fn test_reinit_restores_device() {
+        let sim = Arc::new(SimulatedBackend::new());
+        sim.set_version(7);
+        sim.add_model("yolo.rknn");
+
+        let mut kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        kmbox.init().unwrap();
+        kmbox.run().unwrap();
+        kmbox.enable(true).unwrap();
+        kmbox.mask_keyboard(4).unwrap();
+        kmbox.yolo_load_model("yolo.rknn").unwrap();
+
+        let config = HealthConfig::new().with_failure_threshold(2).unwrap();
+        let supervisor = kmbox.health_supervisor(config);
+        let events = supervisor.subscribe();
+        let reloads = Arc::new(AtomicU32::new(0));
+        let counter = reloads.clone();
+        supervisor.on_reinit(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+
+        assert_eq!(supervisor.step(), HealthState::Healthy);
+        assert!(events.try_recv().is_err());
+
+        sim.inject_fault(DEVICE_UNAVAILABLE);
+        assert_eq!(supervisor.step(), HealthState::Degraded { failures: 1 });
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            HealthEvent::Degraded { failures: 1, .. }
+        ));
+        assert_eq!(supervisor.step(), HealthState::Recovering { attempts: 0 });
+        match events.try_recv().unwrap() {
+            HealthEvent::Lost { error } => {
+                // kmAI_Version 的返回码没有文档，只保留原始值
+                assert_eq!(error.code(), Some(DEVICE_UNAVAILABLE));
+                assert_eq!(error.operation(), Some("version"));
+                assert_eq!(error.name(), "KMAI_CALL_FAILED");
+            }
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        // 设备仍不可用，重新初始化失败
+        assert_eq!(supervisor.step(), HealthState::Recovering { attempts: 1 });
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            HealthEvent::ReinitFailed { attempt: 1, .. }
+        ));
+
+        sim.clear_fault();
+        assert_eq!(supervisor.step(), HealthState::Healthy);
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            HealthEvent::Recovered {
+                attempts: 2,
+                version: 7
+            }
+        ));
+        assert!(events.try_recv().is_err());
+
+        let state = sim.state();
+        assert!(state.initialized && state.running && state.enabled);
+        assert!(state.keyboard_masks.contains(&4));
+        assert_eq!(state.loaded_models, ["yolo.rknn"]);
+        assert_eq!(reloads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_transient_failure_recovers_without_reinit() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        let supervisor = kmbox.health_supervisor(HealthConfig::new());
+        let events = supervisor.subscribe();
+
+        sim.inject_fault(-2);
+        assert_eq!(supervisor.step(), HealthState::Degraded { failures: 1 });
+        sim.clear_fault();
+        assert_eq!(supervisor.step(), HealthState::Healthy);
+
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            HealthEvent::Degraded { failures: 1, .. }
+        ));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            HealthEvent::Recovered { attempts: 0, .. }
+        ));
+        assert!(!sim.state().initialized);
+    }
+
+    #[test]
+    fn test_restore_errors() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        let config = HealthConfig::new().with_failure_threshold(1).unwrap();
+        let supervisor = kmbox.health_supervisor(config);
+        let events = supervisor.subscribe();
+
+        // 设备调用失败（超时、-1）时重新初始化失败，之后返回配置错误
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        supervisor.on_reinit(move || match counter.fetch_add(1, Ordering::SeqCst) {
+            0 => Err(KmboxError::call(Operation::RknnLoadModel, -2)),
+            1 => Err(KmboxError::call(Operation::MaskKeyboard, -1)),
+            2 => Err(KmboxError::call(Operation::RknnLoadModel, -6)),
+            _ => Err(KmboxError::ModelError("模型文件无效".to_string())),
+        });
+
+        sim.inject_fault(DEVICE_UNAVAILABLE);
+        assert_eq!(supervisor.step(), HealthState::Recovering { attempts: 0 });
+        sim.clear_fault();
+        assert_eq!(supervisor.step(), HealthState::Recovering { attempts: 1 });
+        assert_eq!(supervisor.step(), HealthState::Recovering { attempts: 2 });
+        assert_eq!(supervisor.step(), HealthState::Healthy);
+
+        let events: Vec<HealthEvent> = events.try_iter().collect();
+        assert!(matches!(events[0], HealthEvent::Lost { .. }));
+        match &events[1] {
+            HealthEvent::ReinitFailed { attempt: 1, error } => {
+                assert_eq!(error.name(), "RKNN_ERR_TIMEOUT");
+            }
+            event => panic!("unexpected event: {:?}", event),
+        }
+        match &events[2] {
+            HealthEvent::ReinitFailed { attempt: 2, error } => assert_eq!(error.code(), Some(-1)),
+            event => panic!("unexpected event: {:?}", event),
+        }
+        match &events[3] {
+            HealthEvent::RestoreFailed { error } => {
+                assert_eq!(error.name(), "RKNN_ERR_MODEL_INVALID")
+            }
+            event => panic!("unexpected event: {:?}", event),
+        }
+        assert!(matches!(
+            events[4],
+            HealthEvent::Recovered { attempts: 3, .. }
+        ));
+        assert_eq!(events.len(), 5);
+
+        // 配置错误不会让设备停在恢复状态
+        sim.inject_fault(DEVICE_UNAVAILABLE);
+        supervisor.step();
+        sim.clear_fault();
+        assert_eq!(supervisor.step(), HealthState::Healthy);
+    }
+
+    #[test]
+    fn test_background_supervisor() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let mut kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        kmbox.init().unwrap();
+        let config = HealthConfig::new()
+            .with_probe_interval(ms(5))
+            .with_failure_threshold(2)
+            .unwrap()
+            .with_reinit_backoff(ms(5), ms(20))
+            .unwrap();
+        let mut supervisor = kmbox.health_supervisor(config);
+        let events = supervisor.subscribe();
+        supervisor.start().unwrap();
+        assert!(supervisor.start().is_err());
+
+        sim.inject_fault(DEVICE_UNAVAILABLE);
+        loop {
+            match events.recv_timeout(Duration::from_secs(5)).unwrap() {
+                HealthEvent::ReinitFailed { .. } => break,
+                HealthEvent::Recovered { .. } => panic!("设备不应在故障期间恢复"),
+                _ => {}
+            }
+        }
+        sim.clear_fault();
+        loop {
+            if let HealthEvent::Recovered { attempts, .. } =
+                events.recv_timeout(Duration::from_secs(5)).unwrap()
+            {
+                assert!(attempts >= 2);
+                break;
+            }
+        }
+        supervisor.stop();
+        assert!(sim.state().initialized);
+        assert!(supervisor.state().is_healthy());
+    }
+}
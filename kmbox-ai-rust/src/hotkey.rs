@@ -0,0 +1,723 @@
+//! 全局热键模块
+//!
+//! 监听盒子上连接的物理键盘，在组合键按下、释放或按住指定时长时
+//! 执行回调或向通道发送消息。组合键用按键名称书写，例如 `"F8"`、`"LEFTCTRL+LEFTSHIFT+Q"`，
+//! 名称按 [`Key`] 的 `FromStr` 解析，也接受 `CTRL`、`ALT` 等别名，不区分大小写。
+//!
+//! ## 匹配规则
+//!
+//! - 组合键的所有按键都处于按下状态，且没有按下组合键以外的修饰键时视为激活
+//! - 组合键的最后一个按键为触发键；开启拦截（[`Hotkey::consume`]）后，
+//!   其余按键都已按下时会通过 `mask_keyboard` 屏蔽触发键，主机不会收到该按键，
+//!   触发键松开且组合不再满足时自动 `unmask_keyboard`
+//! - 屏蔽记录在 [`MaskTracker`] 中，与守卫和手动屏蔽共同计数；通过
+//!   [`KmboxAI::hotkey_manager`](crate::kmbox_ai::KmboxAI::hotkey_manager) 创建的管理器与 `KmboxAI`
+//!   共享记录，panic 时会被解除，设备重新初始化后会被健康监控重新设置
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use kmbox_ai_rust::backend::default_backend;
+//! use kmbox_ai_rust::hotkey::{Hotkey, HotkeyManager, HotkeyTrigger};
+//! use kmbox_ai_rust::monitor::MonitorConfig;
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let mut manager = HotkeyManager::new(default_backend());
+//! manager.on(Hotkey::parse("F8")?, HotkeyTrigger::Press, |_| println!("切换"))?;
+//! let quit = manager.subscribe(
+//!     Hotkey::parse("LEFTCTRL+LEFTSHIFT+Q")?.consume(true),
+//!     HotkeyTrigger::Press,
+//! )?;
+//! manager.start(MonitorConfig::default())?;
+//! quit.recv().ok();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::{KmboxError, KmboxResult};
+use crate::keyboard::Key;
+use crate::mask::{MaskTarget, MaskTracker};
+use crate::monitor::{EventKind, InputEvent, InputMonitor, MonitorConfig};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 修饰键的 HID 键值范围（LEFTCTRL..=RIGHT_GUI）
+const MODIFIERS: std::ops::RangeInclusive<i16> = 0xE0..=0xE7;
+
+/// 组合键
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotkey {
+    keys: Vec<Key>,
+    consume: bool,
+}
+
+impl Hotkey {
+    /// 使用按键列表创建组合键，最后一个按键为触发键
+    pub fn new(keys: Vec<Key>) -> KmboxResult<Self> {
+        if keys.is_empty() {
+            return Err(KmboxError::ParameterError("组合键不能为空".to_string()));
+        }
+        for key in &keys {
+            if matches!(key, Key::Custom(_)) || key.hex_value() == 0 || key.hex_value() > 0xFF {
+                return Err(KmboxError::ParameterError(format!(
+                    "按键不能用于热键: {}",
+                    key.string_name()
+                )));
+            }
+        }
+        Ok(Self {
+            keys,
+            consume: false,
+        })
+    }
+
+    /// 解析 `"LEFTCTRL+LEFTSHIFT+Q"` 形式的组合键
+    pub fn parse(text: &str) -> KmboxResult<Self> {
+        let keys = text
+            .split('+')
+            .map(|name| {
+                let name = name.trim();
+                name.parse::<Key>()
+                    .map_err(|_| KmboxError::ParameterError(format!("未知按键: {:?}", name)))
+            })
+            .collect::<KmboxResult<Vec<_>>>()?;
+        Self::new(keys)
+    }
+
+    /// 设置是否拦截组合键，拦截后主机不会收到触发键
+    pub fn consume(mut self, consume: bool) -> Self {
+        self.consume = consume;
+        self
+    }
+
+    /// 获取组合键中的按键
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    /// 是否拦截组合键
+    pub fn is_consumed(&self) -> bool {
+        self.consume
+    }
+
+    fn codes(&self) -> impl Iterator<Item = i16> + '_ {
+        self.keys.iter().map(|key| key.hex_value() as i16)
+    }
+
+    fn trigger_code(&self) -> i16 {
+        self.keys[self.keys.len() - 1].hex_value() as i16
+    }
+
+    /// 没有按下组合键以外的修饰键
+    fn modifiers_match(&self, down: &BTreeSet<i16>) -> bool {
+        down.iter()
+            .filter(|code| MODIFIERS.contains(code))
+            .all(|code| self.codes().any(|own| own == *code))
+    }
+
+    /// 组合键的所有按键都已按下
+    fn is_active(&self, down: &BTreeSet<i16>) -> bool {
+        self.modifiers_match(down) && self.codes().all(|code| down.contains(&code))
+    }
+
+    /// 除触发键外的按键都已按下，下一次按下触发键就会激活组合键
+    fn is_armed(&self, down: &BTreeSet<i16>) -> bool {
+        let trigger = self.trigger_code();
+        self.modifiers_match(down)
+            && self
+                .codes()
+                .filter(|code| *code != trigger)
+                .all(|code| down.contains(&code))
+    }
+}
+
+impl FromStr for Hotkey {
+    type Err = KmboxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = self.keys.iter().map(|key| key.string_name()).collect();
+        write!(f, "{}", names.join("+"))
+    }
+}
+
+/// 热键触发方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyTrigger {
+    /// 组合键按下
+    Press,
+    /// 组合键释放
+    Release,
+    /// 组合键按住指定时长
+    Hold(Duration),
+}
+
+/// 热键注册编号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HotkeyId(u64);
+
+/// 热键触发事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotkeyEvent {
+    /// 注册编号
+    pub id: HotkeyId,
+    /// 触发的组合键
+    pub hotkey: Hotkey,
+    /// 触发方式
+    pub trigger: HotkeyTrigger,
+    /// 触发时刻
+    pub timestamp: Instant,
+}
+
+type Callback = Arc<Mutex<dyn FnMut(&HotkeyEvent) + Send>>;
+
+/// 热键触发后的动作
+enum Action {
+    Callback(Callback),
+    Channel(Sender<HotkeyEvent>),
+}
+
+/// 一条热键注册
+struct Binding {
+    hotkey: Hotkey,
+    trigger: HotkeyTrigger,
+    action: Action,
+    /// 组合键处于激活状态的起始时刻
+    pressed_at: Option<Instant>,
+    /// 本次按住是否已经触发过 Hold
+    hold_fired: bool,
+    /// 触发键在拦截状态下被按下，松开前保持屏蔽
+    swallowing: bool,
+}
+
+/// 热键表和物理键盘状态
+#[derive(Default)]
+struct Registry {
+    next_id: u64,
+    bindings: BTreeMap<HotkeyId, Binding>,
+    /// 当前按下的物理按键
+    down: BTreeSet<i16>,
+    /// 当前由热键管理器屏蔽的按键
+    masked: BTreeSet<i16>,
+}
+
+impl Registry {
+    /// 处理一次按键变化，返回需要执行的动作
+    fn key_changed(
+        &mut self,
+        code: i16,
+        pressed: bool,
+        now: Instant,
+    ) -> Vec<(Action, HotkeyEvent)> {
+        // 触发键按下前先判断是否处于拦截状态
+        if pressed {
+            for binding in self.bindings.values_mut() {
+                if binding.hotkey.consume
+                    && binding.hotkey.trigger_code() == code
+                    && binding.hotkey.is_armed(&self.down)
+                {
+                    binding.swallowing = true;
+                }
+            }
+            self.down.insert(code);
+        } else {
+            self.down.remove(&code);
+            for binding in self.bindings.values_mut() {
+                if binding.hotkey.trigger_code() == code {
+                    binding.swallowing = false;
+                }
+            }
+        }
+
+        let mut fired = Vec::new();
+        for (id, binding) in self.bindings.iter_mut() {
+            let active = binding.hotkey.is_active(&self.down);
+            let fire = match (binding.pressed_at.is_some(), active) {
+                (false, true) => {
+                    binding.pressed_at = Some(now);
+                    binding.hold_fired = false;
+                    binding.trigger == HotkeyTrigger::Press
+                }
+                (true, false) => {
+                    binding.pressed_at = None;
+                    binding.trigger == HotkeyTrigger::Release
+                }
+                _ => false,
+            };
+            if fire {
+                fired.push(binding.fire(*id, now));
+            }
+        }
+        fired.extend(self.tick(now));
+        fired
+    }
+
+    /// 检查按住时长，返回需要执行的动作
+    fn tick(&mut self, now: Instant) -> Vec<(Action, HotkeyEvent)> {
+        let mut fired = Vec::new();
+        for (id, binding) in self.bindings.iter_mut() {
+            if let (HotkeyTrigger::Hold(duration), Some(pressed_at)) =
+                (binding.trigger, binding.pressed_at)
+            {
+                if !binding.hold_fired && now.saturating_duration_since(pressed_at) >= duration {
+                    binding.hold_fired = true;
+                    fired.push(binding.fire(*id, now));
+                }
+            }
+        }
+        fired
+    }
+
+    /// 应该被屏蔽的按键集合
+    fn wanted_masks(&self) -> BTreeSet<i16> {
+        self.bindings
+            .values()
+            .filter(|binding| {
+                binding.hotkey.consume
+                    && (binding.swallowing || binding.hotkey.is_armed(&self.down))
+            })
+            .map(|binding| binding.hotkey.trigger_code())
+            .collect()
+    }
+
+    /// 让设备上的屏蔽状态与 [`Registry::wanted_masks`] 一致
+    ///
+    /// 失败的按键保持原来的记录，下一次同步时重试；返回第一个错误。
+    fn sync_masks(&mut self, masks: &MaskTracker) -> KmboxResult<()> {
+        let wanted = self.wanted_masks();
+        let mut first_error = None;
+        for code in wanted.difference(&self.masked).copied().collect::<Vec<_>>() {
+            match masks.acquire(MaskTarget::Keyboard(code)) {
+                Ok(()) => {
+                    self.masked.insert(code);
+                }
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        for code in self.masked.difference(&wanted).copied().collect::<Vec<_>>() {
+            // 引用已经释放，即使设备返回错误也不再重复释放
+            self.masked.remove(&code);
+            if let Err(e) = masks.release(MaskTarget::Keyboard(code)) {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+impl Binding {
+    fn fire(&self, id: HotkeyId, now: Instant) -> (Action, HotkeyEvent) {
+        let action = match &self.action {
+            Action::Callback(callback) => Action::Callback(callback.clone()),
+            Action::Channel(sender) => Action::Channel(sender.clone()),
+        };
+        let event = HotkeyEvent {
+            id,
+            hotkey: self.hotkey.clone(),
+            trigger: self.trigger,
+            timestamp: now,
+        };
+        (action, event)
+    }
+}
+
+/// 在不持有热键表锁的情况下执行动作，回调中可以注册或注销热键
+fn dispatch(fired: Vec<(Action, HotkeyEvent)>) {
+    for (action, event) in fired {
+        match action {
+            Action::Callback(callback) => {
+                let mut callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+                callback(&event);
+            }
+            Action::Channel(sender) => {
+                let _ = sender.send(event);
+            }
+        }
+    }
+}
+
+/// 管理器与监听线程共享的状态
+struct Shared {
+    backend: Arc<dyn Backend>,
+    masks: Arc<MaskTracker>,
+    registry: Mutex<Registry>,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Registry> {
+        self.registry.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn handle_event(&self, event: &InputEvent) -> KmboxResult<()> {
+        let (code, pressed) = match event.kind {
+            EventKind::KeyDown(code) => (code, true),
+            EventKind::KeyUp(code) => (code, false),
+            _ => return Ok(()),
+        };
+        let (fired, result) = {
+            let mut registry = self.lock();
+            let fired = registry.key_changed(code, pressed, event.timestamp);
+            (fired, registry.sync_masks(&self.masks))
+        };
+        // 屏蔽失败不影响热键触发
+        dispatch(fired);
+        result
+    }
+
+    fn tick(&self, now: Instant) {
+        let fired = self.lock().tick(now);
+        dispatch(fired);
+    }
+}
+
+/// 全局热键管理器
+///
+/// 可以通过 [`HotkeyManager::start`] 在后台线程中自动监听物理键盘，
+/// 也可以把 [`InputMonitor`] 的事件手动交给 [`HotkeyManager::handle_event`]。
+/// 销毁时解除所有由管理器设置的按键屏蔽。
+pub struct HotkeyManager {
+    shared: Arc<Shared>,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl HotkeyManager {
+    /// 在指定后端上创建热键管理器，屏蔽记录只属于该管理器
+    ///
+    /// 需要与 `KmboxAI` 共享屏蔽记录时使用
+    /// [`KmboxAI::hotkey_manager`](crate::kmbox_ai::KmboxAI::hotkey_manager)。
+    pub fn new(backend: Arc<dyn Backend>) -> Self {
+        let masks = Arc::new(MaskTracker::new(backend.clone()));
+        Self::with_masks(backend, masks)
+    }
+
+    /// 创建通过 `masks` 屏蔽按键的热键管理器
+    pub(crate) fn with_masks(backend: Arc<dyn Backend>, masks: Arc<MaskTracker>) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                backend,
+                masks,
+                registry: Mutex::new(Registry::default()),
+            }),
+            running: Arc::new(AtomicBool::new(false)),
+            handle: None,
+        }
+    }
+
+    fn register(
+        &self,
+        hotkey: Hotkey,
+        trigger: HotkeyTrigger,
+        action: Action,
+    ) -> KmboxResult<HotkeyId> {
+        let mut registry = self.shared.lock();
+        registry.next_id += 1;
+        let id = HotkeyId(registry.next_id);
+        registry.bindings.insert(
+            id,
+            Binding {
+                hotkey,
+                trigger,
+                action,
+                pressed_at: None,
+                hold_fired: false,
+                swallowing: false,
+            },
+        );
+        registry.sync_masks(&self.shared.masks)?;
+        Ok(id)
+    }
+
+    /// 注册热键回调
+    ///
+    /// 回调在监听线程（或调用 [`HotkeyManager::handle_event`] 的线程）中执行。
+    pub fn on<F>(
+        &self,
+        hotkey: Hotkey,
+        trigger: HotkeyTrigger,
+        callback: F,
+    ) -> KmboxResult<HotkeyId>
+    where
+        F: FnMut(&HotkeyEvent) + Send + 'static,
+    {
+        let callback: Callback = Arc::new(Mutex::new(callback));
+        self.register(hotkey, trigger, Action::Callback(callback))
+    }
+
+    /// 注册热键并通过通道接收触发事件
+    pub fn subscribe(
+        &self,
+        hotkey: Hotkey,
+        trigger: HotkeyTrigger,
+    ) -> KmboxResult<Receiver<HotkeyEvent>> {
+        let (sender, receiver) = mpsc::channel();
+        self.register(hotkey, trigger, Action::Channel(sender))?;
+        Ok(receiver)
+    }
+
+    /// 注销热键
+    pub fn unregister(&self, id: HotkeyId) -> KmboxResult<()> {
+        let mut registry = self.shared.lock();
+        if registry.bindings.remove(&id).is_none() {
+            return Err(KmboxError::ParameterError(format!("热键不存在: {:?}", id)));
+        }
+        registry.sync_masks(&self.shared.masks)
+    }
+
+    /// 处理一个物理输入事件，只关心键盘按下和释放
+    ///
+    /// 热键照常触发；屏蔽或解除屏蔽触发键失败时返回错误，下一次按键变化时重试。
+    pub fn handle_event(&self, event: &InputEvent) -> KmboxResult<()> {
+        self.shared.handle_event(event)
+    }
+
+    /// 检查按住类热键，`now` 为当前时刻
+    pub fn tick(&self, now: Instant) {
+        self.shared.tick(now);
+    }
+
+    /// 启动后台监听线程
+    pub fn start(&mut self, config: MonitorConfig) -> KmboxResult<()> {
+        if self.handle.is_some() {
+            return Err(KmboxError::SystemError("热键监听已经启动".to_string()));
+        }
+
+        let interval = config.interval;
+        let monitor = InputMonitor::start(self.shared.backend.clone(), config)?;
+        let shared = self.shared.clone();
+        let running = self.running.clone();
+        running.store(true, Ordering::Release);
+
+        let handle = thread::Builder::new()
+            .name("kmbox-hotkey".to_string())
+            .spawn(move || {
+                while running.load(Ordering::Acquire) {
+                    match monitor.recv_timeout(interval) {
+                        // 屏蔽失败时记录保持不变，下一次按键变化时重试
+                        Some(event) => {
+                            let _ = shared.handle_event(&event);
+                        }
+                        None => shared.tick(Instant::now()),
+                    }
+                }
+            })
+            .map_err(|e| KmboxError::io("启动热键监听线程", e))?;
+        self.handle = Some(handle);
+        Ok(())
+    }
+
+    /// 停止后台监听线程
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for HotkeyManager {
+    fn drop(&mut self) {
+        self.stop();
+        let mut registry = self.shared.lock();
+        for code in std::mem::take(&mut registry.masked) {
+            let _ = self.shared.masks.release(MaskTarget::Keyboard(code));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::SimulatedBackend;
+    use crate::keyboard::constants;
+    use crate::kmbox_ai::KmboxAI;
+
+    const CTRL: i16 = constants::KEY_LEFTCONTROL as i16;
+    const SHIFT: i16 = constants::KEY_LEFTSHIFT as i16;
+    const Q: i16 = constants::KEY_Q as i16;
+
+    fn key_event(kind: EventKind, timestamp: Instant) -> InputEvent {
+        InputEvent { kind, timestamp }
+    }
+
+    #[test]
+    fn test_parse_hotkey() {
+        let hotkey: Hotkey = "leftctrl + LEFTSHIFT+Q".parse().unwrap();
+        assert_eq!(hotkey.keys(), &[Key::LeftControl, Key::LeftShift, Key::Q]);
+        assert_eq!(hotkey.to_string(), "LEFTCTRL+LEFTSHIFT+Q");
+        assert!(!hotkey.is_consumed());
+
+        assert!(Hotkey::parse("").is_err());
+        assert!(Hotkey::parse("LEFTCTRL+NOPE").is_err());
+    }
+
+    #[test]
+    fn test_press_release_and_hold() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let manager = HotkeyManager::new(sim);
+        let hotkey = Hotkey::parse("LEFTCTRL+Q").unwrap();
+        let press = manager
+            .subscribe(hotkey.clone(), HotkeyTrigger::Press)
+            .unwrap();
+        let release = manager
+            .subscribe(hotkey.clone(), HotkeyTrigger::Release)
+            .unwrap();
+        let hold = manager
+            .subscribe(hotkey, HotkeyTrigger::Hold(Duration::from_millis(300)))
+            .unwrap();
+
+        let t0 = Instant::now();
+        manager
+            .handle_event(&key_event(EventKind::KeyDown(CTRL), t0))
+            .unwrap();
+        assert!(press.try_recv().is_err());
+        manager
+            .handle_event(&key_event(EventKind::KeyDown(Q), t0))
+            .unwrap();
+        assert_eq!(press.try_recv().unwrap().timestamp, t0);
+
+        manager.tick(t0 + Duration::from_millis(100));
+        assert!(hold.try_recv().is_err());
+        manager.tick(t0 + Duration::from_millis(300));
+        assert!(hold.try_recv().is_ok());
+        // 每次按住只触发一次
+        manager.tick(t0 + Duration::from_millis(600));
+        assert!(hold.try_recv().is_err());
+
+        manager
+            .handle_event(&key_event(EventKind::KeyUp(CTRL), t0))
+            .unwrap();
+        assert!(release.try_recv().is_ok());
+        assert!(press.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_extra_modifier_does_not_match() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let manager = HotkeyManager::new(sim);
+        let count = Arc::new(Mutex::new(Vec::new()));
+        for text in ["LEFTCTRL+Q", "LEFTCTRL+LEFTSHIFT+Q"] {
+            let count = count.clone();
+            manager
+                .on(
+                    Hotkey::parse(text).unwrap(),
+                    HotkeyTrigger::Press,
+                    move |e| count.lock().unwrap().push(e.hotkey.to_string()),
+                )
+                .unwrap();
+        }
+
+        let now = Instant::now();
+        for code in [CTRL, SHIFT, Q] {
+            manager
+                .handle_event(&key_event(EventKind::KeyDown(code), now))
+                .unwrap();
+        }
+        assert_eq!(*count.lock().unwrap(), vec!["LEFTCTRL+LEFTSHIFT+Q"]);
+    }
+
+    #[test]
+    fn test_consumed_hotkey_masks_trigger_key() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let manager = HotkeyManager::new(sim.clone());
+        let hotkey = Hotkey::parse("LEFTCTRL+Q").unwrap().consume(true);
+        manager.subscribe(hotkey, HotkeyTrigger::Press).unwrap();
+        let now = Instant::now();
+
+        assert!(sim.state().keyboard_masks.is_empty());
+        manager
+            .handle_event(&key_event(EventKind::KeyDown(CTRL), now))
+            .unwrap();
+        assert!(sim.state().keyboard_masks.contains(&Q));
+
+        // 先松开修饰键，触发键松开前保持屏蔽
+        manager
+            .handle_event(&key_event(EventKind::KeyDown(Q), now))
+            .unwrap();
+        manager
+            .handle_event(&key_event(EventKind::KeyUp(CTRL), now))
+            .unwrap();
+        assert!(sim.state().keyboard_masks.contains(&Q));
+        manager
+            .handle_event(&key_event(EventKind::KeyUp(Q), now))
+            .unwrap();
+        assert!(sim.state().keyboard_masks.is_empty());
+
+        // 销毁时解除屏蔽
+        manager
+            .handle_event(&key_event(EventKind::KeyDown(CTRL), now))
+            .unwrap();
+        drop(manager);
+        assert!(sim.state().keyboard_masks.is_empty());
+    }
+
+    #[test]
+    fn test_masks_shared_with_kmbox() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        let manager = kmbox.hotkey_manager();
+        let hotkey = Hotkey::parse("LEFTCTRL+Q").unwrap().consume(true);
+        manager.subscribe(hotkey, HotkeyTrigger::Press).unwrap();
+        let now = Instant::now();
+
+        manager
+            .handle_event(&key_event(EventKind::KeyDown(CTRL), now))
+            .unwrap();
+        assert_eq!(kmbox.active_masks(), vec![MaskTarget::Keyboard(Q)]);
+        manager
+            .handle_event(&key_event(EventKind::KeyUp(CTRL), now))
+            .unwrap();
+        assert!(kmbox.active_masks().is_empty());
+        assert!(sim.state().keyboard_masks.is_empty());
+
+        // 设备错误通过返回值报告，失败的屏蔽不会被记录
+        sim.inject_fault(-4);
+        let error = manager
+            .handle_event(&key_event(EventKind::KeyDown(CTRL), now))
+            .unwrap_err();
+        assert_eq!(error.code(), Some(-4));
+        assert!(kmbox.active_masks().is_empty());
+
+        sim.clear_fault();
+        manager
+            .handle_event(&key_event(EventKind::KeyDown(4), now))
+            .unwrap();
+        assert!(sim.state().keyboard_masks.contains(&Q));
+        // KmboxAI 销毁时一并解除热键设置的屏蔽
+        drop(kmbox);
+        assert!(sim.state().keyboard_masks.is_empty());
+    }
+
+    #[test]
+    fn test_background_listener() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let mut manager = HotkeyManager::new(sim.clone());
+        let f8 = manager
+            .subscribe(Hotkey::parse("F8").unwrap(), HotkeyTrigger::Press)
+            .unwrap();
+        let config = MonitorConfig::default()
+            .with_interval(Duration::from_millis(1))
+            .with_keys([constants::KEY_F8 as i16]);
+        manager.start(config).unwrap();
+
+        sim.press_physical_key(constants::KEY_F8 as i16);
+        let event = f8.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(event.hotkey.keys(), &[Key::F8]);
+        manager.stop();
+    }
+}
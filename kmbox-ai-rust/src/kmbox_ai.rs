@@ -20,191 +20,46 @@
 //!
 //! ## 使用示例
 //!
-//! ```rust
+//! ```rust,no_run
 //! use kmbox_ai_rust::kmbox_ai::{KmboxAI, MouseButton, MouseAction};
 //!
-//! let kmbox = KmboxAI::new()?;
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let mut kmbox = KmboxAI::new()?;
 //! kmbox.init()?;
 //! kmbox.mouse_move(100, 200)?;
-//! kmbox.mouse_click(MouseButton::Left)?;
+//! kmbox.mouse_button(MouseButton::Left, MouseAction::Click)?;
+//! # Ok(())
+//! # }
 //! ```
 
-use crate::error::{check_result, KmboxError, KmboxResult};
-use std::ffi::CString;
-
-// 导入所有kmAI函数
-unsafe extern "C" {
-    // 系统初始化和控制
-    #[link_name = "\u{1}_Z7kmAI_Initv"]
-    fn kmAI_Init() -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z7kmAI_Runv"]
-    fn kmAI_Run() -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z11kmAI_kmEnablei"]
-    fn kmAI_kmEnable(enable: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z10kmAI_Versionv"]
-    fn kmAI_Version() -> std::os::raw::c_int;
-
-    // 鼠标操作
-    #[link_name = "\u{1}_Z12kmAI_mouse_moveii"]
-    fn kmAI_mouse_move(x: std::os::raw::c_int, y: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z13kmAI_mouse_lefti"]
-    fn kmAI_mouse_left(isdown: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z14kmAI_mouse_righti"]
-    fn kmAI_mouse_right(isdown: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z15kmAI_mouse_middlei"]
-    fn kmAI_mouse_middle(isdown: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z14kmAI_mouse_wheeli"]
-    fn kmAI_mouse_wheel(wheel: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z15kmAI_mouse_side1i"]
-    fn kmAI_mouse_side1(isdown: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z15kmAI_mouse_side2i"]
-    fn kmAI_mouse_side2(isdown: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z13kmAI_mouse_alliiiiii"]
-    fn kmAI_mouse_all(
-        x: std::os::raw::c_int,
-        y: std::os::raw::c_int,
-        left: std::os::raw::c_int,
-        right: std::os::raw::c_int,
-        middle: std::os::raw::c_int,
-        wheel: std::os::raw::c_int,
-    ) -> std::os::raw::c_int;
-
-    // 键盘操作
-    #[link_name = "\u{1}_Z11kmAI_keydowni"]
-    fn kmAI_keydown(vkey: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z9kmAI_keyupi"]
-    fn kmAI_keyup(vkey: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z11kmAI_keyinitv"]
-    fn kmAI_keyinit() -> std::os::raw::c_int;
-
-    // 鼠标监控
-    #[link_name = "\u{1}_Z22kmAI_monitor_mouse_leftv"]
-    fn kmAI_monitor_mouse_left() -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z24kmAI_monitor_mouse_middlev"]
-    fn kmAI_monitor_mouse_middle() -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z23kmAI_monitor_mouse_rightv"]
-    fn kmAI_monitor_mouse_right() -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z24kmAI_monitor_mouse_side1v"]
-    fn kmAI_monitor_mouse_side1() -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z24kmAI_monitor_mouse_side2v"]
-    fn kmAI_monitor_mouse_side2() -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z20kmAI_monitor_mouse_xyPiS_"]
-    fn kmAI_monitor_mouse_xy(
-        x: *mut std::os::raw::c_int,
-        y: *mut std::os::raw::c_int,
-    ) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z24kmAI_monitor_mouse_wheelPi"]
-    fn kmAI_monitor_mouse_wheel(wheel: *mut std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z21kmAI_monitor_keyboards"]
-    fn kmAI_monitor_keyboard(vk_key: std::os::raw::c_short) -> std::os::raw::c_int;
-
-    // 鼠标掩码
-    #[link_name = "\u{1}_Z20kmAI_mask_mouse_lefti"]
-    fn kmAI_mask_mouse_left(enable: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z21kmAI_mask_mouse_righti"]
-    fn kmAI_mask_mouse_right(enable: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z22kmAI_mask_mouse_middlei"]
-    fn kmAI_mask_mouse_middle(enable: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z22kmAI_mask_mouse_side1i"]
-    fn kmAI_mask_mouse_side1(enable: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z22kmAI_mask_mouse_side2i"]
-    fn kmAI_mask_mouse_side2(enable: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z18kmAI_mask_mouse_xi"]
-    fn kmAI_mask_mouse_x(enable: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z18kmAI_mask_mouse_yi"]
-    fn kmAI_mask_mouse_y(enable: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z19kmAI_mask_mouse_xyi"]
-    fn kmAI_mask_mouse_xy(enable: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z21kmAI_mask_mouse_wheeli"]
-    fn kmAI_mask_mouse_wheel(enable: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    // 键盘掩码
-    #[link_name = "\u{1}_Z19kmAI_mask_keyboards"]
-    fn kmAI_mask_keyboard(vkey: std::os::raw::c_short) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z21kmAI_unmask_keyboards"]
-    fn kmAI_unmask_keyboard(vkey: std::os::raw::c_short) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z15kmAI_unmask_allv"]
-    fn kmAI_unmask_all() -> std::os::raw::c_int;
-
-    // MiniUI控制
-    #[link_name = "\u{1}_Z18kmAI_MiniUI_Enablei"]
-    fn kmAI_MiniUI_Enable(MODE: std::os::raw::c_int) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z20kmAI_MiniUI_UserLockv"]
-    fn kmAI_MiniUI_UserLock() -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z22kmAI_MiniUI_UserUnLockv"]
-    fn kmAI_MiniUI_UserUnLock() -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z21kmAI_MiniUI_LCDdisplayN2cv3MatE"]
-    fn kmAI_MiniUI_LCDdisplay(img: *mut std::os::raw::c_void) -> std::os::raw::c_int;
-
-    // YOLO模型管理
-    #[link_name = "\u{1}_Z18kmAI_YOLO_LoadmodelPKc"]
-    fn kmAI_YOLO_Loadmodel(model_path: *const std::os::raw::c_char) -> *mut std::os::raw::c_void;
-
-    #[link_name = "\u{1}_Z23kmAI_YOLO_InterfaceModelPv"]
-    fn kmAI_YOLO_InterfaceModel(ctx: *mut std::os::raw::c_void) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z22kmAI_YOLO_DrawRectangleiiiiii"]
-    fn kmAI_YOLO_DrawRectangle(
-        x: std::os::raw::c_int,
-        y: std::os::raw::c_int,
-        w: std::os::raw::c_int,
-        h: std::os::raw::c_int,
-        color: std::os::raw::c_int,
-        thickness: std::os::raw::c_int,
-    ) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z16kmAI_YOLO_DrawTextPKciiiii"]
-    fn kmAI_YOLO_DrawText(
-        text: *const std::os::raw::c_char,
-        x: std::os::raw::c_int,
-        y: std::os::raw::c_int,
-        color: std::os::raw::c_int,
-        scale: std::os::raw::c_int,
-        thickness: std::os::raw::c_int,
-    ) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z18kmAI_YOLO_ReleasePv"]
-    fn kmAI_YOLO_Release(ctx: *mut std::os::raw::c_void) -> std::os::raw::c_int;
-
-    // 系统操作
-    #[link_name = "\u{1}_Z10kmAI_rebootv"]
-    fn kmAI_reboot() -> std::os::raw::c_int;
-}
+#[cfg(feature = "async")]
+use crate::asynchronous::{AsyncKmbox, EventStream};
+use crate::backend::{default_backend, Backend};
+use crate::cursor::{CursorModel, VirtualCursor};
+use crate::error::{check_result, check_state, KmboxError, KmboxResult, Operation};
+use crate::failsafe::{HeldInputs, HeldTracker};
+use crate::ffi::{cv_Mat, object_detected_list, rknn_context_t};
+use crate::gesture::{self, GesturePlanner, GestureStep};
+use crate::handle::{KmboxHandle, Writer};
+use crate::health::{HealthConfig, HealthSupervisor};
+use crate::hotkey::HotkeyManager;
+use crate::lcd::{DisplayOptions, LcdFrame};
+use crate::mask::{install_panic_hook, MaskGuard, MaskTarget, MaskTracker};
+use crate::monitor::{InputMonitor, MonitorConfig};
+use crate::motion::{MotionHandle, MotionPlanner};
+use crate::report::{MouseButtons, MouseReport};
+use crate::scheduler::OutputScheduler;
+use crate::vision::Image;
+use serde::{Deserialize, Serialize};
+use std::ffi::{CStr, CString};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
 
 /// 鼠标按钮枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MouseButton {
     /// 左键
     Left,
@@ -218,6 +73,39 @@ pub enum MouseButton {
     Side2,
 }
 
+impl MouseButton {
+    /// 所有鼠标按钮
+    pub(crate) const ALL: [MouseButton; 5] = [
+        MouseButton::Left,
+        MouseButton::Right,
+        MouseButton::Middle,
+        MouseButton::Side1,
+        MouseButton::Side2,
+    ];
+
+    /// 按钮在 `kmAI_mouse_all` 按键位掩码中对应的位
+    pub(crate) fn mask_bit(&self) -> i32 {
+        match self {
+            MouseButton::Left => 1,
+            MouseButton::Right => 1 << 1,
+            MouseButton::Middle => 1 << 2,
+            MouseButton::Side1 => 1 << 3,
+            MouseButton::Side2 => 1 << 4,
+        }
+    }
+
+    /// 查询物理按键状态的操作
+    pub(crate) fn monitor_operation(&self) -> Operation {
+        match self {
+            MouseButton::Left => Operation::MonitorMouseLeft,
+            MouseButton::Right => Operation::MonitorMouseRight,
+            MouseButton::Middle => Operation::MonitorMouseMiddle,
+            MouseButton::Side1 => Operation::MonitorMouseSide1,
+            MouseButton::Side2 => Operation::MonitorMouseSide2,
+        }
+    }
+}
+
 /// 鼠标动作枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MouseAction {
@@ -230,14 +118,54 @@ pub enum MouseAction {
 }
 
 /// 鼠标位置结构体
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct MousePosition {
     pub x: i32,
     pub y: i32,
 }
 
+/// 物理鼠标屏蔽目标枚举
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MouseMask {
+    /// 左键
+    Left,
+    /// 右键
+    Right,
+    /// 中键
+    Middle,
+    /// 侧键1
+    Side1,
+    /// 侧键2
+    Side2,
+    /// X轴移动
+    X,
+    /// Y轴移动
+    Y,
+    /// XY轴移动
+    XY,
+    /// 滚轮
+    Wheel,
+}
+
+impl MouseMask {
+    /// 获取掩码操作
+    pub(crate) fn operation(&self) -> Operation {
+        match self {
+            MouseMask::Left => Operation::MaskMouseLeft,
+            MouseMask::Right => Operation::MaskMouseRight,
+            MouseMask::Middle => Operation::MaskMouseMiddle,
+            MouseMask::Side1 => Operation::MaskMouseSide1,
+            MouseMask::Side2 => Operation::MaskMouseSide2,
+            MouseMask::X => Operation::MaskMouseX,
+            MouseMask::Y => Operation::MaskMouseY,
+            MouseMask::XY => Operation::MaskMouseXy,
+            MouseMask::Wheel => Operation::MaskMouseWheel,
+        }
+    }
+}
+
 /// 鼠标状态结构体
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct MouseState {
     pub position: MousePosition,
     pub left: bool,
@@ -248,6 +176,19 @@ pub struct MouseState {
     pub wheel: i32,
 }
 
+impl MouseState {
+    /// 查询指定按钮是否按下
+    pub fn is_pressed(&self, button: MouseButton) -> bool {
+        match button {
+            MouseButton::Left => self.left,
+            MouseButton::Right => self.right,
+            MouseButton::Middle => self.middle,
+            MouseButton::Side1 => self.side1,
+            MouseButton::Side2 => self.side2,
+        }
+    }
+}
+
 /// MiniUI模式枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MiniUIMode {
@@ -257,86 +198,357 @@ pub enum MiniUIMode {
     Enabled = 1,
 }
 
+/// 已加载的YOLO模型上下文
+///
+/// 厂商库没有说明上下文能否跨线程使用，上下文不实现 `Send`，只在 [`YoloThread`] 上创建、使用和释放。
+struct YoloContext(Box<rknn_context_t>);
+
+impl YoloContext {
+    /// 加载模型，失败时返回厂商库的错误码
+    fn load(backend: &dyn Backend, path: &CStr) -> Result<Self, i32> {
+        // rknn_context_t 只包含整数、浮点数和指针，全零是合法的初始状态
+        let mut ctx: Box<rknn_context_t> = Box::new(unsafe { std::mem::zeroed() });
+        let result = unsafe { backend.yolo_load_model(ctx.as_mut(), path) };
+        if result != 0 {
+            return Err(result);
+        }
+        Ok(Self(ctx))
+    }
+
+    fn release(mut self, backend: &dyn Backend) {
+        unsafe {
+            let _ = backend.yolo_release(self.0.as_mut());
+        }
+    }
+}
+
+/// 已加载的YOLO模型
+struct YoloModel {
+    path: CString,
+    /// 重新加载失败时为 `None`，下次恢复时再次尝试
+    context: Option<YoloContext>,
+}
+
+/// 在YOLO模型线程上执行的任务
+type YoloJob = Box<dyn FnOnce(&mut Option<YoloModel>) + Send>;
+
+/// 调用时传给模型线程的 `cv::Mat` 指针
+struct MatPtr(*mut cv_Mat);
+
+// SAFETY: 只在 `KmboxAI::yolo_interface_model` 中创建，调用方在模型线程执行完任务之前一直阻塞，
+// 调用期间 `cv::Mat` 只被模型线程访问；这里不涉及厂商库上下文的线程归属。
+unsafe impl Send for MatPtr {}
+
+/// YOLO模型线程
+///
+/// 模型上下文只存在于这个线程上，加载、推理、设备重新初始化后的重新加载和释放都作为任务发送给它，
+/// 调用方等待任务完成。句柄可以克隆，最后一个句柄销毁后线程释放模型并退出。
+#[derive(Clone)]
+struct YoloThread {
+    backend: Arc<dyn Backend>,
+    sender: Sender<YoloJob>,
+}
+
+impl YoloThread {
+    fn spawn(backend: Arc<dyn Backend>) -> KmboxResult<Self> {
+        let (sender, receiver) = mpsc::channel::<YoloJob>();
+        let thread_backend = backend.clone();
+        thread::Builder::new()
+            .name("kmbox-yolo".to_string())
+            .spawn(move || {
+                let mut model = None;
+                for job in receiver {
+                    job(&mut model);
+                }
+                release_model(thread_backend.as_ref(), &mut model);
+            })
+            .map_err(|e| KmboxError::io("启动YOLO模型线程", e))?;
+        Ok(Self { backend, sender })
+    }
+
+    /// 在模型线程上执行 `job` 并等待结果
+    fn call<T, F>(&self, job: F) -> KmboxResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn Backend, &mut Option<YoloModel>) -> KmboxResult<T> + Send + 'static,
+    {
+        let closed = || KmboxError::SystemError("YOLO模型线程已退出".to_string());
+        let backend = self.backend.clone();
+        let (reply, result) = mpsc::sync_channel(1);
+        self.sender
+            .send(Box::new(move |model: &mut Option<YoloModel>| {
+                let _ = reply.send(job(backend.as_ref(), model));
+            }))
+            .map_err(|_| closed())?;
+        result.recv().map_err(|_| closed())?
+    }
+
+    /// 释放之前的模型后加载 `path`
+    fn load(&self, path: CString) -> KmboxResult<()> {
+        self.call(move |backend, model| {
+            release_model(backend, model);
+            let ctx = YoloContext::load(backend, &path)
+                .map_err(|result| KmboxError::call(Operation::YoloLoadModel, result))?;
+            *model = Some(YoloModel {
+                path,
+                context: Some(ctx),
+            });
+            Ok(())
+        })
+    }
+
+    /// 设备重新初始化后重新加载已加载的模型
+    fn reload(&self) -> KmboxResult<()> {
+        self.call(|backend, model| {
+            let Some(model) = model.as_mut() else {
+                return Ok(());
+            };
+            // 设备重启后旧的上下文已经失效
+            if let Some(ctx) = model.context.take() {
+                ctx.release(backend);
+            }
+            let ctx = YoloContext::load(backend, &model.path)
+                .map_err(|code| KmboxError::call(Operation::YoloReloadModel, code))?;
+            model.context = Some(ctx);
+            Ok(())
+        })
+    }
+
+    fn release(&self) -> KmboxResult<()> {
+        self.call(|backend, model| {
+            release_model(backend, model);
+            Ok(())
+        })
+    }
+}
+
+fn release_model(backend: &dyn Backend, model: &mut Option<YoloModel>) {
+    if let Some(ctx) = model.take().and_then(|model| model.context) {
+        ctx.release(backend);
+    }
+}
+
+/// 设备重新初始化后需要恢复的设置
+#[derive(Default)]
+pub(crate) struct DeviceSettings {
+    /// 是否调用过 `run`
+    running: bool,
+    /// 最近一次设置的键鼠直通状态
+    enabled: Option<bool>,
+    /// 加载过YOLO模型后启动的模型线程
+    yolo: Option<YoloThread>,
+}
+
+impl DeviceSettings {
+    /// 在重新初始化后的设备上依次恢复运行状态、键鼠直通和YOLO模型
+    pub(crate) fn restore(&mut self, backend: &dyn Backend) -> KmboxResult<()> {
+        if self.running {
+            check_result(backend.run(), Operation::Run)?;
+        }
+        if let Some(enable) = self.enabled {
+            check_result(backend.km_enable(enable), Operation::Enable)?;
+        }
+        if let Some(yolo) = &self.yolo {
+            yolo.reload()?;
+        }
+        Ok(())
+    }
+}
+
 /// KmboxAI核心控制器
+///
+/// 实现了 `Send` 和 `Sync`。创建时启动一个写线程，键鼠调用（移动、按键、手势、监控和屏蔽）都由该线程按顺序执行；
+/// [`KmboxAI::handle`] 返回共用这个写线程的 [`KmboxHandle`]，多个线程的调用不会同时进入厂商库。
 pub struct KmboxAI {
+    backend: Arc<dyn Backend>,
+    writer: KmboxHandle,
     initialized: bool,
-    yolo_context: Option<*mut std::os::raw::c_void>,
+    settings: Arc<Mutex<DeviceSettings>>,
+    masks: Arc<MaskTracker>,
+    held: Arc<HeldTracker>,
 }
 
 impl KmboxAI {
     /// 创建新的KmboxAI实例
     pub fn new() -> KmboxResult<Self> {
+        Self::with_backend(default_backend())
+    }
+
+    /// 使用指定后端创建KmboxAI实例
+    pub fn with_backend(backend: Arc<dyn Backend>) -> KmboxResult<Self> {
+        let masks = Arc::new(MaskTracker::new(backend.clone()));
+        let held = HeldTracker::new(backend.clone());
+        let writer = KmboxHandle::with_trackers(backend.clone(), held.clone(), masks.clone())?;
         Ok(Self {
+            masks,
+            held,
+            writer,
+            backend,
             initialized: false,
-            yolo_context: None,
+            settings: Arc::new(Mutex::new(DeviceSettings::default())),
         })
     }
 
+    /// 获取当前使用的后端
+    pub fn backend(&self) -> &Arc<dyn Backend> {
+        &self.backend
+    }
+
     /// 初始化KmboxAI系统
     pub fn init(&mut self) -> KmboxResult<()> {
-        let result = unsafe { kmAI_Init() };
-        check_result(result, "初始化KmboxAI系统")?;
+        let result = self.backend.init();
+        check_result(result, Operation::Init)?;
         self.initialized = true;
         Ok(())
     }
 
     /// 运行KmboxAI系统
     pub fn run(&self) -> KmboxResult<()> {
-        let result = unsafe { kmAI_Run() };
-        check_result(result, "运行KmboxAI系统")
+        let result = self.backend.run();
+        check_result(result, Operation::Run)?;
+        self.settings().running = true;
+        Ok(())
+    }
+
+    fn settings(&self) -> MutexGuard<'_, DeviceSettings> {
+        self.settings.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 在写线程中执行调用并等待结果
+    fn call<T, F>(&self, call: F) -> KmboxResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Writer) -> KmboxResult<T> + Send + 'static,
+    {
+        self.writer.submit(call)?
+    }
+
+    /// 创建设备健康监控
+    ///
+    /// 监控与本实例共享后端、屏蔽记录和设备设置，
+    /// 重新初始化后会恢复 [`run`](Self::run)、[`enable`](Self::enable)、当前屏蔽和已加载的YOLO模型。
+    pub fn health_supervisor(&self, config: HealthConfig) -> HealthSupervisor {
+        HealthSupervisor::new(
+            self.backend.clone(),
+            self.masks.clone(),
+            self.settings.clone(),
+            config,
+        )
+    }
+
+    /// 获取可在线程间共享的设备句柄
+    ///
+    /// 句柄与本实例共用同一个写线程、按键记录和屏蔽记录，本实例和所有句柄的调用按提交顺序依次执行。
+    pub fn handle(&self) -> KmboxHandle {
+        self.writer.clone()
+    }
+
+    /// 创建异步键鼠接口，调用由本实例的写线程执行
+    #[cfg(feature = "async")]
+    pub fn async_handle(&self) -> AsyncKmbox {
+        AsyncKmbox::new(self.handle())
     }
 
     /// 启用/禁用KmboxAI
     pub fn enable(&self, enable: bool) -> KmboxResult<()> {
-        let result = unsafe { kmAI_kmEnable(if enable { 1 } else { 0 }) };
-        check_result(result, "设置KmboxAI启用状态")
+        let result = self.backend.km_enable(enable);
+        check_result(result, Operation::Enable)?;
+        self.settings().enabled = Some(enable);
+        Ok(())
     }
 
     /// 获取版本信息
     pub fn version(&self) -> KmboxResult<i32> {
-        let result = unsafe { kmAI_Version() };
+        let result = self.backend.version();
         Ok(result)
     }
 
-    /// 移动鼠标到指定位置
+    /// 鼠标相对移动
+    ///
+    /// 设备接口使用 16 位有符号整数，超出 `i16` 范围的位移返回参数错误
     pub fn mouse_move(&self, x: i32, y: i32) -> KmboxResult<()> {
-        let result = unsafe { kmAI_mouse_move(x, y) };
-        check_result(result, "移动鼠标")
+        self.writer.mouse_move(x, y)
+    }
+
+    /// 按规划器的路径和报告率平滑移动，完成后返回
+    ///
+    /// 位移不受 `i16` 范围限制，所有报告的位移之和等于 `(x, y)`。
+    /// 整个移动作为一次调用在写线程中执行，期间其他调用排队等待。
+    pub fn mouse_move_smooth(&self, x: i32, y: i32, planner: &MotionPlanner) -> KmboxResult<()> {
+        let planner = planner.clone();
+        self.call(move |writer| planner.execute(writer.backend.as_ref(), x, y))
+    }
+
+    /// 按发送时刻依次发送手势报告
+    ///
+    /// 发送失败时释放本次手势按下但尚未释放的按键。
+    /// 整个手势作为一次调用在写线程中执行，期间其他调用排队等待。
+    pub fn perform_gesture(&self, steps: &[GestureStep]) -> KmboxResult<()> {
+        let steps = steps.to_vec();
+        self.call(move |writer| gesture::perform(writer.backend.as_ref(), &writer.held, &steps))
+    }
+
+    /// 拖放，`from` 和 `to` 都是相对当前光标位置的位移
+    pub fn drag(
+        &self,
+        from: (i32, i32),
+        to: (i32, i32),
+        button: MouseButton,
+        planner: &GesturePlanner,
+    ) -> KmboxResult<()> {
+        self.perform_gesture(&planner.drag(from, to, button))
+    }
+
+    /// 连续点击 `count` 次（双击为 2、三击为 3）
+    pub fn multi_click(
+        &self,
+        button: MouseButton,
+        count: u32,
+        planner: &GesturePlanner,
+    ) -> KmboxResult<()> {
+        self.perform_gesture(&planner.click(button, count))
+    }
+
+    /// 长按
+    pub fn long_press(&self, button: MouseButton, planner: &GesturePlanner) -> KmboxResult<()> {
+        self.perform_gesture(&planner.long_press(button))
+    }
+
+    /// 按行数平滑滚动，正数向上
+    pub fn scroll_lines(&self, lines: i32, planner: &GesturePlanner) -> KmboxResult<()> {
+        self.perform_gesture(&planner.scroll_lines(lines))
+    }
+
+    /// 创建跟踪主机光标绝对位置的虚拟光标
+    pub fn virtual_cursor(&self, model: CursorModel) -> VirtualCursor {
+        VirtualCursor::new(self.backend.clone(), model)
+    }
+
+    /// 在后台线程平滑移动，返回的句柄销毁时停止移动
+    pub fn spawn_mouse_move(
+        &self,
+        x: i32,
+        y: i32,
+        planner: &MotionPlanner,
+    ) -> KmboxResult<MotionHandle> {
+        planner.spawn(self.backend.clone(), x, y)
     }
 
     /// 鼠标按键操作
-    #[allow(clippy::only_used_in_recursion)]
+    ///
+    /// 点击时按下和释放作为一次调用执行，中间不会插入其他线程的调用。
     pub fn mouse_button(&self, button: MouseButton, action: MouseAction) -> KmboxResult<()> {
-        let isdown = match action {
-            MouseAction::Press => 1,
-            MouseAction::Release => 0,
-            MouseAction::Click => {
-                // 先按下再释放
-                self.mouse_button(button, MouseAction::Press)?;
-                std::thread::sleep(std::time::Duration::from_millis(50));
-                return self.mouse_button(button, MouseAction::Release);
-            }
-        };
-
-        let result = unsafe {
-            match button {
-                MouseButton::Left => kmAI_mouse_left(isdown),
-                MouseButton::Right => kmAI_mouse_right(isdown),
-                MouseButton::Middle => kmAI_mouse_middle(isdown),
-                MouseButton::Side1 => kmAI_mouse_side1(isdown),
-                MouseButton::Side2 => kmAI_mouse_side2(isdown),
-            }
-        };
-        check_result(result, "鼠标按键操作")
+        self.writer.mouse_button(button, action)
     }
 
     /// 鼠标滚轮操作
     pub fn mouse_wheel(&self, wheel: i32) -> KmboxResult<()> {
-        let result = unsafe { kmAI_mouse_wheel(wheel) };
-        check_result(result, "鼠标滚轮操作")
+        self.writer.mouse_wheel(wheel)
     }
 
     /// 综合鼠标操作
+    ///
+    /// 只能控制左、右、中键，侧键会被释放；需要完整控制时使用 [`KmboxAI::send_mouse_report`]。
     pub fn mouse_all(
         &self,
         x: i32,
@@ -346,29 +558,32 @@ impl KmboxAI {
         middle: bool,
         wheel: i32,
     ) -> KmboxResult<()> {
-        let result = unsafe {
-            kmAI_mouse_all(
-                x,
-                y,
-                if left { 1 } else { 0 },
-                if right { 1 } else { 0 },
-                if middle { 1 } else { 0 },
-                wheel,
-            )
-        };
-        check_result(result, "综合鼠标操作")
+        let mut buttons = MouseButtons::NONE;
+        buttons.set(MouseButton::Left, left);
+        buttons.set(MouseButton::Right, right);
+        buttons.set(MouseButton::Middle, middle);
+        let report = MouseReport::new()
+            .with_buttons(buttons)
+            .with_move(x, y)
+            .with_wheel(wheel);
+        self.send_mouse_report(&report)
+    }
+
+    /// 以一个报告同时发送按键、位移和滚轮
+    ///
+    /// 报告中没有的按键会被释放。
+    pub fn send_mouse_report(&self, report: &MouseReport) -> KmboxResult<()> {
+        self.writer.send_mouse_report(*report)
     }
 
     /// 键盘按键按下
     pub fn key_down(&self, vkey: i32) -> KmboxResult<()> {
-        let result = unsafe { kmAI_keydown(vkey) };
-        check_result(result, "键盘按键按下")
+        self.writer.key_down(vkey)
     }
 
     /// 键盘按键释放
     pub fn key_up(&self, vkey: i32) -> KmboxResult<()> {
-        let result = unsafe { kmAI_keyup(vkey) };
-        check_result(result, "键盘按键释放")
+        self.writer.key_up(vkey)
     }
 
     /// 键盘按键点击
@@ -380,182 +595,254 @@ impl KmboxAI {
 
     /// 初始化键盘
     pub fn key_init(&self) -> KmboxResult<()> {
-        let result = unsafe { kmAI_keyinit() };
-        check_result(result, "初始化键盘")
+        self.writer.key_init()
+    }
+
+    /// 获取通过本实例按下、尚未释放的鼠标按键和键盘按键
+    pub fn held_inputs(&self) -> HeldInputs {
+        self.held.held()
+    }
+
+    /// 释放通过本实例按下的所有鼠标按键和键盘按键，并初始化键盘
+    pub fn release_all(&self) -> KmboxResult<()> {
+        self.call(|writer| {
+            writer.held.release_all();
+            writer.key_init()
+        })
+    }
+
+    /// 在写线程中读取一个物理鼠标按键的状态
+    fn monitor_mouse_button(&self, button: MouseButton) -> KmboxResult<bool> {
+        self.call(move |writer| {
+            let result = writer.backend.monitor_mouse_button(button);
+            check_state(result, button.monitor_operation())
+        })
     }
 
     /// 监控鼠标左键状态
     pub fn monitor_mouse_left(&self) -> KmboxResult<bool> {
-        let result = unsafe { kmAI_monitor_mouse_left() };
-        Ok(result != 0)
+        self.monitor_mouse_button(MouseButton::Left)
     }
 
     /// 监控鼠标中键状态
     pub fn monitor_mouse_middle(&self) -> KmboxResult<bool> {
-        let result = unsafe { kmAI_monitor_mouse_middle() };
-        Ok(result != 0)
+        self.monitor_mouse_button(MouseButton::Middle)
     }
 
     /// 监控鼠标右键状态
     pub fn monitor_mouse_right(&self) -> KmboxResult<bool> {
-        let result = unsafe { kmAI_monitor_mouse_right() };
-        Ok(result != 0)
+        self.monitor_mouse_button(MouseButton::Right)
     }
 
     /// 监控鼠标侧键1状态
     pub fn monitor_mouse_side1(&self) -> KmboxResult<bool> {
-        let result = unsafe { kmAI_monitor_mouse_side1() };
-        Ok(result != 0)
+        self.monitor_mouse_button(MouseButton::Side1)
     }
 
     /// 监控鼠标侧键2状态
     pub fn monitor_mouse_side2(&self) -> KmboxResult<bool> {
-        let result = unsafe { kmAI_monitor_mouse_side2() };
-        Ok(result != 0)
+        self.monitor_mouse_button(MouseButton::Side2)
     }
 
     /// 监控鼠标位置
     pub fn monitor_mouse_position(&self) -> KmboxResult<MousePosition> {
-        let mut x = 0i32;
-        let mut y = 0i32;
-        let result = unsafe { kmAI_monitor_mouse_xy(&mut x, &mut y) };
-        check_result(result, "监控鼠标位置")?;
-        Ok(MousePosition { x, y })
+        self.call(|writer| {
+            let mut x = 0i32;
+            let mut y = 0i32;
+            let result = writer.backend.monitor_mouse_xy(&mut x, &mut y);
+            check_result(result, Operation::MonitorMouseXy)?;
+            Ok(MousePosition { x, y })
+        })
     }
 
     /// 监控鼠标滚轮
     pub fn monitor_mouse_wheel(&self) -> KmboxResult<i32> {
-        let mut wheel = 0i32;
-        let result = unsafe { kmAI_monitor_mouse_wheel(&mut wheel) };
-        check_result(result, "监控鼠标滚轮")?;
-        Ok(wheel)
+        self.call(|writer| {
+            let mut wheel = 0i32;
+            let result = writer.backend.monitor_mouse_wheel(&mut wheel);
+            check_result(result, Operation::MonitorMouseWheel)?;
+            Ok(wheel)
+        })
     }
 
     /// 监控键盘按键
     pub fn monitor_keyboard(&self, vk_key: i16) -> KmboxResult<bool> {
-        let result = unsafe { kmAI_monitor_keyboard(vk_key) };
-        Ok(result != 0)
+        self.writer.monitor_keyboard(vk_key)
     }
 
     /// 获取完整鼠标状态
     pub fn get_mouse_state(&self) -> KmboxResult<MouseState> {
-        let position = self.monitor_mouse_position()?;
-        let left = self.monitor_mouse_left()?;
-        let right = self.monitor_mouse_right()?;
-        let middle = self.monitor_mouse_middle()?;
-        let side1 = self.monitor_mouse_side1()?;
-        let side2 = self.monitor_mouse_side2()?;
-        let wheel = self.monitor_mouse_wheel()?;
-
-        Ok(MouseState {
-            position,
-            left,
-            right,
-            middle,
-            side1,
-            side2,
-            wheel,
-        })
+        self.writer.get_mouse_state()
+    }
+
+    /// 以 `Stream` 形式启动物理键鼠事件监听
+    #[cfg(feature = "async")]
+    pub fn event_stream(&self, config: MonitorConfig) -> KmboxResult<EventStream> {
+        EventStream::start(self.backend.clone(), config)
+    }
+
+    /// 启动合并位移、限制报告频率的输出调度器
+    pub fn output_scheduler(&self, interval: Duration) -> KmboxResult<OutputScheduler> {
+        OutputScheduler::start(self.backend.clone(), interval)
+    }
+
+    /// 启动物理键鼠事件监听
+    pub fn input_monitor(&self, config: MonitorConfig) -> KmboxResult<InputMonitor> {
+        InputMonitor::start(self.backend.clone(), config)
+    }
+
+    /// 创建热键管理器
+    ///
+    /// 拦截热键设置的按键屏蔽记录在本实例中，
+    /// 与 [`active_masks`](Self::active_masks)、[`restore_masks`](Self::restore_masks)、
+    /// panic 钩子和健康监控的重新设置共用。
+    pub fn hotkey_manager(&self) -> HotkeyManager {
+        HotkeyManager::with_masks(self.backend.clone(), self.masks.clone())
+    }
+
+    /// 掩码物理鼠标输入
+    pub fn mask_mouse(&self, mask: MouseMask, enable: bool) -> KmboxResult<()> {
+        self.writer.mask_mouse(mask, enable)
     }
 
     /// 掩码鼠标左键
     pub fn mask_mouse_left(&self, enable: bool) -> KmboxResult<()> {
-        let result = unsafe { kmAI_mask_mouse_left(if enable { 1 } else { 0 }) };
-        check_result(result, "掩码鼠标左键")
+        self.mask_mouse(MouseMask::Left, enable)
     }
 
     /// 掩码鼠标右键
     pub fn mask_mouse_right(&self, enable: bool) -> KmboxResult<()> {
-        let result = unsafe { kmAI_mask_mouse_right(if enable { 1 } else { 0 }) };
-        check_result(result, "掩码鼠标右键")
+        self.mask_mouse(MouseMask::Right, enable)
     }
 
     /// 掩码鼠标中键
     pub fn mask_mouse_middle(&self, enable: bool) -> KmboxResult<()> {
-        let result = unsafe { kmAI_mask_mouse_middle(if enable { 1 } else { 0 }) };
-        check_result(result, "掩码鼠标中键")
+        self.mask_mouse(MouseMask::Middle, enable)
     }
 
     /// 掩码鼠标侧键1
     pub fn mask_mouse_side1(&self, enable: bool) -> KmboxResult<()> {
-        let result = unsafe { kmAI_mask_mouse_side1(if enable { 1 } else { 0 }) };
-        check_result(result, "掩码鼠标侧键1")
+        self.mask_mouse(MouseMask::Side1, enable)
     }
 
     /// 掩码鼠标侧键2
     pub fn mask_mouse_side2(&self, enable: bool) -> KmboxResult<()> {
-        let result = unsafe { kmAI_mask_mouse_side2(if enable { 1 } else { 0 }) };
-        check_result(result, "掩码鼠标侧键2")
+        self.mask_mouse(MouseMask::Side2, enable)
     }
 
     /// 掩码鼠标X轴移动
     pub fn mask_mouse_x(&self, enable: bool) -> KmboxResult<()> {
-        let result = unsafe { kmAI_mask_mouse_x(if enable { 1 } else { 0 }) };
-        check_result(result, "掩码鼠标X轴移动")
+        self.mask_mouse(MouseMask::X, enable)
     }
 
     /// 掩码鼠标Y轴移动
     pub fn mask_mouse_y(&self, enable: bool) -> KmboxResult<()> {
-        let result = unsafe { kmAI_mask_mouse_y(if enable { 1 } else { 0 }) };
-        check_result(result, "掩码鼠标Y轴移动")
+        self.mask_mouse(MouseMask::Y, enable)
     }
 
     /// 掩码鼠标XY轴移动
     pub fn mask_mouse_xy(&self, enable: bool) -> KmboxResult<()> {
-        let result = unsafe { kmAI_mask_mouse_xy(if enable { 1 } else { 0 }) };
-        check_result(result, "掩码鼠标XY轴移动")
+        self.mask_mouse(MouseMask::XY, enable)
     }
 
     /// 掩码鼠标滚轮
     pub fn mask_mouse_wheel(&self, enable: bool) -> KmboxResult<()> {
-        let result = unsafe { kmAI_mask_mouse_wheel(if enable { 1 } else { 0 }) };
-        check_result(result, "掩码鼠标滚轮")
+        self.mask_mouse(MouseMask::Wheel, enable)
     }
 
     /// 掩码键盘按键
     pub fn mask_keyboard(&self, vkey: i16) -> KmboxResult<()> {
-        let result = unsafe { kmAI_mask_keyboard(vkey) };
-        check_result(result, "掩码键盘按键")
+        self.writer.mask_keyboard(vkey)
     }
 
     /// 取消掩码键盘按键
     pub fn unmask_keyboard(&self, vkey: i16) -> KmboxResult<()> {
-        let result = unsafe { kmAI_unmask_keyboard(vkey) };
-        check_result(result, "取消掩码键盘按键")
+        self.writer.unmask_keyboard(vkey)
     }
 
     /// 取消所有掩码
     pub fn unmask_all(&self) -> KmboxResult<()> {
-        let result = unsafe { kmAI_unmask_all() };
-        check_result(result, "取消所有掩码")
+        self.writer.unmask_all()
+    }
+
+    /// 掩码物理鼠标输入，返回的守卫销毁时自动解除
+    pub fn mask_mouse_guard(&self, mask: MouseMask) -> KmboxResult<MaskGuard> {
+        MaskGuard::new(self.masks.clone(), MaskTarget::Mouse(mask))
+    }
+
+    /// 掩码键盘按键，返回的守卫销毁时自动解除
+    pub fn mask_keyboard_guard(&self, vkey: i16) -> KmboxResult<MaskGuard> {
+        MaskGuard::new(self.masks.clone(), MaskTarget::Keyboard(vkey))
+    }
+
+    /// 获取当前通过本实例设置的所有掩码
+    pub fn active_masks(&self) -> Vec<MaskTarget> {
+        self.masks.active()
+    }
+
+    /// 解除通过本实例设置的所有掩码
+    ///
+    /// 与 [`KmboxAI::unmask_all`] 不同，其他程序或模块设置的掩码不受影响。
+    pub fn restore_masks(&self) -> KmboxResult<()> {
+        self.masks.restore()
+    }
+
+    /// 安装 panic 钩子，任意线程 panic 时解除通过本实例设置的所有掩码
+    ///
+    /// 适用于 `panic = "abort"` 或掩码由其他线程持有的场景；
+    /// 正常展开时 [`MaskGuard`] 和 `KmboxAI` 的析构已经会解除掩码。
+    /// 进程中只安装一个钩子，多次调用不会叠加。
+    pub fn install_mask_panic_hook(&self) {
+        install_panic_hook(&self.masks);
     }
 
     /// 启用/禁用MiniUI
     pub fn miniui_enable(&self, mode: MiniUIMode) -> KmboxResult<()> {
-        let result = unsafe { kmAI_MiniUI_Enable(mode as i32) };
-        check_result(result, "设置MiniUI状态")
+        let result = self.backend.miniui_enable(mode as i32);
+        check_result(result, Operation::MiniuiEnable)
     }
 
     /// 锁定MiniUI用户界面
     pub fn miniui_user_lock(&self) -> KmboxResult<()> {
-        let result = unsafe { kmAI_MiniUI_UserLock() };
-        check_result(result, "锁定MiniUI用户界面")
+        let result = self.backend.miniui_user_lock();
+        check_result(result, Operation::MiniuiUserLock)
     }
 
     /// 解锁MiniUI用户界面
     pub fn miniui_user_unlock(&self) -> KmboxResult<()> {
-        let result = unsafe { kmAI_MiniUI_UserUnLock() };
-        check_result(result, "解锁MiniUI用户界面")
+        let result = self.backend.miniui_user_unlock();
+        check_result(result, Operation::MiniuiUserUnlock)
     }
 
     /// 在LCD上显示图像
     ///
     /// # Safety
     ///
-    /// 调用者必须确保 `img` 是一个有效的图像指针，且指向的内存在使用期间保持有效。
-    pub unsafe fn miniui_lcd_display(&self, img: *mut std::os::raw::c_void) -> KmboxResult<()> {
-        let result = kmAI_MiniUI_LCDdisplay(img);
-        check_result(result, "LCD显示图像")
+    /// 调用者必须确保 `img` 指向一个有效的 `cv::Mat` 对象，且在调用期间保持有效。
+    pub unsafe fn miniui_lcd_display(&self, img: *mut cv_Mat) -> KmboxResult<()> {
+        let result = self.backend.miniui_lcd_display(img);
+        check_result(result, Operation::MiniuiLcdDisplay)
+    }
+
+    /// 在LCD上显示图像，保持宽高比缩放并留黑边
+    ///
+    /// 支持 [`ImageFormat`](crate::vision::ImageFormat) 的所有格式，
+    /// 转换由 [`LcdFrame::render`] 在 Rust 中完成，不需要 `cv::Mat`。
+    pub fn display(&self, image: &Image) -> KmboxResult<()> {
+        self.display_with(image, &DisplayOptions::default())
+    }
+
+    /// 按指定的缩放方式和背景色在LCD上显示图像
+    pub fn display_with(&self, image: &Image, options: &DisplayOptions) -> KmboxResult<()> {
+        let frame = LcdFrame::render(image, options)?;
+        self.display_frame(&frame)
+    }
+
+    /// 在LCD上显示已经转换好的一帧
+    pub fn display_frame(&self, frame: &LcdFrame) -> KmboxResult<()> {
+        self.backend
+            .lcd_write(frame)
+            .map_err(|e| KmboxError::io("LCD显示图像", e))
     }
 
     /// 加载YOLO模型
@@ -563,78 +850,161 @@ impl KmboxAI {
         let path_cstr = CString::new(model_path)
             .map_err(|e| KmboxError::ParameterError(format!("无效的模型路径: {}", e)))?;
 
-        let ctx = unsafe { kmAI_YOLO_Loadmodel(path_cstr.as_ptr()) };
-        if ctx.is_null() {
-            return Err(KmboxError::ModelError("加载YOLO模型失败".to_string()));
-        }
-
-        self.yolo_context = Some(ctx);
-        Ok(())
+        let yolo = {
+            let mut settings = self.settings();
+            match &settings.yolo {
+                Some(yolo) => yolo.clone(),
+                None => settings
+                    .yolo
+                    .insert(YoloThread::spawn(self.backend.clone())?)
+                    .clone(),
+            }
+        };
+        // 在模型线程上释放之前加载的模型并加载新模型
+        yolo.load(path_cstr)
     }
 
-    /// 接口YOLO模型
-    pub fn yolo_interface_model(&self) -> KmboxResult<()> {
-        if let Some(ctx) = self.yolo_context {
-            let result = unsafe { kmAI_YOLO_InterfaceModel(ctx) };
-            check_result(result, "接口YOLO模型")
-        } else {
-            Err(KmboxError::ModelError("YOLO模型未加载".to_string()))
-        }
+    /// 对图像执行YOLO推理
+    ///
+    /// # Safety
+    ///
+    /// 调用者必须确保 `img` 指向一个有效的 `cv::Mat` 对象，且在调用期间保持有效。
+    /// 推理在YOLO模型线程上执行，调用期间 `img` 不能被其他线程访问。
+    pub unsafe fn yolo_interface_model(
+        &mut self,
+        img: *mut cv_Mat,
+    ) -> KmboxResult<object_detected_list> {
+        let not_loaded = || KmboxError::ModelError("YOLO模型未加载".to_string());
+        let yolo = self.settings().yolo.clone().ok_or_else(not_loaded)?;
+        let img = MatPtr(img);
+        yolo.call(move |backend, model| {
+            let img = img;
+            let ctx = model
+                .as_mut()
+                .and_then(|model| model.context.as_mut())
+                .ok_or_else(not_loaded)?;
+            // SAFETY: 调用方保证 `img` 在本次调用期间有效，上下文由本线程创建
+            let mut out: object_detected_list = unsafe { std::mem::zeroed() };
+            let result = unsafe { backend.yolo_interface_model(ctx.0.as_mut(), img.0, &mut out) };
+            check_result(result, Operation::YoloInference)?;
+            Ok(out)
+        })
     }
 
-    /// 绘制矩形
-    pub fn yolo_draw_rectangle(
+    /// 在图像上绘制矩形
+    ///
+    /// # Safety
+    ///
+    /// 调用者必须确保 `img` 指向一个有效的 `cv::Mat` 对象，且在调用期间保持有效。
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn yolo_draw_rectangle(
         &self,
+        img: *mut cv_Mat,
         x: i32,
         y: i32,
         w: i32,
         h: i32,
-        color: i32,
+        color: u32,
         thickness: i32,
-    ) -> KmboxResult<()> {
-        let result = unsafe { kmAI_YOLO_DrawRectangle(x, y, w, h, color, thickness) };
-        check_result(result, "绘制矩形")
+    ) {
+        self.backend
+            .yolo_draw_rectangle(img, x, y, w, h, color, thickness);
     }
 
-    /// 绘制文本
-    pub fn yolo_draw_text(
+    /// 在图像上绘制文本
+    ///
+    /// # Safety
+    ///
+    /// 调用者必须确保 `img` 指向一个有效的 `cv::Mat` 对象，且在调用期间保持有效。
+    pub unsafe fn yolo_draw_text(
         &self,
+        img: *mut cv_Mat,
         text: &str,
         x: i32,
         y: i32,
-        color: i32,
-        scale: i32,
-        thickness: i32,
+        color: u32,
+        font_size: i32,
     ) -> KmboxResult<()> {
         let text_cstr = CString::new(text)
             .map_err(|e| KmboxError::ParameterError(format!("无效的文本: {}", e)))?;
 
-        let result =
-            unsafe { kmAI_YOLO_DrawText(text_cstr.as_ptr(), x, y, color, scale, thickness) };
-        check_result(result, "绘制文本")
+        self.backend
+            .yolo_draw_text(img, &text_cstr, x, y, color, font_size);
+        Ok(())
+    }
+
+    /// 在模型线程上释放已加载的YOLO模型，模型线程随后退出
+    fn yolo_release(&mut self) {
+        let yolo = self.settings().yolo.take();
+        if let Some(yolo) = yolo {
+            let _ = yolo.release();
+        }
     }
 
     /// 重启系统
     pub fn reboot(&self) -> KmboxResult<()> {
-        let result = unsafe { kmAI_reboot() };
-        check_result(result, "重启系统")
+        let result = self.backend.reboot();
+        check_result(result, Operation::Reboot)
     }
 }
 
+/// 从后端读取一次完整的物理鼠标状态
+pub(crate) fn read_mouse_state(backend: &dyn Backend) -> KmboxResult<MouseState> {
+    let mut x = 0i32;
+    let mut y = 0i32;
+    let result = backend.monitor_mouse_xy(&mut x, &mut y);
+    check_result(result, Operation::MonitorMouseXy)?;
+
+    let mut wheel = 0i32;
+    let result = backend.monitor_mouse_wheel(&mut wheel);
+    check_result(result, Operation::MonitorMouseWheel)?;
+
+    let pressed = |button: MouseButton| {
+        check_state(
+            backend.monitor_mouse_button(button),
+            button.monitor_operation(),
+        )
+    };
+    Ok(MouseState {
+        position: MousePosition { x, y },
+        left: pressed(MouseButton::Left)?,
+        right: pressed(MouseButton::Right)?,
+        middle: pressed(MouseButton::Middle)?,
+        side1: pressed(MouseButton::Side1)?,
+        side2: pressed(MouseButton::Side2)?,
+        wheel,
+    })
+}
+
 impl Drop for KmboxAI {
     fn drop(&mut self) {
-        // 释放YOLO上下文
-        if let Some(ctx) = self.yolo_context {
-            unsafe {
-                let _ = kmAI_YOLO_Release(ctx);
-            }
+        // 在写线程中执行，不会与句柄仍在排队的调用交错；写线程已经退出时直接执行
+        if self.writer.submit(release_device).is_err() {
+            release_device(&Writer {
+                backend: self.backend.clone(),
+                held: self.held.clone(),
+                masks: self.masks.clone(),
+            });
         }
+        // 释放YOLO上下文
+        self.yolo_release();
     }
 }
 
+/// 销毁 `KmboxAI` 时复位设备
+fn release_device(writer: &Writer) {
+    // 释放仍处于按下状态的按键并复位键盘
+    writer.held.release_all();
+    writer.backend.key_init();
+    // 恢复掩码，仍然存活的守卫销毁时不再重复解除；再解除设备上剩余的全部屏蔽
+    let _ = writer.masks.restore();
+    writer.backend.unmask_all();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
 
     #[test]
     fn test_kmbox_ai_creation() {
@@ -683,4 +1053,165 @@ mod tests {
         assert!(state.left);
         assert!(!state.right);
     }
+
+    #[test]
+    fn test_mouse_control_with_simulated_backend() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let mut kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        kmbox.init().unwrap();
+        kmbox.enable(true).unwrap();
+        kmbox.mouse_move(100, -20).unwrap();
+        kmbox
+            .mouse_button(MouseButton::Right, MouseAction::Click)
+            .unwrap();
+        kmbox.mouse_wheel(3).unwrap();
+
+        let state = sim.state();
+        assert!(state.initialized);
+        assert!(state.enabled);
+        assert_eq!(state.cursor, MousePosition { x: 100, y: -20 });
+        assert!(state.mouse_buttons.is_empty());
+        assert_eq!(
+            state.reports,
+            vec![
+                HidReport::Move { x: 100, y: -20 },
+                HidReport::Button {
+                    button: MouseButton::Right,
+                    down: true
+                },
+                HidReport::Button {
+                    button: MouseButton::Right,
+                    down: false
+                },
+                HidReport::Wheel(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mouse_all_and_move_range() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        kmbox.mouse_all(5, 6, true, false, true, -1).unwrap();
+        assert!(kmbox.mouse_move(i32::from(i16::MAX) + 1, 0).is_err());
+
+        let state = sim.state();
+        assert_eq!(
+            state.reports,
+            vec![HidReport::MouseAll {
+                buttons: 0b101,
+                x: 5,
+                y: 6,
+                wheel: -1
+            }]
+        );
+        assert!(state.mouse_buttons.contains(&MouseButton::Middle));
+    }
+
+    #[test]
+    fn test_monitor_with_simulated_backend() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        sim.press_physical_button(MouseButton::Left);
+        sim.move_physical(7, -3);
+        sim.press_physical_key(4);
+
+        let state = kmbox.get_mouse_state().unwrap();
+        assert!(state.left);
+        assert!(!state.right);
+        assert_eq!(state.position, MousePosition { x: 7, y: -3 });
+        assert!(kmbox.monitor_keyboard(4).unwrap());
+        assert!(!kmbox.monitor_keyboard(5).unwrap());
+
+        kmbox.mask_mouse_xy(true).unwrap();
+        kmbox.mask_keyboard(4).unwrap();
+        assert!(sim.state().mouse_masks.contains(&MouseMask::XY));
+        kmbox.unmask_all().unwrap();
+        assert!(sim.state().keyboard_masks.is_empty());
+
+        // 设备故障时监控接口返回错误，而不是把错误码当作按下
+        sim.inject_fault(-4);
+        assert!(kmbox.get_mouse_state().is_err());
+        assert!(kmbox.monitor_mouse_left().is_err());
+        assert!(kmbox.monitor_keyboard(4).is_err());
+    }
+
+    #[test]
+    fn test_yolo_errors_keep_vendor_code() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let mut kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+
+        let err = kmbox.yolo_load_model("missing.rknn").unwrap_err();
+        assert_eq!(err.operation(), Some("yolo_load_model"));
+        assert_eq!(err.error_code(), None);
+        assert_eq!(err.code(), Some(-1));
+
+        sim.add_model("yolo.rknn");
+        kmbox.yolo_load_model("yolo.rknn").unwrap();
+        // 模拟设备没有NPU，推理总是返回 -1
+        let err = unsafe { kmbox.yolo_interface_model(std::ptr::null_mut()) }.unwrap_err();
+        assert_eq!(err.operation(), Some("yolo_interface_model"));
+        assert_eq!(err.code(), Some(-1));
+    }
+
+    #[test]
+    fn test_masks_restored_on_drop_and_panic() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        let guard = kmbox.mask_mouse_guard(MouseMask::XY).unwrap();
+        kmbox.mask_keyboard(4).unwrap();
+        assert_eq!(
+            kmbox.active_masks(),
+            vec![MaskTarget::Mouse(MouseMask::XY), MaskTarget::Keyboard(4)]
+        );
+        drop(kmbox);
+        assert!(sim.state().mouse_masks.is_empty());
+        assert!(sim.state().keyboard_masks.is_empty());
+        drop(guard);
+
+        // 任意线程 panic 时钩子都会解除掩码
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        kmbox.install_mask_panic_hook();
+        std::mem::forget(kmbox.mask_keyboard_guard(41).unwrap());
+        let result = std::thread::spawn(|| panic!("模拟崩溃")).join();
+        assert!(result.is_err());
+        assert!(sim.state().keyboard_masks.is_empty());
+    }
+
+    #[test]
+    fn test_held_inputs_released_on_drop() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        kmbox
+            .mouse_button(MouseButton::Left, MouseAction::Press)
+            .unwrap();
+        kmbox.mouse_all(0, 0, false, true, false, 0).unwrap();
+        kmbox.key_down(4).unwrap();
+        kmbox.key_down(5).unwrap();
+        kmbox.key_up(5).unwrap();
+
+        let held = kmbox.held_inputs();
+        assert_eq!(
+            held.buttons.into_iter().collect::<Vec<_>>(),
+            vec![MouseButton::Right]
+        );
+        assert_eq!(held.keys.into_iter().collect::<Vec<_>>(), vec![4]);
+
+        // 销毁时还会解除设备上剩余的全部屏蔽
+        sim.mask_keyboard(7);
+        let before = sim.reports().len();
+        drop(kmbox);
+        assert!(sim.state().keyboard_masks.is_empty());
+        assert_eq!(
+            sim.reports()[before..],
+            vec![
+                HidReport::Button {
+                    button: MouseButton::Right,
+                    down: false
+                },
+                HidReport::KeyUp(4),
+                HidReport::KeyInit,
+            ]
+        );
+    }
 }
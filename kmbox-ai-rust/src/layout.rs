@@ -0,0 +1,583 @@
+//! 键盘布局模块
+//!
+//! `soft_String` 按美式布局发送 ASCII 字符，主机使用德语、法语或日语布局时会输入错误的字符。
+//! 本模块在 Rust 中把文本转换为按键序列：按主机的键盘布局查找每个字符对应的物理按键和修饰键，
+//! 重音字符通过死键（dead key）组合输入，例如法语布局下的 `ê` 为 `^` 死键加 `e`。
+//!
+//! 支持的布局：美式（US）、英式（UK）、德语（DE）、法语（FR）、日语（JP，仅限半角字符）。
+//! 无法在布局上输入的字符（如日文假名需要输入法）在发送任何按键之前返回错误。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::keyboard::Keyboard;
+//! use kmbox_ai_rust::layout::{KeyboardLayout, TextTyper};
+//! use std::time::Duration;
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let keyboard = Keyboard::new()?;
+//! let typer = TextTyper::new(KeyboardLayout::De).with_char_delay(Duration::from_millis(30));
+//! keyboard.type_text("Grüße, Zoë!", &typer)?;
+//!
+//! // 只规划不发送
+//! let strokes = KeyboardLayout::Fr.keystrokes('ê')?;
+//! assert_eq!(strokes.len(), 2);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{KmboxError, KmboxResult};
+use crate::keyboard::Key;
+use crate::report::Modifiers;
+use std::fmt;
+use std::time::Duration;
+
+/// 一次按键：按住修饰键后点击按键
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keystroke {
+    /// 按键
+    pub key: Key,
+    /// 同时按住的修饰键
+    pub modifiers: Modifiers,
+}
+
+impl Keystroke {
+    /// 创建按键
+    pub fn new(key: Key, modifiers: Modifiers) -> Self {
+        Self { key, modifiers }
+    }
+
+    /// 需要同时按下的按键：修饰键在前
+    pub fn keys(&self) -> Vec<Key> {
+        let mut keys: Vec<Key> = self.modifiers.keys().collect();
+        keys.push(self.key.clone());
+        keys
+    }
+}
+
+/// 各层的修饰键：普通、Shift、AltGr
+const LEVELS: [Modifiers; 3] = [Modifiers::NONE, Modifiers::LEFT_SHIFT, Modifiers::RIGHT_ALT];
+
+/// 布局表
+///
+/// `keys` 中每个字符串依次为普通、Shift、AltGr 层输出的字符，空格表示该层没有字符；
+/// 死键单独列在 `dead` 中；字母默认位于同名按键上，`letters` 列出位置不同的字母。
+struct Table {
+    keys: &'static [(Key, &'static str)],
+    dead: &'static [(char, Key, Modifiers)],
+    letters: &'static [(char, Key)],
+}
+
+const US: Table = Table {
+    keys: &[
+        (Key::GraveAccentTilde, "`~"),
+        (Key::Num1, "1!"),
+        (Key::Num2, "2@"),
+        (Key::Num3, "3#"),
+        (Key::Num4, "4$"),
+        (Key::Num5, "5%"),
+        (Key::Num6, "6^"),
+        (Key::Num7, "7&"),
+        (Key::Num8, "8*"),
+        (Key::Num9, "9("),
+        (Key::Num0, "0)"),
+        (Key::Minus, "-_"),
+        (Key::Equal, "=+"),
+        (Key::LeftBracket, "[{"),
+        (Key::RightBracket, "]}"),
+        (Key::Backslash, "\\|"),
+        (Key::Semicolon, ";:"),
+        (Key::Quote, "'\""),
+        (Key::Comma, ",<"),
+        (Key::Period, ".>"),
+        (Key::Slash, "/?"),
+    ],
+    dead: &[],
+    letters: &[],
+};
+
+const UK: Table = Table {
+    keys: &[
+        (Key::GraveAccentTilde, "`¬¦"),
+        (Key::Num1, "1!"),
+        (Key::Num2, "2\""),
+        (Key::Num3, "3£"),
+        (Key::Num4, "4$€"),
+        (Key::Num5, "5%"),
+        (Key::Num6, "6^"),
+        (Key::Num7, "7&"),
+        (Key::Num8, "8*"),
+        (Key::Num9, "9("),
+        (Key::Num0, "0)"),
+        (Key::Minus, "-_"),
+        (Key::Equal, "=+"),
+        (Key::LeftBracket, "[{"),
+        (Key::RightBracket, "]}"),
+        (Key::NonusNumberSignTilde, "#~"),
+        (Key::Semicolon, ";:"),
+        (Key::Quote, "'@"),
+        (Key::Comma, ",<"),
+        (Key::Period, ".>"),
+        (Key::Slash, "/?"),
+        (Key::NonusBackSlashVerticalBar, "\\|"),
+    ],
+    dead: &[],
+    letters: &[],
+};
+
+const DE: Table = Table {
+    keys: &[
+        (Key::GraveAccentTilde, " °"),
+        (Key::Num1, "1!"),
+        (Key::Num2, "2\"²"),
+        (Key::Num3, "3§³"),
+        (Key::Num4, "4$"),
+        (Key::Num5, "5%"),
+        (Key::Num6, "6&"),
+        (Key::Num7, "7/{"),
+        (Key::Num8, "8(["),
+        (Key::Num9, "9)]"),
+        (Key::Num0, "0=}"),
+        (Key::Minus, "ß?\\"),
+        (Key::Q, "  @"),
+        (Key::E, "  €"),
+        (Key::LeftBracket, "üÜ"),
+        (Key::RightBracket, "+*~"),
+        (Key::Semicolon, "öÖ"),
+        (Key::Quote, "äÄ"),
+        (Key::NonusNumberSignTilde, "#'"),
+        (Key::NonusBackSlashVerticalBar, "<>|"),
+        (Key::M, "  µ"),
+        (Key::Comma, ",;"),
+        (Key::Period, ".:"),
+        (Key::Slash, "-_"),
+    ],
+    dead: &[
+        ('^', Key::GraveAccentTilde, Modifiers::NONE),
+        ('´', Key::Equal, Modifiers::NONE),
+        ('`', Key::Equal, Modifiers::LEFT_SHIFT),
+    ],
+    letters: &[('y', Key::Z), ('z', Key::Y)],
+};
+
+const FR: Table = Table {
+    keys: &[
+        (Key::GraveAccentTilde, "²"),
+        (Key::Num1, "&1"),
+        (Key::Num2, "é2"),
+        (Key::Num3, "\"3#"),
+        (Key::Num4, "'4{"),
+        (Key::Num5, "(5["),
+        (Key::Num6, "-6|"),
+        (Key::Num7, "è7"),
+        (Key::Num8, "_8\\"),
+        (Key::Num9, "ç9^"),
+        (Key::Num0, "à0@"),
+        (Key::Minus, ")°]"),
+        (Key::Equal, "=+}"),
+        (Key::E, "  €"),
+        (Key::RightBracket, "$£¤"),
+        (Key::Quote, "ù%"),
+        (Key::NonusNumberSignTilde, "*µ"),
+        (Key::NonusBackSlashVerticalBar, "<>"),
+        (Key::M, ",?"),
+        (Key::Comma, ";."),
+        (Key::Period, ":/"),
+        (Key::Slash, "!§"),
+    ],
+    dead: &[
+        ('^', Key::LeftBracket, Modifiers::NONE),
+        ('¨', Key::LeftBracket, Modifiers::LEFT_SHIFT),
+        ('~', Key::Num2, Modifiers::RIGHT_ALT),
+        ('`', Key::Num7, Modifiers::RIGHT_ALT),
+    ],
+    letters: &[
+        ('a', Key::Q),
+        ('q', Key::A),
+        ('z', Key::W),
+        ('w', Key::Z),
+        ('m', Key::Semicolon),
+    ],
+};
+
+const JP: Table = Table {
+    keys: &[
+        (Key::Num1, "1!"),
+        (Key::Num2, "2\""),
+        (Key::Num3, "3#"),
+        (Key::Num4, "4$"),
+        (Key::Num5, "5%"),
+        (Key::Num6, "6&"),
+        (Key::Num7, "7'"),
+        (Key::Num8, "8("),
+        (Key::Num9, "9)"),
+        (Key::Num0, "0"),
+        (Key::Minus, "-="),
+        (Key::Equal, "^~"),
+        (Key::International3, "¥|"),
+        (Key::LeftBracket, "@`"),
+        (Key::RightBracket, "[{"),
+        (Key::NonusNumberSignTilde, "]}"),
+        (Key::Semicolon, ";+"),
+        (Key::Quote, ":*"),
+        (Key::Comma, ",<"),
+        (Key::Period, ".>"),
+        (Key::Slash, "/?"),
+        (Key::International1, "\\_"),
+    ],
+    dead: &[],
+    letters: &[],
+};
+
+/// 死键组合：死键、基础字符、组合结果（按位置一一对应）
+const COMPOSE: &[(char, &str, &str)] = &[
+    ('^', "aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+    ('´', "aeiouyAEIOUY", "áéíóúýÁÉÍÓÚÝ"),
+    ('`', "aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+    ('¨', "aeiouyAEIOU", "äëïöüÿÄËÏÖÜ"),
+    ('~', "anoANO", "ãñõÃÑÕ"),
+];
+
+/// 主机键盘布局
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyboardLayout {
+    /// 美式 QWERTY
+    Us,
+    /// 英式 QWERTY
+    Uk,
+    /// 德语 QWERTZ
+    De,
+    /// 法语 AZERTY
+    Fr,
+    /// 日语 JIS（半角字符）
+    Jp,
+}
+
+impl KeyboardLayout {
+    /// 所有布局
+    pub const ALL: [KeyboardLayout; 5] = [
+        KeyboardLayout::Us,
+        KeyboardLayout::Uk,
+        KeyboardLayout::De,
+        KeyboardLayout::Fr,
+        KeyboardLayout::Jp,
+    ];
+
+    /// 布局名称，如 `"DE"`
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyboardLayout::Us => "US",
+            KeyboardLayout::Uk => "UK",
+            KeyboardLayout::De => "DE",
+            KeyboardLayout::Fr => "FR",
+            KeyboardLayout::Jp => "JP",
+        }
+    }
+
+    /// 根据名称查找布局，不区分大小写
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|layout| layout.name().eq_ignore_ascii_case(name))
+    }
+
+    fn table(&self) -> &'static Table {
+        match self {
+            KeyboardLayout::Us => &US,
+            KeyboardLayout::Uk => &UK,
+            KeyboardLayout::De => &DE,
+            KeyboardLayout::Fr => &FR,
+            KeyboardLayout::Jp => &JP,
+        }
+    }
+
+    /// 一次按键即可输入的字符
+    fn direct(&self, ch: char) -> Option<Keystroke> {
+        match ch {
+            ' ' => return Some(Keystroke::new(Key::Space, Modifiers::NONE)),
+            '\n' => return Some(Keystroke::new(Key::Enter, Modifiers::NONE)),
+            '\t' => return Some(Keystroke::new(Key::Tab, Modifiers::NONE)),
+            _ => {}
+        }
+
+        let table = self.table();
+        for (key, chars) in table.keys {
+            for (level, output) in chars.chars().enumerate() {
+                if output == ch && output != ' ' {
+                    return Some(Keystroke::new(key.clone(), LEVELS[level]));
+                }
+            }
+        }
+
+        if ch.is_ascii_alphabetic() {
+            let lower = ch.to_ascii_lowercase();
+            let key = match table.letters.iter().find(|(letter, _)| *letter == lower) {
+                Some((_, key)) => key.clone(),
+                None => Key::from_name(&lower.to_string())?,
+            };
+            let modifiers = if ch.is_ascii_uppercase() {
+                Modifiers::LEFT_SHIFT
+            } else {
+                Modifiers::NONE
+            };
+            return Some(Keystroke::new(key, modifiers));
+        }
+        None
+    }
+
+    /// 死键
+    fn dead(&self, accent: char) -> Option<Keystroke> {
+        self.table()
+            .dead
+            .iter()
+            .find(|(ch, _, _)| *ch == accent)
+            .map(|(_, key, modifiers)| Keystroke::new(key.clone(), *modifiers))
+    }
+
+    /// 输入一个字符所需的按键序列
+    ///
+    /// 优先使用单次按键；布局上只有死键的字符输入为死键加空格；
+    /// 重音字符输入为死键加基础字符。
+    pub fn keystrokes(&self, ch: char) -> KmboxResult<Vec<Keystroke>> {
+        if let Some(stroke) = self.direct(ch) {
+            return Ok(vec![stroke]);
+        }
+        if let Some(dead) = self.dead(ch) {
+            return Ok(vec![dead, Keystroke::new(Key::Space, Modifiers::NONE)]);
+        }
+        for (accent, bases, results) in COMPOSE {
+            let Some(index) = results.chars().position(|result| result == ch) else {
+                continue;
+            };
+            let base = bases.chars().nth(index).and_then(|base| self.direct(base));
+            if let (Some(dead), Some(base)) = (self.dead(*accent), base) {
+                return Ok(vec![dead, base]);
+            }
+        }
+        Err(KmboxError::ParameterError(format!(
+            "{} 布局无法输入字符 {:?} (U+{:04X})",
+            self.name(),
+            ch,
+            ch as u32
+        )))
+    }
+
+    /// 把文本转换为按键序列，每个字符对应一组按键
+    ///
+    /// 任意字符无法输入时返回错误，错误信息中包含字符位置。
+    pub fn plan(&self, text: &str) -> KmboxResult<Vec<Vec<Keystroke>>> {
+        text.chars()
+            .enumerate()
+            .map(|(index, ch)| {
+                self.keystrokes(ch).map_err(|e| match e {
+                    KmboxError::ParameterError(message) => KmboxError::ParameterError(format!(
+                        "{}，位于第 {} 个字符",
+                        message,
+                        index + 1
+                    )),
+                    other => other,
+                })
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for KeyboardLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// 按布局输入文本的配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextTyper {
+    layout: KeyboardLayout,
+    char_delay: Duration,
+    press_duration: Duration,
+}
+
+impl TextTyper {
+    /// 创建配置：字符间隔 20ms，每次按键按下 10ms
+    pub fn new(layout: KeyboardLayout) -> Self {
+        Self {
+            layout,
+            char_delay: Duration::from_millis(20),
+            press_duration: Duration::from_millis(10),
+        }
+    }
+
+    /// 设置相邻两个字符之间的间隔
+    pub fn with_char_delay(mut self, delay: Duration) -> Self {
+        self.char_delay = delay;
+        self
+    }
+
+    /// 设置每次按键按下的时长
+    pub fn with_press_duration(mut self, duration: Duration) -> Self {
+        self.press_duration = duration;
+        self
+    }
+
+    /// 主机键盘布局
+    pub fn layout(&self) -> KeyboardLayout {
+        self.layout
+    }
+
+    /// 相邻两个字符之间的间隔
+    pub fn char_delay(&self) -> Duration {
+        self.char_delay
+    }
+
+    /// 每次按键按下的时长
+    pub fn press_duration(&self) -> Duration {
+        self.press_duration
+    }
+
+    /// 把文本转换为按键序列
+    pub fn plan(&self, text: &str) -> KmboxResult<Vec<Vec<Keystroke>>> {
+        self.layout.plan(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
+    use crate::keyboard::Keyboard;
+    use std::sync::Arc;
+
+    fn plain(key: Key) -> Keystroke {
+        Keystroke::new(key, Modifiers::NONE)
+    }
+
+    fn shift(key: Key) -> Keystroke {
+        Keystroke::new(key, Modifiers::LEFT_SHIFT)
+    }
+
+    fn altgr(key: Key) -> Keystroke {
+        Keystroke::new(key, Modifiers::RIGHT_ALT)
+    }
+
+    #[test]
+    fn test_us_and_uk_ascii() {
+        let us = KeyboardLayout::Us;
+        assert_eq!(us.keystrokes('a').unwrap(), [plain(Key::A)]);
+        assert_eq!(us.keystrokes('Q').unwrap(), [shift(Key::Q)]);
+        assert_eq!(us.keystrokes('@').unwrap(), [shift(Key::Num2)]);
+        assert_eq!(us.keystrokes('\n').unwrap(), [plain(Key::Enter)]);
+        assert!(us.keystrokes('é').is_err());
+
+        // 所有可打印 ASCII 字符在美式和英式布局上都能输入
+        for layout in [KeyboardLayout::Us, KeyboardLayout::Uk] {
+            for ch in (' '..='~').filter(|ch| !ch.is_ascii_control()) {
+                assert!(layout.keystrokes(ch).is_ok(), "{} {:?}", layout, ch);
+            }
+        }
+        let uk = KeyboardLayout::Uk;
+        assert_eq!(uk.keystrokes('"').unwrap(), [shift(Key::Num2)]);
+        assert_eq!(uk.keystrokes('£').unwrap(), [shift(Key::Num3)]);
+        assert_eq!(
+            uk.keystrokes('#').unwrap(),
+            [plain(Key::NonusNumberSignTilde)]
+        );
+        assert_eq!(KeyboardLayout::from_name("uk"), Some(uk));
+    }
+
+    #[test]
+    fn test_german_layout() {
+        let de = KeyboardLayout::De;
+        assert_eq!(de.keystrokes('z').unwrap(), [plain(Key::Y)]);
+        assert_eq!(de.keystrokes('Y').unwrap(), [shift(Key::Z)]);
+        assert_eq!(de.keystrokes('ß').unwrap(), [plain(Key::Minus)]);
+        assert_eq!(de.keystrokes('Ü').unwrap(), [shift(Key::LeftBracket)]);
+        assert_eq!(de.keystrokes('@').unwrap(), [altgr(Key::Q)]);
+        assert_eq!(de.keystrokes('{').unwrap(), [altgr(Key::Num7)]);
+        // 死键：单独输入时加空格，组合输入重音字符
+        assert_eq!(
+            de.keystrokes('^').unwrap(),
+            [plain(Key::GraveAccentTilde), plain(Key::Space)]
+        );
+        assert_eq!(
+            de.keystrokes('é').unwrap(),
+            [plain(Key::Equal), plain(Key::E)]
+        );
+        assert_eq!(
+            de.keystrokes('È').unwrap(),
+            [shift(Key::Equal), shift(Key::E)]
+        );
+    }
+
+    #[test]
+    fn test_french_layout() {
+        let fr = KeyboardLayout::Fr;
+        assert_eq!(fr.keystrokes('a').unwrap(), [plain(Key::Q)]);
+        assert_eq!(fr.keystrokes('m').unwrap(), [plain(Key::Semicolon)]);
+        assert_eq!(fr.keystrokes(',').unwrap(), [plain(Key::M)]);
+        assert_eq!(fr.keystrokes('1').unwrap(), [shift(Key::Num1)]);
+        assert_eq!(fr.keystrokes('é').unwrap(), [plain(Key::Num2)]);
+        assert_eq!(fr.keystrokes('^').unwrap(), [altgr(Key::Num9)]);
+        assert_eq!(
+            fr.keystrokes('ê').unwrap(),
+            [plain(Key::LeftBracket), plain(Key::E)]
+        );
+        assert_eq!(
+            fr.keystrokes('Ï').unwrap(),
+            [shift(Key::LeftBracket), shift(Key::I)]
+        );
+        assert_eq!(
+            fr.keystrokes('ñ').unwrap(),
+            [altgr(Key::Num2), plain(Key::N)]
+        );
+        assert_eq!(
+            fr.keystrokes('~').unwrap(),
+            [altgr(Key::Num2), plain(Key::Space)]
+        );
+    }
+
+    #[test]
+    fn test_japanese_layout_and_errors() {
+        let jp = KeyboardLayout::Jp;
+        assert_eq!(jp.keystrokes('@').unwrap(), [plain(Key::LeftBracket)]);
+        assert_eq!(jp.keystrokes('"').unwrap(), [shift(Key::Num2)]);
+        assert_eq!(jp.keystrokes(':').unwrap(), [plain(Key::Quote)]);
+        assert_eq!(jp.keystrokes('_').unwrap(), [shift(Key::International1)]);
+        assert_eq!(jp.keystrokes('¥').unwrap(), [plain(Key::International3)]);
+
+        let error = jp.plan("abcあ").unwrap_err();
+        assert!(matches!(
+            error,
+            KmboxError::ParameterError(ref message)
+                if message == "JP 布局无法输入字符 'あ' (U+3042)，位于第 4 个字符"
+        ));
+    }
+
+    #[test]
+    fn test_type_text_on_keyboard() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let keyboard = Keyboard::with_backend(sim.clone()).unwrap();
+        let typer = TextTyper::new(KeyboardLayout::Fr)
+            .with_char_delay(Duration::ZERO)
+            .with_press_duration(Duration::ZERO);
+
+        // 无法输入的字符在发送前报错
+        assert!(keyboard.type_text("aあ", &typer).is_err());
+        assert!(sim.reports().is_empty());
+
+        keyboard.type_text("Aê", &typer).unwrap();
+        let usage = |key: Key| key.hex_value() as i32;
+        assert_eq!(
+            sim.reports(),
+            [
+                HidReport::KeyDown(usage(Key::LeftShift)),
+                HidReport::KeyDown(usage(Key::Q)),
+                HidReport::KeyUp(usage(Key::Q)),
+                HidReport::KeyUp(usage(Key::LeftShift)),
+                HidReport::KeyDown(usage(Key::LeftBracket)),
+                HidReport::KeyUp(usage(Key::LeftBracket)),
+                HidReport::KeyDown(usage(Key::E)),
+                HidReport::KeyUp(usage(Key::E)),
+            ]
+        );
+        assert!(keyboard.held_keys().is_empty());
+    }
+}
@@ -0,0 +1,558 @@
+//! LCD 显示模块
+//!
+//! `kmAI_MiniUI_LCDdisplay` 只接受 OpenCV 的 `cv::Mat`，Rust 中无法构造。
+//! 本模块在纯 Rust 中把任意格式的 [`Image`] 缩放并转换为屏幕的原生格式 [`LcdFrame`]
+//! （320x240、BGR565，与厂商示例 `04-opencv-test` 写入 `/dev/fb0` 的格式一致），
+//! 再由后端写入帧缓冲。转换过程不依赖屏幕，可以直接测试。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::kmbox_ai::KmboxAI;
+//! use kmbox_ai_rust::lcd::{DisplayOptions, Filter, ScaleMode};
+//! use kmbox_ai_rust::vision::ImageProcessor;
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let kmbox = KmboxAI::new()?;
+//! let image = ImageProcessor::new()?.capture_screen()?;
+//!
+//! // 默认保持宽高比缩放，上下或左右留黑边
+//! kmbox.display(&image)?;
+//!
+//! // 铺满屏幕并裁掉超出部分，最近邻缩放
+//! let options = DisplayOptions::new()
+//!     .with_scale_mode(ScaleMode::Fill)
+//!     .with_filter(Filter::Nearest);
+//! kmbox.display_with(&image, &options)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{KmboxError, KmboxResult};
+use crate::vision::{Image, ImageFormat};
+
+/// 屏幕宽度（像素）
+pub const LCD_WIDTH: i32 = 320;
+/// 屏幕高度（像素）
+pub const LCD_HEIGHT: i32 = 240;
+
+/// 缩放方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScaleMode {
+    /// 拉伸到整个屏幕，不保持宽高比
+    Stretch,
+    /// 保持宽高比缩放到屏幕内，空白处填充背景色（letterbox）
+    #[default]
+    Fit,
+    /// 保持宽高比缩放到铺满屏幕，裁掉超出部分
+    Fill,
+    /// 不缩放，居中显示，超出屏幕的部分被裁掉
+    Center,
+}
+
+/// 缩放时的采样方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Filter {
+    /// 最近邻
+    Nearest,
+    /// 双线性插值
+    #[default]
+    Bilinear,
+}
+
+/// 显示参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayOptions {
+    /// 缩放方式
+    pub scale_mode: ScaleMode,
+    /// 采样方式
+    pub filter: Filter,
+    /// 留白和透明像素的背景色（RGB）
+    pub background: [u8; 3],
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            scale_mode: ScaleMode::Fit,
+            filter: Filter::Bilinear,
+            background: [0, 0, 0],
+        }
+    }
+}
+
+impl DisplayOptions {
+    /// 创建默认参数：保持宽高比、双线性插值、黑色背景
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置缩放方式
+    pub fn with_scale_mode(mut self, scale_mode: ScaleMode) -> Self {
+        self.scale_mode = scale_mode;
+        self
+    }
+
+    /// 设置采样方式
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// 设置背景色
+    pub fn with_background(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.background = [r, g, b];
+        self
+    }
+}
+
+/// 把 RGB 颜色打包为屏幕的 BGR565 格式（蓝色在高 5 位，红色在低 5 位）
+pub fn pack_bgr565(r: u8, g: u8, b: u8) -> u16 {
+    (u16::from(b >> 3) << 11) | (u16::from(g >> 2) << 5) | u16::from(r >> 3)
+}
+
+/// 把 BGR565 像素还原为 RGB 颜色，低位按高位补齐
+pub fn unpack_bgr565(pixel: u16) -> [u8; 3] {
+    let expand5 = |v: u16| ((v << 3) | (v >> 2)) as u8;
+    let expand6 = |v: u16| ((v << 2) | (v >> 4)) as u8;
+    [
+        expand5(pixel & 0x1f),
+        expand6((pixel >> 5) & 0x3f),
+        expand5(pixel >> 11),
+    ]
+}
+
+/// 屏幕原生格式的一帧图像，按行排列的 BGR565 像素
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LcdFrame {
+    width: i32,
+    height: i32,
+    pixels: Vec<u16>,
+}
+
+impl Default for LcdFrame {
+    fn default() -> Self {
+        Self::filled(0, 0, 0)
+    }
+}
+
+impl LcdFrame {
+    /// 创建整屏填充为指定颜色的帧
+    pub fn filled(r: u8, g: u8, b: u8) -> Self {
+        Self {
+            width: LCD_WIDTH,
+            height: LCD_HEIGHT,
+            pixels: vec![pack_bgr565(r, g, b); (LCD_WIDTH * LCD_HEIGHT) as usize],
+        }
+    }
+
+    /// 把图像转换为屏幕尺寸和格式
+    pub fn render(image: &Image, options: &DisplayOptions) -> KmboxResult<Self> {
+        let source = decode(image)?;
+        let mut frame = Self::filled(
+            options.background[0],
+            options.background[1],
+            options.background[2],
+        );
+        let placement = Placement::new(options.scale_mode, image.width as f32, image.height as f32);
+
+        for y in 0..frame.height {
+            let Some(sy) = placement.source_y(y as f32 + 0.5) else {
+                continue;
+            };
+            for x in 0..frame.width {
+                let Some(sx) = placement.source_x(x as f32 + 0.5) else {
+                    continue;
+                };
+                let [r, g, b, a] = match options.filter {
+                    Filter::Nearest => source.nearest(sx, sy),
+                    Filter::Bilinear => source.bilinear(sx, sy),
+                };
+                let blend = |c: f32, bg: u8| {
+                    (c * a + f32::from(bg) * (1.0 - a))
+                        .round()
+                        .clamp(0.0, 255.0) as u8
+                };
+                frame.pixels[(y * frame.width + x) as usize] = pack_bgr565(
+                    blend(r, options.background[0]),
+                    blend(g, options.background[1]),
+                    blend(b, options.background[2]),
+                );
+            }
+        }
+        Ok(frame)
+    }
+
+    /// 宽度
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// 高度
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// 按行排列的 BGR565 像素
+    pub fn pixels(&self) -> &[u16] {
+        &self.pixels
+    }
+
+    /// 指定位置的 BGR565 像素
+    pub fn pixel(&self, x: i32, y: i32) -> Option<u16> {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+        self.pixels.get((y * self.width + x) as usize).copied()
+    }
+
+    /// 指定位置像素的 RGB 颜色
+    pub fn rgb(&self, x: i32, y: i32) -> Option<[u8; 3]> {
+        self.pixel(x, y).map(unpack_bgr565)
+    }
+
+    /// 帧缓冲的字节序列（小端）
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flat_map(|p| p.to_le_bytes()).collect()
+    }
+}
+
+/// 图像在屏幕上的位置和缩放比例
+struct Placement {
+    /// 图像左上角在屏幕上的坐标，可以为负（`Fill`、`Center` 时裁掉的部分）
+    left: f32,
+    top: f32,
+    /// 屏幕像素与图像像素的比例
+    scale_x: f32,
+    scale_y: f32,
+    /// 图像尺寸
+    width: f32,
+    height: f32,
+}
+
+impl Placement {
+    fn new(mode: ScaleMode, width: f32, height: f32) -> Self {
+        let (screen_w, screen_h) = (LCD_WIDTH as f32, LCD_HEIGHT as f32);
+        let (scale_x, scale_y) = match mode {
+            ScaleMode::Stretch => (screen_w / width, screen_h / height),
+            ScaleMode::Fit => {
+                let scale = (screen_w / width).min(screen_h / height);
+                (scale, scale)
+            }
+            ScaleMode::Fill => {
+                let scale = (screen_w / width).max(screen_h / height);
+                (scale, scale)
+            }
+            ScaleMode::Center => (1.0, 1.0),
+        };
+        // 对齐到整数像素，留白两侧相差不超过 1 像素
+        let left = ((screen_w - width * scale_x) / 2.0).round();
+        let top = ((screen_h - height * scale_y) / 2.0).round();
+        Self {
+            left,
+            top,
+            scale_x,
+            scale_y,
+            width,
+            height,
+        }
+    }
+
+    /// 屏幕像素中心对应的图像坐标，落在图像外时返回 `None`
+    fn source_x(&self, x: f32) -> Option<f32> {
+        let sx = (x - self.left) / self.scale_x;
+        (sx >= 0.0 && sx < self.width).then_some(sx)
+    }
+
+    fn source_y(&self, y: f32) -> Option<f32> {
+        let sy = (y - self.top) / self.scale_y;
+        (sy >= 0.0 && sy < self.height).then_some(sy)
+    }
+}
+
+/// 解码为 RGBA 的源图像，透明度归一化到 0..=1
+struct Source {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Source {
+    fn at(&self, x: usize, y: usize) -> [f32; 4] {
+        self.pixels[y.min(self.height - 1) * self.width + x.min(self.width - 1)]
+    }
+
+    fn nearest(&self, x: f32, y: f32) -> [f32; 4] {
+        self.at(x as usize, y as usize)
+    }
+
+    fn bilinear(&self, x: f32, y: f32) -> [f32; 4] {
+        // 以像素中心为采样点
+        let x = (x - 0.5).max(0.0);
+        let y = (y - 0.5).max(0.0);
+        let (x0, y0) = (x as usize, y as usize);
+        let (fx, fy) = (x.fract(), y.fract());
+        let lerp =
+            |a: [f32; 4], b: [f32; 4], t: f32| [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t);
+        let top = lerp(self.at(x0, y0), self.at(x0 + 1, y0), fx);
+        let bottom = lerp(self.at(x0, y0 + 1), self.at(x0 + 1, y0 + 1), fx);
+        lerp(top, bottom, fy)
+    }
+}
+
+/// YUV420SP 图像 UV 平面的行字节数和总字节数
+fn uv_plane(width: usize, height: usize) -> (usize, usize) {
+    let stride = width.div_ceil(2) * 2;
+    (stride, stride * height.div_ceil(2))
+}
+
+/// BT.601 有限范围 YUV 转 RGB，与 OpenCV 的 `COLOR_YUV2RGB_NV12` 一致
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [f32; 3] {
+    let c = 1.164 * (f32::from(y) - 16.0);
+    let d = f32::from(u) - 128.0;
+    let e = f32::from(v) - 128.0;
+    [
+        (c + 1.596 * e).clamp(0.0, 255.0),
+        (c - 0.392 * d - 0.813 * e).clamp(0.0, 255.0),
+        (c + 2.017 * d).clamp(0.0, 255.0),
+    ]
+}
+
+/// 把任意支持的格式解码为 RGBA
+fn decode(image: &Image) -> KmboxResult<Source> {
+    if image.width <= 0 || image.height <= 0 {
+        return Err(KmboxError::ImageError(format!(
+            "图像尺寸无效: {}x{}",
+            image.width, image.height
+        )));
+    }
+    let (width, height) = (image.width as usize, image.height as usize);
+    let count = width * height;
+    let required = match image.format {
+        ImageFormat::Gray8 => count,
+        ImageFormat::Rgb888 | ImageFormat::Bgr888 => count * 3,
+        ImageFormat::Rgba8888 => count * 4,
+        ImageFormat::Yuv420spNv12 | ImageFormat::Yuv420spNv21 => count + uv_plane(width, height).1,
+    };
+    if image.data.len() < required {
+        return Err(KmboxError::ImageError(format!(
+            "{}x{} 的 {:?} 图像需要 {} 字节数据，实际只有 {} 字节",
+            width,
+            height,
+            image.format,
+            required,
+            image.data.len()
+        )));
+    }
+
+    let data = &image.data;
+    let opaque = |[r, g, b]: [f32; 3]| [r, g, b, 1.0];
+    let pixels = match image.format {
+        ImageFormat::Gray8 => data[..count]
+            .iter()
+            .map(|&v| opaque([f32::from(v); 3]))
+            .collect(),
+        ImageFormat::Rgb888 => data[..count * 3]
+            .chunks_exact(3)
+            .map(|p| opaque([p[0], p[1], p[2]].map(f32::from)))
+            .collect(),
+        ImageFormat::Bgr888 => data[..count * 3]
+            .chunks_exact(3)
+            .map(|p| opaque([p[2], p[1], p[0]].map(f32::from)))
+            .collect(),
+        ImageFormat::Rgba8888 => data[..count * 4]
+            .chunks_exact(4)
+            .map(|p| {
+                let [r, g, b, a] = [p[0], p[1], p[2], p[3]].map(f32::from);
+                [r, g, b, a / 255.0]
+            })
+            .collect(),
+        ImageFormat::Yuv420spNv12 | ImageFormat::Yuv420spNv21 => {
+            let (stride, _) = uv_plane(width, height);
+            let uv = &data[count..];
+            let nv12 = image.format == ImageFormat::Yuv420spNv12;
+            (0..count)
+                .map(|i| {
+                    let (x, y) = (i % width, i / width);
+                    let offset = (y / 2) * stride + (x / 2) * 2;
+                    let (first, second) = (uv[offset], uv[offset + 1]);
+                    let (u, v) = if nv12 {
+                        (first, second)
+                    } else {
+                        (second, first)
+                    };
+                    opaque(yuv_to_rgb(data[i], u, v))
+                })
+                .collect()
+        }
+    };
+    Ok(Source {
+        width,
+        height,
+        pixels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: i32, height: i32, rgb: [u8; 3]) -> Image {
+        let mut image = Image::new(width, height, ImageFormat::Rgb888);
+        for pixel in image.data.chunks_exact_mut(3) {
+            pixel.copy_from_slice(&rgb);
+        }
+        image
+    }
+
+    #[test]
+    fn test_bgr565_packing() {
+        // 与厂商示例相同：蓝色在高 5 位
+        assert_eq!(pack_bgr565(0, 0, 255), 0xf800);
+        assert_eq!(pack_bgr565(0, 255, 0), 0x07e0);
+        assert_eq!(pack_bgr565(255, 0, 0), 0x001f);
+        assert_eq!(unpack_bgr565(pack_bgr565(255, 255, 255)), [255, 255, 255]);
+        assert_eq!(unpack_bgr565(pack_bgr565(0, 0, 0)), [0, 0, 0]);
+
+        let frame = LcdFrame::filled(0, 0, 255);
+        assert_eq!(
+            frame.to_bytes().len(),
+            (LCD_WIDTH * LCD_HEIGHT * 2) as usize
+        );
+        assert_eq!(&frame.to_bytes()[..2], &[0x00, 0xf8]);
+    }
+
+    #[test]
+    fn test_scale_modes() {
+        let red = pack_bgr565(255, 0, 0);
+        let background = pack_bgr565(0, 0, 0);
+        // 160x60 的图像，宽高比 8:3
+        let image = solid(160, 60, [255, 0, 0]);
+
+        // 保持宽高比放大两倍到 320x120，上下各留 60 行
+        let frame = LcdFrame::render(&image, &DisplayOptions::new()).unwrap();
+        assert_eq!(frame.pixel(0, 59), Some(background));
+        assert_eq!(frame.pixel(0, 60), Some(red));
+        assert_eq!(frame.pixel(319, 179), Some(red));
+        assert_eq!(frame.pixel(319, 180), Some(background));
+
+        let options = DisplayOptions::new().with_scale_mode(ScaleMode::Stretch);
+        let frame = LcdFrame::render(&image, &options).unwrap();
+        assert!(frame.pixels().iter().all(|&p| p == red));
+
+        let options = DisplayOptions::new().with_scale_mode(ScaleMode::Fill);
+        let frame = LcdFrame::render(&image, &options).unwrap();
+        assert!(frame.pixels().iter().all(|&p| p == red));
+
+        // 不缩放时居中：左右各 80 列、上下各 90 行背景
+        let options = DisplayOptions::new()
+            .with_scale_mode(ScaleMode::Center)
+            .with_background(0, 0, 255);
+        let frame = LcdFrame::render(&image, &options).unwrap();
+        assert_eq!(frame.rgb(79, 120), Some([0, 0, 255]));
+        assert_eq!(frame.pixel(80, 90), Some(red));
+        assert_eq!(frame.pixel(239, 149), Some(red));
+        assert_eq!(frame.rgb(240, 149), Some([0, 0, 255]));
+    }
+
+    #[test]
+    fn test_fill_crops_center() {
+        // 左半黑、右半白的 240x120 图像放大两倍铺满屏幕，裁掉左右各 80 列
+        let mut image = Image::new(240, 120, ImageFormat::Gray8);
+        for (i, pixel) in image.data.iter_mut().enumerate() {
+            *pixel = if i % 240 < 120 { 0 } else { 255 };
+        }
+        let options = DisplayOptions::new()
+            .with_scale_mode(ScaleMode::Fill)
+            .with_filter(Filter::Nearest);
+        let frame = LcdFrame::render(&image, &options).unwrap();
+        assert_eq!(frame.rgb(159, 0), Some([0, 0, 0]));
+        assert_eq!(frame.rgb(160, 0), Some([255, 255, 255]));
+
+        // 双线性插值在边界处平滑过渡
+        let frame = LcdFrame::render(&image, &options.with_filter(Filter::Bilinear)).unwrap();
+        assert_eq!(frame.rgb(0, 0), Some([0, 0, 0]));
+        assert_eq!(frame.rgb(319, 0), Some([255, 255, 255]));
+        let [edge, _, _] = frame.rgb(159, 0).unwrap();
+        assert!(edge > 0 && edge < 255);
+    }
+
+    #[test]
+    fn test_formats() {
+        let white = pack_bgr565(255, 255, 255);
+        let options = DisplayOptions::new().with_scale_mode(ScaleMode::Stretch);
+
+        // BGR 通道顺序
+        let mut bgr = Image::new(2, 2, ImageFormat::Bgr888);
+        for pixel in bgr.data.chunks_exact_mut(3) {
+            pixel.copy_from_slice(&[255, 0, 0]);
+        }
+        let frame = LcdFrame::render(&bgr, &options).unwrap();
+        assert_eq!(frame.rgb(10, 10), Some([0, 0, 255]));
+
+        // 半透明像素与背景混合
+        let mut rgba = Image::new(2, 2, ImageFormat::Rgba8888);
+        for pixel in rgba.data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[255, 255, 255, 0]);
+        }
+        let frame = LcdFrame::render(&rgba, &options.with_background(0, 255, 0)).unwrap();
+        assert_eq!(frame.rgb(0, 0), Some([0, 255, 0]));
+
+        // NV12 与 NV21 的 UV 顺序相反：U=255、V=0 偏蓝，交换后偏红
+        let mut nv12 = Image::new(4, 2, ImageFormat::Yuv420spNv12);
+        assert_eq!(nv12.data.len(), 4 * 2 + 4);
+        nv12.data[..8].fill(128);
+        for uv in nv12.data[8..].chunks_exact_mut(2) {
+            uv.copy_from_slice(&[255, 0]);
+        }
+        let [r, _, b] = LcdFrame::render(&nv12, &options)
+            .unwrap()
+            .rgb(0, 0)
+            .unwrap();
+        assert!(b > r);
+        nv12.format = ImageFormat::Yuv420spNv21;
+        let [r, _, b] = LcdFrame::render(&nv12, &options)
+            .unwrap()
+            .rgb(0, 0)
+            .unwrap();
+        assert!(r > b);
+
+        // 全白 Y、中性 UV
+        let mut yuv = Image::new(2, 2, ImageFormat::Yuv420spNv12);
+        yuv.data[..4].fill(235);
+        yuv.data[4..].fill(128);
+        let frame = LcdFrame::render(&yuv, &options).unwrap();
+        assert_eq!(frame.pixel(100, 100), Some(white));
+
+        // 数据不足或尺寸无效
+        let mut short = Image::new(4, 4, ImageFormat::Rgb888);
+        short.data.truncate(10);
+        assert!(matches!(
+            LcdFrame::render(&short, &options),
+            Err(KmboxError::ImageError(_))
+        ));
+        assert!(LcdFrame::render(&Image::new(0, 4, ImageFormat::Gray8), &options).is_err());
+    }
+
+    #[test]
+    fn test_display_on_simulated_backend() {
+        use crate::backend::SimulatedBackend;
+        use crate::kmbox_ai::KmboxAI;
+        use std::sync::Arc;
+
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        let image = solid(640, 480, [0, 255, 0]);
+        kmbox.display(&image).unwrap();
+        let shown = sim.state().lcd.unwrap();
+        assert_eq!((shown.width(), shown.height()), (LCD_WIDTH, LCD_HEIGHT));
+        assert!(shown.pixels().iter().all(|&p| p == pack_bgr565(0, 255, 0)));
+
+        // 写入失败时返回输入输出错误，屏幕内容保持不变
+        sim.inject_fault(-4);
+        let error = kmbox.display(&solid(8, 8, [255, 0, 0])).unwrap_err();
+        assert!(matches!(error, KmboxError::Io { .. }));
+        assert_eq!(error.operation(), Some("LCD显示图像"));
+        assert_eq!(sim.state().lcd, Some(shown));
+    }
+}
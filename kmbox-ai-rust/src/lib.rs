@@ -37,42 +37,46 @@
 //!
 //! ## 使用示例
 //!
-//! ```rust
+//! ```rust,no_run
 //! use kmbox_ai_rust::{
 //!     keyboard::{Keyboard, Key},
-//!     vision::{ImageProcessor, Image, ImageFormat},
+//!     vision::{ImageProcessor, Image, ImageFormat, ImageRect},
 //!     yolo::{YoloDetector, ObjectType, BoundingBox},
 //!     rknn::{RknnContext, RknnModel},
 //! };
 //!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
 //! // 键盘操作
 //! let keyboard = Keyboard::new()?;
 //! keyboard.press_key(Key::A)?;
 //! keyboard.release_key(Key::A)?;
-//! keyboard.click_key(Key::Enter)?;
+//! keyboard.click_key(Key::Enter, 50)?;
 //! keyboard.type_string("Hello, World!")?;
 //!
 //! // 图像处理
 //! let processor = ImageProcessor::new()?;
 //! let image = processor.capture_screen()?;
+//! let rect = ImageRect::new(0, 0, 320, 240);
 //! let cropped = processor.crop(&image, rect)?;
-//! processor.save_to_file(&image, "screenshot.png")?;
+//! processor.save_to_file(&cropped, "screenshot.png")?;
 //!
 //! // 目标检测
-//! let detector = YoloDetector::new("model.rknn")?;
+//! let mut detector = YoloDetector::new("model.rknn")?;
 //! detector.set_confidence_threshold(0.5)?;
 //! let results = detector.detect(&image)?;
 //! for obj in results.objects {
 //!     println!("检测到: {:?}, 置信度: {}", obj.object_type, obj.confidence);
 //! }
+//! # Ok(())
+//! # }
 //! ```
 //!
 //! ## 错误处理
 //!
 //! 所有函数都返回 `KmboxResult<T>` 类型，提供统一的错误处理：
 //!
-//! ```rust
-//! use kmbox_ai_rust::{KmboxError, KmboxResult};
+//! ```rust,no_run
+//! use kmbox_ai_rust::{Key, Keyboard, KmboxError, KmboxResult};
 //!
 //! fn example() -> KmboxResult<()> {
 //!     let keyboard = Keyboard::new()?;
@@ -81,6 +85,14 @@
 //! }
 //! ```
 //!
+//! ## Cargo 特性
+//!
+//! - `hardware`（默认启用）：链接厂商动态库并在构建时重新生成 `src/bindings`，
+//!   默认后端为 `HardwareBackend`
+//!
+//! 关闭 `hardware` 特性（`cargo build --no-default-features`）后不再需要厂商库和 bindgen，
+//! 默认后端为进程内共享的 [`SimulatedBackend`]，模型加载和推理接口返回错误。
+//!
 //! ## 系统要求
 //!
 //! - Linux 系统
@@ -91,17 +103,64 @@
 //!
 //! 本项目遵循相应的开源许可证。
 
+#[cfg(feature = "async")]
+pub mod asynchronous;
+pub mod backend;
+pub mod cursor;
 pub mod error;
+pub mod failsafe;
+pub mod ffi;
+pub mod gesture;
+pub mod handle;
+pub mod health;
+pub mod hotkey;
 pub mod keyboard;
 pub mod kmbox_ai;
+pub mod layout;
+pub mod lcd;
+pub mod mask;
+pub mod monitor;
+pub mod motion;
+pub mod recognizer;
+pub mod recorder;
+pub mod report;
 pub mod rknn;
+pub mod scheduler;
+pub mod script;
+pub mod typematic;
 pub mod vision;
 pub mod yolo;
 
 // 重新导出常用的类型和函数
-pub use error::{KmboxError, KmboxResult};
+#[cfg(feature = "async")]
+pub use asynchronous::{AsyncKmbox, EventStream, InferenceTask, InferenceWorker};
+#[cfg(feature = "hardware")]
+pub use backend::HardwareBackend;
+pub use backend::{Backend, SimulatedBackend};
+pub use cursor::{AccelerationCurve, Corner, CursorModel, VirtualCursor};
+pub use error::{Api, CallError, ErrorCode, KmboxError, KmboxResult, Language, Operation};
+pub use failsafe::HeldInputs;
+pub use gesture::{GesturePlanner, GestureStep};
+pub use handle::{HandleStats, KmboxHandle};
+pub use health::{HealthConfig, HealthEvent, HealthState, HealthSupervisor};
+pub use hotkey::{Hotkey, HotkeyManager, HotkeyTrigger};
 pub use keyboard::{Key, KeyState, Keyboard};
-pub use kmbox_ai::{KmboxAI, MiniUIMode, MouseAction, MouseButton, MousePosition, MouseState};
+pub use kmbox_ai::{
+    KmboxAI, MiniUIMode, MouseAction, MouseButton, MouseMask, MousePosition, MouseState,
+};
+pub use layout::{KeyboardLayout, Keystroke, TextTyper};
+pub use lcd::{DisplayOptions, Filter, LcdFrame, ScaleMode};
+pub use mask::{MaskGuard, MaskTarget};
+pub use monitor::{EventKind, InputEvent, InputMonitor, MonitorConfig};
+pub use motion::{MotionPath, MotionPlanner};
+pub use recognizer::{
+    Direction, GestureEvent, GestureId, GesturePattern, GestureRecognizer, RecognizerConfig,
+};
+pub use recorder::{Recorder, Recording, Replayer};
+pub use report::{KeyboardReport, KeyboardReportDiff, Modifiers, MouseButtons, MouseReport};
 pub use rknn::{RknnContext, RknnModel};
+pub use scheduler::{OutputReport, OutputScheduler, ReportCoalescer, SchedulerStats};
+pub use script::{Executor, Script};
+pub use typematic::{KeyHold, RepeatConfig};
 pub use vision::{DetectionResult, Image, ImageFormat, ImageProcessor, ImageRect};
 pub use yolo::{BoundingBox, ObjectType, YoloDetector};
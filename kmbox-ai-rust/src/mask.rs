@@ -0,0 +1,321 @@
+//! 输入屏蔽管理模块
+//!
+//! 记录通过 [`KmboxAI`](crate::kmbox_ai::KmboxAI) 设置的所有物理键鼠屏蔽，
+//! 并提供作用域守卫 [`MaskGuard`]：守卫销毁时自动解除对应屏蔽，
+//! 即使代码提前返回或发生 panic，物理鼠标和键盘也不会一直处于被屏蔽状态。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::kmbox_ai::{KmboxAI, MouseMask};
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let kmbox = KmboxAI::new()?;
+//! {
+//!     let _guard = kmbox.mask_mouse_guard(MouseMask::XY)?;
+//!     // 在这里移动鼠标，物理鼠标的移动不会转发给主机
+//!     kmbox.mouse_move(10, 0)?;
+//! } // 离开作用域时自动解除屏蔽
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## 屏蔽计数
+//!
+//! 同一目标可以同时被多个守卫和手动调用屏蔽：
+//! 只有最后一个守卫销毁且没有手动屏蔽时才会真正解除；
+//! 手动解除（`mask_mouse(.., false)`、`unmask_keyboard`）会立即解除，不论还有多少守卫。
+
+use crate::backend::Backend;
+use crate::error::{check_result, KmboxResult, Operation};
+use crate::failsafe::lock_for_release;
+use crate::kmbox_ai::MouseMask;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard, Once, Weak};
+
+/// 屏蔽目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MaskTarget {
+    /// 物理鼠标按键或坐标轴
+    Mouse(MouseMask),
+    /// 物理键盘按键（HID 键值）
+    Keyboard(i16),
+}
+
+/// 单个屏蔽目标的引用状态
+#[derive(Debug, Clone, Copy, Default)]
+struct MaskEntry {
+    /// 存活的守卫数量
+    guards: usize,
+    /// 是否被手动屏蔽
+    manual: bool,
+}
+
+/// 屏蔽状态记录
+pub(crate) struct MaskTracker {
+    backend: Arc<dyn Backend>,
+    active: Mutex<BTreeMap<MaskTarget, MaskEntry>>,
+}
+
+impl MaskTracker {
+    pub(crate) fn new(backend: Arc<dyn Backend>) -> Self {
+        Self {
+            backend,
+            active: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<MaskTarget, MaskEntry>> {
+        self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 在设备上设置或解除屏蔽
+    fn apply(&self, target: MaskTarget, enable: bool) -> KmboxResult<()> {
+        match target {
+            MaskTarget::Mouse(mask) => {
+                let result = self.backend.mask_mouse(mask, enable);
+                check_result(result, mask.operation())
+            }
+            MaskTarget::Keyboard(vkey) if enable => {
+                let result = self.backend.mask_keyboard(vkey);
+                check_result(result, Operation::MaskKeyboard)
+            }
+            MaskTarget::Keyboard(vkey) => {
+                let result = self.backend.unmask_keyboard(vkey);
+                check_result(result, Operation::UnmaskKeyboard)
+            }
+        }
+    }
+
+    /// 手动屏蔽或解除屏蔽
+    pub(crate) fn set_manual(&self, target: MaskTarget, enable: bool) -> KmboxResult<()> {
+        let mut active = self.lock();
+        self.apply(target, enable)?;
+        if enable {
+            active.entry(target).or_default().manual = true;
+        } else {
+            active.remove(&target);
+        }
+        Ok(())
+    }
+
+    /// 增加一个守卫引用，第一次引用时在设备上屏蔽
+    pub(crate) fn acquire(&self, target: MaskTarget) -> KmboxResult<()> {
+        let mut active = self.lock();
+        if !active.contains_key(&target) {
+            self.apply(target, true)?;
+        }
+        active.entry(target).or_default().guards += 1;
+        Ok(())
+    }
+
+    /// 释放一个守卫引用，没有其他引用时在设备上解除屏蔽
+    pub(crate) fn release(&self, target: MaskTarget) -> KmboxResult<()> {
+        let mut active = self.lock();
+        let Some(entry) = active.get_mut(&target) else {
+            // 已经被手动解除
+            return Ok(());
+        };
+        entry.guards = entry.guards.saturating_sub(1);
+        if entry.guards == 0 && !entry.manual {
+            active.remove(&target);
+            self.apply(target, false)?;
+        }
+        Ok(())
+    }
+
+    /// 当前处于屏蔽状态的目标
+    pub(crate) fn active(&self) -> Vec<MaskTarget> {
+        self.lock().keys().copied().collect()
+    }
+
+    /// 忘记所有记录（设备上的屏蔽已经被全部解除时使用）
+    pub(crate) fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// 在设备上重新设置所有记录的屏蔽（设备重新初始化后丢失了屏蔽时使用）
+    pub(crate) fn reapply(&self) -> KmboxResult<()> {
+        let active = self.lock();
+        for &target in active.keys() {
+            self.apply(target, true)?;
+        }
+        Ok(())
+    }
+
+    /// 解除所有记录的屏蔽，恢复到设置屏蔽之前的状态
+    pub(crate) fn restore(&self) -> KmboxResult<()> {
+        let mut active = self.lock();
+        let mut first_error = None;
+        for target in std::mem::take(&mut *active).into_keys() {
+            if let Err(e) = self.apply(target, false) {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// panic 时尽力恢复，最多等待 [`RELEASE_LOCK_TIMEOUT`](crate::failsafe::RELEASE_LOCK_TIMEOUT)
+    fn restore_on_panic(&self) {
+        let Some(mut active) = lock_for_release(&self.active) else {
+            return;
+        };
+        for target in std::mem::take(&mut *active).into_keys() {
+            let _ = self.apply(target, false);
+        }
+    }
+}
+
+/// 登记了 panic 恢复的屏蔽记录
+static PANIC_TRACKERS: Mutex<Vec<Weak<MaskTracker>>> = Mutex::new(Vec::new());
+
+/// 登记 `tracker`，panic 时解除它记录的所有屏蔽
+///
+/// 进程中只安装一个钩子，原有的钩子会在之后继续执行；
+/// 登记表只持有弱引用，对应的 `KmboxAI` 销毁后在下次登记时移除，重复登记同一记录不会重复恢复。
+pub(crate) fn install_panic_hook(tracker: &Arc<MaskTracker>) {
+    static INSTALL: Once = Once::new();
+    {
+        let mut trackers = PANIC_TRACKERS.lock().unwrap_or_else(|e| e.into_inner());
+        trackers.retain(|weak| weak.strong_count() > 0);
+        if !trackers
+            .iter()
+            .any(|weak| std::ptr::eq(weak.as_ptr(), Arc::as_ptr(tracker)))
+        {
+            trackers.push(Arc::downgrade(tracker));
+        }
+    }
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            restore_panic_trackers();
+            previous(info);
+        }));
+    });
+}
+
+/// 解除所有登记记录中的屏蔽
+fn restore_panic_trackers() {
+    let trackers: Vec<Arc<MaskTracker>> = match lock_for_release(&PANIC_TRACKERS) {
+        Some(trackers) => trackers.iter().filter_map(Weak::upgrade).collect(),
+        None => return,
+    };
+    for tracker in trackers {
+        tracker.restore_on_panic();
+    }
+}
+
+/// 屏蔽作用域守卫
+///
+/// 由 [`KmboxAI::mask_mouse_guard`](crate::kmbox_ai::KmboxAI::mask_mouse_guard)
+/// 和 [`KmboxAI::mask_keyboard_guard`](crate::kmbox_ai::KmboxAI::mask_keyboard_guard) 创建，
+/// 销毁时解除屏蔽。
+#[must_use = "守卫销毁时会立即解除屏蔽"]
+pub struct MaskGuard {
+    tracker: Arc<MaskTracker>,
+    target: MaskTarget,
+}
+
+impl MaskGuard {
+    pub(crate) fn new(tracker: Arc<MaskTracker>, target: MaskTarget) -> KmboxResult<Self> {
+        tracker.acquire(target)?;
+        Ok(Self { tracker, target })
+    }
+
+    /// 获取屏蔽目标
+    pub fn target(&self) -> MaskTarget {
+        self.target
+    }
+}
+
+impl std::fmt::Debug for MaskGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MaskGuard")
+            .field("target", &self.target)
+            .finish()
+    }
+}
+
+impl Drop for MaskGuard {
+    fn drop(&mut self) {
+        let _ = self.tracker.release(self.target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::SimulatedBackend;
+
+    fn tracker() -> (Arc<SimulatedBackend>, Arc<MaskTracker>) {
+        let sim = Arc::new(SimulatedBackend::new());
+        let tracker = Arc::new(MaskTracker::new(sim.clone()));
+        (sim, tracker)
+    }
+
+    #[test]
+    fn test_guard_unmasks_on_drop() {
+        let (sim, tracker) = tracker();
+        let guard = MaskGuard::new(tracker.clone(), MaskTarget::Keyboard(4)).unwrap();
+        assert_eq!(guard.target(), MaskTarget::Keyboard(4));
+        assert!(sim.state().keyboard_masks.contains(&4));
+        drop(guard);
+        assert!(sim.state().keyboard_masks.is_empty());
+        assert!(tracker.active().is_empty());
+    }
+
+    #[test]
+    fn test_nested_guards_and_manual_mask() {
+        let (sim, tracker) = tracker();
+        let target = MaskTarget::Mouse(MouseMask::Left);
+        let outer = MaskGuard::new(tracker.clone(), target).unwrap();
+        let inner = MaskGuard::new(tracker.clone(), target).unwrap();
+        drop(inner);
+        assert!(sim.state().mouse_masks.contains(&MouseMask::Left));
+
+        // 手动屏蔽的目标在守卫销毁后依然保持
+        tracker.set_manual(target, true).unwrap();
+        drop(outer);
+        assert!(sim.state().mouse_masks.contains(&MouseMask::Left));
+
+        tracker.set_manual(target, false).unwrap();
+        assert!(sim.state().mouse_masks.is_empty());
+    }
+
+    #[test]
+    fn test_panic_hook_registry() {
+        let (_, first) = tracker();
+        let (_, second) = tracker();
+        install_panic_hook(&first);
+        install_panic_hook(&first);
+        install_panic_hook(&second);
+        let dead = Arc::downgrade(&second);
+        drop(second);
+        // 重新登记时移除已经销毁的记录，同一记录只登记一次
+        install_panic_hook(&first);
+
+        let trackers = PANIC_TRACKERS.lock().unwrap();
+        let first = Arc::downgrade(&first);
+        assert_eq!(
+            trackers.iter().filter(|weak| weak.ptr_eq(&first)).count(),
+            1
+        );
+        assert!(!trackers.iter().any(|weak| weak.ptr_eq(&dead)));
+    }
+
+    #[test]
+    fn test_restore() {
+        let (sim, tracker) = tracker();
+        tracker
+            .set_manual(MaskTarget::Mouse(MouseMask::Wheel), true)
+            .unwrap();
+        let _guard = MaskGuard::new(tracker.clone(), MaskTarget::Keyboard(41)).unwrap();
+        assert_eq!(tracker.active().len(), 2);
+
+        tracker.restore().unwrap();
+        let state = sim.state();
+        assert!(state.mouse_masks.is_empty());
+        assert!(state.keyboard_masks.is_empty());
+        assert!(tracker.active().is_empty());
+    }
+}
@@ -0,0 +1,507 @@
+//! 物理键鼠事件监听模块
+//!
+//! 后台线程按固定间隔采样 `monitor_*` 接口，对采样结果做边沿检测，
+//! 把状态变化转换为带时间戳的 [`InputEvent`]，通过通道交给调用方。
+//!
+//! `kmAI_monitor_mouse_xy`/`kmAI_monitor_mouse_wheel` 返回的是最近一次移动的值，读取后不会清零，
+//! 因此只有与上一次采样不同的值才会产生 [`EventKind::Moved`]/[`EventKind::Wheel`]。
+//! 连续两次完全相同的移动无法与静止区分，只会产生一个事件。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::kmbox_ai::{KmboxAI, MouseButton};
+//! use kmbox_ai_rust::monitor::{EventKind, MonitorConfig};
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let kmbox = KmboxAI::new()?;
+//! let monitor = kmbox.input_monitor(MonitorConfig::default())?;
+//! for event in monitor.iter() {
+//!     if event.kind == EventKind::ButtonDown(MouseButton::Left) {
+//!         println!("左键按下: {:?}", event.timestamp);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::{check_state, KmboxError, KmboxResult, Operation};
+use crate::kmbox_ai::{read_mouse_state, MouseButton, MousePosition, MouseState};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 输入事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// 鼠标按键按下
+    ButtonDown(MouseButton),
+    /// 鼠标按键释放
+    ButtonUp(MouseButton),
+    /// 鼠标相对移动
+    Moved { dx: i32, dy: i32 },
+    /// 鼠标滚轮
+    Wheel(i32),
+    /// 键盘按键按下（HID 键值）
+    KeyDown(i16),
+    /// 键盘按键释放（HID 键值）
+    KeyUp(i16),
+}
+
+/// 带时间戳的输入事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputEvent {
+    /// 事件类型
+    pub kind: EventKind,
+    /// 检测到事件的采样时刻
+    pub timestamp: Instant,
+}
+
+/// 事件监听配置
+#[derive(Debug, Clone)]
+pub struct MonitorConfig {
+    /// 采样间隔
+    pub interval: Duration,
+    /// 需要监听的键盘按键（HID 键值）
+    pub keys: Vec<i16>,
+    /// 按键状态需要连续保持多少次采样才被确认，1 表示只做边沿检测
+    pub debounce_samples: u32,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(5),
+            // 标准按键 0x04..=0xDD 以及 8 个修饰键
+            keys: (0x04..=0xDD).chain(0xE0..=0xE7).collect(),
+            debounce_samples: 1,
+        }
+    }
+}
+
+impl MonitorConfig {
+    /// 设置采样间隔
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// 设置需要监听的键盘按键
+    pub fn with_keys(mut self, keys: impl IntoIterator<Item = i16>) -> Self {
+        self.keys = keys.into_iter().collect();
+        self
+    }
+
+    /// 设置去抖采样次数
+    pub fn with_debounce_samples(mut self, samples: u32) -> Self {
+        self.debounce_samples = samples;
+        self
+    }
+}
+
+/// 单个按键的去抖状态
+#[derive(Debug, Clone, Copy, Default)]
+struct Debounced {
+    /// 已确认的状态
+    stable: bool,
+    /// 与已确认状态不同的连续采样次数
+    pending: u32,
+}
+
+impl Debounced {
+    /// 输入一次采样，状态被确认翻转时返回新状态
+    fn update(&mut self, pressed: bool, threshold: u32) -> Option<bool> {
+        if pressed == self.stable {
+            self.pending = 0;
+            return None;
+        }
+        self.pending += 1;
+        if self.pending < threshold.max(1) {
+            return None;
+        }
+        self.stable = pressed;
+        self.pending = 0;
+        Some(pressed)
+    }
+}
+
+/// 设备保持的最近一次移动值的变化检测
+///
+/// 第一次采样只记录基准值（开始采样之前的移动不算新的移动），
+/// 之后与上一次采样不同的非零值才被当作一次新的移动。
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct LatestValue<T> {
+    previous: Option<T>,
+}
+
+impl<T: Copy + Default + PartialEq> LatestValue<T> {
+    /// 输入一次采样，出现新的移动时返回该值
+    pub(crate) fn update(&mut self, value: T) -> Option<T> {
+        let previous = self.previous.replace(value);
+        let changed = previous.is_some_and(|previous| previous != value);
+        (changed && value != T::default()).then_some(value)
+    }
+}
+
+/// 边沿检测器
+///
+/// 将连续的 [`MouseState`] 和键盘按下集合转换为事件，不依赖后端和线程，可单独使用。
+#[derive(Debug, Clone, Default)]
+pub struct EdgeDetector {
+    debounce_samples: u32,
+    buttons: [Debounced; MouseButton::ALL.len()],
+    keys: BTreeMap<i16, Debounced>,
+    motion: LatestValue<MousePosition>,
+    wheel: LatestValue<i32>,
+}
+
+impl EdgeDetector {
+    /// 创建新的边沿检测器，所有按键初始视为松开
+    pub fn new(debounce_samples: u32) -> Self {
+        Self {
+            debounce_samples,
+            ..Self::default()
+        }
+    }
+
+    /// 输入一次采样，返回本次检测到的事件
+    ///
+    /// `keys` 为本次采样中处于按下状态的键盘按键。
+    /// `mouse` 中的位移和滚轮是设备保持的最近一次移动值，只在与上一次采样不同时产生事件。
+    pub fn update(
+        &mut self,
+        mouse: &MouseState,
+        keys: &BTreeSet<i16>,
+        timestamp: Instant,
+    ) -> Vec<InputEvent> {
+        let mut events = Vec::new();
+        let mut push = |kind| events.push(InputEvent { kind, timestamp });
+
+        for (state, button) in self.buttons.iter_mut().zip(MouseButton::ALL) {
+            match state.update(mouse.is_pressed(button), self.debounce_samples) {
+                Some(true) => push(EventKind::ButtonDown(button)),
+                Some(false) => push(EventKind::ButtonUp(button)),
+                None => {}
+            }
+        }
+
+        if let Some(MousePosition { x, y }) = self.motion.update(mouse.position) {
+            push(EventKind::Moved { dx: x, dy: y });
+        }
+        if let Some(wheel) = self.wheel.update(mouse.wheel) {
+            push(EventKind::Wheel(wheel));
+        }
+
+        for &key in keys {
+            self.keys.entry(key).or_default();
+        }
+        for (&key, state) in self.keys.iter_mut() {
+            match state.update(keys.contains(&key), self.debounce_samples) {
+                Some(true) => push(EventKind::KeyDown(key)),
+                Some(false) => push(EventKind::KeyUp(key)),
+                None => {}
+            }
+        }
+
+        events
+    }
+}
+
+/// 启动采样线程，把检测到的事件交给 `emit`
+///
+/// 读取一次鼠标状态和 `keys` 中按下的按键，任何一次读取失败都返回错误
+fn sample(backend: &dyn Backend, keys: &[i16]) -> KmboxResult<(MouseState, BTreeSet<i16>)> {
+    let mouse = read_mouse_state(backend)?;
+    let mut pressed = BTreeSet::new();
+    for &key in keys {
+        if check_state(backend.monitor_keyboard(key), Operation::MonitorKeyboard)? {
+            pressed.insert(key);
+        }
+    }
+    Ok((mouse, pressed))
+}
+
+/// `emit` 返回 `false` 表示接收方已关闭，采样线程随即退出。
+pub(crate) fn spawn_sampler<F>(
+    backend: Arc<dyn Backend>,
+    config: MonitorConfig,
+    mut emit: F,
+) -> KmboxResult<(Arc<AtomicBool>, JoinHandle<()>)>
+where
+    F: FnMut(InputEvent) -> bool + Send + 'static,
+{
+    if config.interval.is_zero() {
+        return Err(KmboxError::ParameterError("采样间隔不能为0".to_string()));
+    }
+
+    let running = Arc::new(AtomicBool::new(true));
+    let flag = running.clone();
+
+    let handle = thread::Builder::new()
+        .name("kmbox-input-monitor".to_string())
+        .spawn(move || {
+            let mut detector = EdgeDetector::new(config.debounce_samples);
+            while flag.load(Ordering::Acquire) {
+                // 读取失败的采样整体丢弃，不会把读取错误当作按键释放，下一次采样时重试
+                if let Ok((mouse, keys)) = sample(backend.as_ref(), &config.keys) {
+                    for event in detector.update(&mouse, &keys, Instant::now()) {
+                        if !emit(event) {
+                            return;
+                        }
+                    }
+                }
+                thread::sleep(config.interval);
+            }
+        })
+        .map_err(|e| KmboxError::io("启动事件监听线程", e))?;
+
+    Ok((running, handle))
+}
+
+/// 物理键鼠事件监听器
+///
+/// 创建后立即在后台线程开始采样，销毁时停止采样线程。
+pub struct InputMonitor {
+    receiver: Receiver<InputEvent>,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl InputMonitor {
+    /// 在指定后端上启动事件监听
+    pub fn start(backend: Arc<dyn Backend>, config: MonitorConfig) -> KmboxResult<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let (running, handle) =
+            spawn_sampler(backend, config, move |event| sender.send(event).is_ok())?;
+
+        Ok(Self {
+            receiver,
+            running,
+            handle: Some(handle),
+        })
+    }
+
+    /// 阻塞等待下一个事件，监听已停止时返回 `None`
+    pub fn recv(&self) -> Option<InputEvent> {
+        self.receiver.recv().ok()
+    }
+
+    /// 在超时时间内等待下一个事件
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<InputEvent> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// 取出一个已产生的事件，不阻塞
+    pub fn try_recv(&self) -> Option<InputEvent> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// 阻塞迭代所有事件
+    pub fn iter(&self) -> impl Iterator<Item = InputEvent> + '_ {
+        self.receiver.iter()
+    }
+
+    /// 停止采样线程
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for InputMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::SimulatedBackend;
+
+    fn kinds(events: Vec<InputEvent>) -> Vec<EventKind> {
+        events.into_iter().map(|event| event.kind).collect()
+    }
+
+    #[test]
+    fn test_edge_detection() {
+        let mut detector = EdgeDetector::new(1);
+        let now = Instant::now();
+        let mut mouse = MouseState {
+            left: true,
+            ..MouseState::default()
+        };
+        let keys = BTreeSet::from([4]);
+
+        let events = kinds(detector.update(&mouse, &keys, now));
+        assert_eq!(
+            events,
+            vec![
+                EventKind::ButtonDown(MouseButton::Left),
+                EventKind::KeyDown(4)
+            ]
+        );
+
+        // 状态保持不变时不产生事件
+        assert!(detector.update(&mouse, &keys, now).is_empty());
+
+        mouse.left = false;
+        let events = kinds(detector.update(&mouse, &BTreeSet::new(), now));
+        assert_eq!(
+            events,
+            vec![EventKind::ButtonUp(MouseButton::Left), EventKind::KeyUp(4)]
+        );
+    }
+
+    #[test]
+    fn test_motion_and_wheel() {
+        let mut detector = EdgeDetector::new(1);
+        let now = Instant::now();
+        let none = BTreeSet::new();
+        let sample = |x, y, wheel| MouseState {
+            position: MousePosition { x, y },
+            wheel,
+            ..MouseState::default()
+        };
+
+        // 第一次采样只记录开始监听前的最近一次移动
+        assert!(detector.update(&sample(9, 9, -1), &none, now).is_empty());
+
+        let events = kinds(detector.update(&sample(3, -2, 1), &none, now));
+        assert_eq!(
+            events,
+            vec![EventKind::Moved { dx: 3, dy: -2 }, EventKind::Wheel(1)]
+        );
+
+        // 设备一直返回最近一次的值，重复的采样不是新的移动
+        for _ in 0..10 {
+            assert!(detector.update(&sample(3, -2, 1), &none, now).is_empty());
+        }
+        assert_eq!(
+            kinds(detector.update(&sample(3, 4, 1), &none, now)),
+            vec![EventKind::Moved { dx: 3, dy: 4 }]
+        );
+        assert_eq!(
+            kinds(detector.update(&sample(3, 4, -2), &none, now)),
+            vec![EventKind::Wheel(-2)]
+        );
+    }
+
+    #[test]
+    fn test_debounce() {
+        let mut detector = EdgeDetector::new(2);
+        let now = Instant::now();
+        let pressed = MouseState {
+            right: true,
+            ..MouseState::default()
+        };
+        let released = MouseState::default();
+        let none = BTreeSet::new();
+
+        // 只出现一次的抖动被忽略
+        assert!(detector.update(&pressed, &none, now).is_empty());
+        assert!(detector.update(&released, &none, now).is_empty());
+
+        assert!(detector.update(&pressed, &none, now).is_empty());
+        assert_eq!(
+            kinds(detector.update(&pressed, &none, now)),
+            vec![EventKind::ButtonDown(MouseButton::Right)]
+        );
+    }
+
+    #[test]
+    fn test_monitor_with_simulated_backend() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let config = MonitorConfig::default()
+            .with_interval(Duration::from_millis(1))
+            .with_keys([4, 5]);
+        let monitor = InputMonitor::start(sim.clone(), config).unwrap();
+        let timeout = Duration::from_secs(1);
+
+        sim.press_physical_button(MouseButton::Side1);
+        let event = monitor.recv_timeout(timeout).unwrap();
+        assert_eq!(event.kind, EventKind::ButtonDown(MouseButton::Side1));
+
+        sim.press_physical_key(5);
+        let event = monitor.recv_timeout(timeout).unwrap();
+        assert_eq!(event.kind, EventKind::KeyDown(5));
+
+        sim.move_physical(7, 8);
+        let event = monitor.recv_timeout(timeout).unwrap();
+        assert_eq!(event.kind, EventKind::Moved { dx: 7, dy: 8 });
+
+        sim.release_physical_button(MouseButton::Side1);
+        let event = monitor.recv_timeout(timeout).unwrap();
+        assert_eq!(event.kind, EventKind::ButtonUp(MouseButton::Side1));
+    }
+
+    #[test]
+    fn test_read_errors_drop_the_sample() {
+        let sim = Arc::new(SimulatedBackend::new());
+        sim.press_physical_key(4);
+        let (_, keys) = sample(sim.as_ref(), &[4, 5]).unwrap();
+        assert_eq!(keys, BTreeSet::from([4]));
+
+        sim.inject_fault(-1);
+        assert_eq!(sample(sim.as_ref(), &[4]).unwrap_err().code(), Some(-1));
+        sim.clear_fault();
+
+        // 读取失败期间不会产生按键释放和重新按下
+        let config = MonitorConfig::default()
+            .with_interval(Duration::from_millis(1))
+            .with_keys([4]);
+        let monitor = InputMonitor::start(sim.clone(), config).unwrap();
+        let event = monitor.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(event.kind, EventKind::KeyDown(4));
+        sim.inject_fault(-1);
+        thread::sleep(Duration::from_millis(10));
+        sim.clear_fault();
+        thread::sleep(Duration::from_millis(10));
+        assert_eq!(monitor.try_recv(), None);
+    }
+
+    #[test]
+    fn test_persisting_last_move_emits_once() {
+        let sim = Arc::new(SimulatedBackend::new());
+        // 开始监听前的移动不产生事件
+        sim.move_physical(50, 50);
+        let config = MonitorConfig::default()
+            .with_interval(Duration::from_millis(1))
+            .with_keys([]);
+        let monitor = InputMonitor::start(sim.clone(), config).unwrap();
+        thread::sleep(Duration::from_millis(10));
+        assert_eq!(monitor.try_recv(), None);
+
+        // 设备一直返回最近一次的值，几十次采样也只产生一个事件
+        sim.move_physical(5, 0);
+        sim.scroll_physical(1);
+        thread::sleep(Duration::from_millis(30));
+        sim.move_physical(-2, 1);
+        thread::sleep(Duration::from_millis(30));
+
+        let events: Vec<EventKind> = std::iter::from_fn(|| monitor.try_recv())
+            .map(|event| event.kind)
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                EventKind::Moved { dx: 5, dy: 0 },
+                EventKind::Wheel(1),
+                EventKind::Moved { dx: -2, dy: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zero_interval_rejected() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let config = MonitorConfig::default().with_interval(Duration::ZERO);
+        assert!(InputMonitor::start(sim, config).is_err());
+    }
+}
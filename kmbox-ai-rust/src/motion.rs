@@ -0,0 +1,380 @@
+//! 平滑移动模块
+//!
+//! `mouse_move` 每次只发送一个相对位移，且受 `short` 范围限制。
+//! [`MotionPlanner`] 把目标位移拆分为按固定报告率发送的一系列小位移，
+//! 路径可以是直线、缓入缓出或三次贝塞尔曲线。
+//!
+//! 规划时先计算路径上每个报告时刻的理想累计位置，再对累计位置取整并求差，
+//! 亚像素的余量累积到后续报告中，所有位移之和与目标位移完全相等。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use kmbox_ai_rust::kmbox_ai::KmboxAI;
+//! use kmbox_ai_rust::motion::{MotionPath, MotionPlanner};
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let kmbox = KmboxAI::new()?;
+//! let planner = MotionPlanner::new()
+//!     .with_report_rate(500)?
+//!     .with_duration(Duration::from_millis(300))
+//!     .with_path(MotionPath::EaseInOut);
+//! kmbox.mouse_move_smooth(800, -120, &planner)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::{check_result, KmboxError, KmboxResult, Operation};
+use crate::recorder::wait_until;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 移动路径
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MotionPath {
+    /// 匀速直线
+    Linear,
+    /// 缓入缓出（三次缓动），两端慢、中间快
+    EaseInOut,
+    /// 三次贝塞尔曲线，起点为 (0, 0)，终点为目标位移，控制点为相对起点的像素坐标
+    Bezier {
+        control1: (f64, f64),
+        control2: (f64, f64),
+    },
+}
+
+impl MotionPath {
+    /// 路径在进度 `t`（0..=1）处相对目标位移 `(dx, dy)` 的位置
+    fn point(&self, t: f64, dx: f64, dy: f64) -> (f64, f64) {
+        match *self {
+            MotionPath::Linear => (dx * t, dy * t),
+            MotionPath::EaseInOut => {
+                let eased = if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                };
+                (dx * eased, dy * eased)
+            }
+            MotionPath::Bezier { control1, control2 } => {
+                let u = 1.0 - t;
+                let a = 3.0 * u * u * t;
+                let b = 3.0 * u * t * t;
+                let c = t * t * t;
+                (
+                    a * control1.0 + b * control2.0 + c * dx,
+                    a * control1.1 + b * control2.1 + c * dy,
+                )
+            }
+        }
+    }
+}
+
+/// 一次位移报告
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MotionStep {
+    /// 相对移动开始的发送时间
+    pub at: Duration,
+    /// X 方向位移
+    pub dx: i16,
+    /// Y 方向位移
+    pub dy: i16,
+}
+
+/// 平滑移动规划器
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotionPlanner {
+    report_rate: u32,
+    max_step: i16,
+    duration: Duration,
+    path: MotionPath,
+}
+
+impl Default for MotionPlanner {
+    fn default() -> Self {
+        Self {
+            report_rate: 1000,
+            max_step: 127,
+            duration: Duration::ZERO,
+            path: MotionPath::Linear,
+        }
+    }
+}
+
+impl MotionPlanner {
+    /// 创建规划器：1000Hz 报告率，单次报告最大位移 127，直线路径，不限定时长
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置报告率（每秒报告数）
+    pub fn with_report_rate(mut self, rate: u32) -> KmboxResult<Self> {
+        if rate == 0 {
+            return Err(KmboxError::ParameterError("报告率不能为0".to_string()));
+        }
+        self.report_rate = rate;
+        Ok(self)
+    }
+
+    /// 设置单次报告在每个方向上的最大位移
+    pub fn with_max_step(mut self, max_step: i16) -> KmboxResult<Self> {
+        if max_step <= 0 {
+            return Err(KmboxError::ParameterError(format!(
+                "单次最大位移必须为正数: {}",
+                max_step
+            )));
+        }
+        self.max_step = max_step;
+        Ok(self)
+    }
+
+    /// 设置期望的移动时长
+    ///
+    /// 按报告率和最大位移无法在该时长内完成时，移动时间会相应延长。
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// 设置移动路径
+    pub fn with_path(mut self, path: MotionPath) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// 报告间隔
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(1) / self.report_rate
+    }
+
+    /// 把目标位移规划为报告序列，位移为 0 的报告不包含在内
+    pub fn plan(&self, dx: i32, dy: i32) -> Vec<MotionStep> {
+        if dx == 0 && dy == 0 {
+            return Vec::new();
+        }
+
+        let max_step = self.max_step as u64;
+        let longest = dx.unsigned_abs().max(dy.unsigned_abs()) as u64;
+        let by_duration = (self.duration.as_secs_f64() * self.report_rate as f64).ceil() as u64;
+        let mut count = by_duration.max(longest.div_ceil(max_step)).max(1);
+
+        // 曲线路径的速度不均匀，增加报告数量直到每次位移都不超过上限
+        loop {
+            let deltas = self.deltas(dx, dy, count);
+            let worst = deltas
+                .iter()
+                .map(|&(x, y)| x.unsigned_abs().max(y.unsigned_abs()))
+                .max()
+                .unwrap_or(0);
+            if worst <= max_step {
+                let interval = self.interval();
+                return deltas
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, delta)| *delta != (0, 0))
+                    .map(|(index, (x, y))| MotionStep {
+                        at: interval * index as u32,
+                        dx: x as i16,
+                        dy: y as i16,
+                    })
+                    .collect();
+            }
+            count = (count + 1).max(count * worst.div_ceil(max_step));
+        }
+    }
+
+    /// 把路径等分为 `count` 段，返回每段取整后的位移
+    fn deltas(&self, dx: i32, dy: i32, count: u64) -> Vec<(i64, i64)> {
+        let (fx, fy) = (dx as f64, dy as f64);
+        let mut previous = (0i64, 0i64);
+        (1..=count)
+            .map(|index| {
+                let current = if index == count {
+                    // 终点直接使用目标位移，避免浮点误差
+                    (dx as i64, dy as i64)
+                } else {
+                    let (x, y) = self.path.point(index as f64 / count as f64, fx, fy);
+                    (x.round() as i64, y.round() as i64)
+                };
+                let delta = (current.0 - previous.0, current.1 - previous.1);
+                previous = current;
+                delta
+            })
+            .collect()
+    }
+
+    /// 在当前线程按计划发送位移，完成后返回
+    pub fn execute(&self, backend: &dyn Backend, dx: i32, dy: i32) -> KmboxResult<()> {
+        self.run(backend, dx, dy, &AtomicBool::new(false))
+    }
+
+    /// 在后台线程按计划发送位移
+    pub fn spawn(&self, backend: Arc<dyn Backend>, dx: i32, dy: i32) -> KmboxResult<MotionHandle> {
+        let planner = self.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        let handle = thread::Builder::new()
+            .name("kmbox-motion".to_string())
+            .spawn(move || planner.run(backend.as_ref(), dx, dy, &flag))
+            .map_err(|e| KmboxError::io("启动移动线程", e))?;
+        Ok(MotionHandle {
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    fn run(&self, backend: &dyn Backend, dx: i32, dy: i32, stop: &AtomicBool) -> KmboxResult<()> {
+        let start = Instant::now();
+        for step in self.plan(dx, dy) {
+            if !wait_until(start + step.at, stop) {
+                return Ok(());
+            }
+            check_result(backend.mouse_move(step.dx, step.dy), Operation::MouseMove)?;
+        }
+        Ok(())
+    }
+}
+
+/// 后台移动句柄，销毁时停止移动
+pub struct MotionHandle {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<KmboxResult<()>>>,
+}
+
+impl MotionHandle {
+    /// 移动是否已经结束
+    pub fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// 停止移动并等待移动线程退出，已发送的位移不会撤销
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+
+    /// 等待移动完成并返回结果
+    pub fn wait(mut self) -> KmboxResult<()> {
+        match self.handle.take() {
+            Some(handle) => handle
+                .join()
+                .map_err(|_| KmboxError::SystemError("移动线程异常退出".to_string()))?,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for MotionHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
+
+    fn total(steps: &[MotionStep]) -> (i32, i32) {
+        steps.iter().fold((0, 0), |(x, y), step| {
+            (x + step.dx as i32, y + step.dy as i32)
+        })
+    }
+
+    #[test]
+    fn test_linear_plan_is_exact() {
+        let planner = MotionPlanner::new().with_max_step(3).unwrap();
+        let steps = planner.plan(10, -4);
+        assert_eq!(steps.len(), 4);
+        assert_eq!(total(&steps), (10, -4));
+        assert!(steps
+            .iter()
+            .all(|step| step.dx.abs() <= 3 && step.dy.abs() <= 3));
+
+        // 超出 short 范围的位移也能拆分
+        let steps = MotionPlanner::new().plan(100_000, 0);
+        assert_eq!(total(&steps), (100_000, 0));
+        assert!(steps.iter().all(|step| step.dx <= 127));
+    }
+
+    #[test]
+    fn test_sub_pixel_remainders() {
+        let planner = MotionPlanner::new()
+            .with_report_rate(100)
+            .unwrap()
+            .with_duration(Duration::from_millis(70));
+        let steps = planner.plan(3, 1);
+        // 7 个报告时刻中只有位移不为 0 的会发送
+        assert_eq!(total(&steps), (3, 1));
+        assert!(steps.len() <= 7);
+        assert!(steps.iter().all(|step| step.at < Duration::from_millis(70)));
+        assert_eq!(steps[0].at.as_millis() % 10, 0);
+    }
+
+    #[test]
+    fn test_ease_in_out_and_bezier() {
+        let planner = MotionPlanner::new()
+            .with_duration(Duration::from_millis(20))
+            .with_path(MotionPath::EaseInOut);
+        let steps = planner.plan(400, 0);
+        assert_eq!(total(&steps), (400, 0));
+        let first = steps.first().unwrap().dx;
+        let middle = steps[steps.len() / 2].dx;
+        assert!(first < middle);
+
+        let planner =
+            MotionPlanner::new()
+                .with_max_step(10)
+                .unwrap()
+                .with_path(MotionPath::Bezier {
+                    control1: (0.0, 300.0),
+                    control2: (100.0, 300.0),
+                });
+        let steps = planner.plan(100, 0);
+        assert_eq!(total(&steps), (100, 0));
+        assert!(steps
+            .iter()
+            .all(|step| step.dx.abs() <= 10 && step.dy.abs() <= 10));
+        // 曲线先向下再回到终点
+        assert!(steps.iter().any(|step| step.dy > 0));
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(MotionPlanner::new().with_report_rate(0).is_err());
+        assert!(MotionPlanner::new().with_max_step(0).is_err());
+        assert!(MotionPlanner::new().plan(0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_execute_and_spawn() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let planner = MotionPlanner::new().with_max_step(50).unwrap();
+        planner.execute(sim.as_ref(), 120, 30).unwrap();
+        let moves: Vec<HidReport> = planner
+            .plan(120, 30)
+            .iter()
+            .map(|step| HidReport::Move {
+                x: step.dx as i32,
+                y: step.dy as i32,
+            })
+            .collect();
+        assert_eq!(sim.reports(), moves);
+
+        let sim = Arc::new(SimulatedBackend::new());
+        let planner = planner.with_duration(Duration::from_secs(60));
+        let mut handle = planner.spawn(sim.clone(), 1000, 0).unwrap();
+        while sim.reports().is_empty() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        handle.stop();
+        assert!(handle.is_finished());
+        assert!(sim.reports().len() < 1000);
+    }
+}
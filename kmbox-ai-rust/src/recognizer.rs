@@ -0,0 +1,776 @@
+//! 物理鼠标手势识别模块
+//!
+//! 根据事件监听得到的物理鼠标按键和移动识别用户做出的手势，
+//! 识别成功后执行回调或向通道发送消息，盒子上的工具不接键盘也能被控制。
+//!
+//! ## 手势轨迹
+//!
+//! - 模板指定了触发键时，按下触发键到释放之间的移动构成一条轨迹，释放时识别
+//! - 未指定触发键时，没有按键按下期间的连续移动构成一条轨迹，
+//!   停止移动超过 [`RecognizerConfig::with_stroke_timeout`] 后识别
+//! - 轨迹时长超过 [`RecognizerConfig::with_max_duration`] 时不识别
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::backend::default_backend;
+//! use kmbox_ai_rust::kmbox_ai::MouseButton;
+//! use kmbox_ai_rust::monitor::MonitorConfig;
+//! use kmbox_ai_rust::recognizer::{Direction, GesturePattern, GestureRecognizer};
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let mut recognizer = GestureRecognizer::new(default_backend());
+//! recognizer.on(
+//!     GesturePattern::Drag { trigger: MouseButton::Side1, direction: Direction::Left, min_distance: 200 },
+//!     |_| println!("上一页"),
+//! )?;
+//! let menu = recognizer.subscribe(GesturePattern::MultiClick { button: MouseButton::Side2, count: 2 })?;
+//! recognizer.on(GesturePattern::Circle { trigger: None, clockwise: None }, |_| println!("画圈"))?;
+//! recognizer.start(MonitorConfig::default())?;
+//! menu.recv().ok();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::{KmboxError, KmboxResult};
+use crate::kmbox_ai::MouseButton;
+use crate::monitor::{EventKind, InputEvent, InputMonitor, MonitorConfig};
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 移动方向（屏幕坐标，Y 轴向下）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// 向左
+    Left,
+    /// 向右
+    Right,
+    /// 向上
+    Up,
+    /// 向下
+    Down,
+}
+
+impl Direction {
+    /// 位移的主方向，位移为 0 时返回 `None`
+    fn of(dx: f64, dy: f64) -> Option<Self> {
+        if dx == 0.0 && dy == 0.0 {
+            None
+        } else if dx.abs() >= dy.abs() {
+            Some(if dx < 0.0 {
+                Direction::Left
+            } else {
+                Direction::Right
+            })
+        } else {
+            Some(if dy < 0.0 {
+                Direction::Up
+            } else {
+                Direction::Down
+            })
+        }
+    }
+}
+
+/// 手势模板
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GesturePattern {
+    /// 按住触发键向指定方向拖动至少 `min_distance` 像素，垂直方向的偏移不超过拖动距离的一半
+    Drag {
+        trigger: MouseButton,
+        direction: Direction,
+        min_distance: u32,
+    },
+    /// 按顺序向各个方向划动，例如 `[Down, Right]` 为 "L" 形；每段至少为最小分段长度
+    Strokes {
+        trigger: Option<MouseButton>,
+        directions: Vec<Direction>,
+    },
+    /// 画一个圈；`clockwise` 为 `None` 时不区分方向
+    Circle {
+        trigger: Option<MouseButton>,
+        clockwise: Option<bool>,
+    },
+    /// 在点击间隔内连续点击 `count` 次，第 `count` 次释放时触发
+    MultiClick { button: MouseButton, count: u32 },
+}
+
+impl GesturePattern {
+    /// 轨迹类模板的触发键，点击类模板返回 `None`
+    fn stroke_trigger(&self) -> Option<Option<MouseButton>> {
+        match self {
+            GesturePattern::Drag { trigger, .. } => Some(Some(*trigger)),
+            GesturePattern::Strokes { trigger, .. } | GesturePattern::Circle { trigger, .. } => {
+                Some(*trigger)
+            }
+            GesturePattern::MultiClick { .. } => None,
+        }
+    }
+}
+
+/// 识别参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecognizerConfig {
+    /// 连续点击时上一次释放到下一次按下的最大间隔
+    pub click_interval: Duration,
+    /// 没有按键时停止移动多久视为轨迹结束
+    pub stroke_timeout: Duration,
+    /// 轨迹的最长时长
+    pub max_duration: Duration,
+    /// 划动中一段的最小长度（像素），按下期间移动不超过该距离时视为点击
+    pub min_segment: u32,
+}
+
+impl Default for RecognizerConfig {
+    fn default() -> Self {
+        Self {
+            click_interval: Duration::from_millis(300),
+            stroke_timeout: Duration::from_millis(250),
+            max_duration: Duration::from_secs(2),
+            min_segment: 30,
+        }
+    }
+}
+
+impl RecognizerConfig {
+    /// 设置连续点击的最大间隔
+    pub fn with_click_interval(mut self, interval: Duration) -> Self {
+        self.click_interval = interval;
+        self
+    }
+
+    /// 设置无按键轨迹的结束判定时间
+    pub fn with_stroke_timeout(mut self, timeout: Duration) -> Self {
+        self.stroke_timeout = timeout;
+        self
+    }
+
+    /// 设置轨迹的最长时长
+    pub fn with_max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = duration;
+        self
+    }
+
+    /// 设置划动分段的最小长度
+    pub fn with_min_segment(mut self, pixels: u32) -> Self {
+        self.min_segment = pixels;
+        self
+    }
+}
+
+/// 一条移动轨迹
+#[derive(Debug, Clone)]
+struct Stroke {
+    trigger: Option<MouseButton>,
+    started: Instant,
+    last_move: Instant,
+    /// 从起点开始的累计位置
+    points: Vec<(f64, f64)>,
+}
+
+impl Stroke {
+    fn new(trigger: Option<MouseButton>, now: Instant) -> Self {
+        Self {
+            trigger,
+            started: now,
+            last_move: now,
+            points: vec![(0.0, 0.0)],
+        }
+    }
+
+    fn push(&mut self, dx: i32, dy: i32, now: Instant) {
+        let (x, y) = self.end();
+        self.points.push((x + dx as f64, y + dy as f64));
+        self.last_move = now;
+    }
+
+    fn end(&self) -> (f64, f64) {
+        self.points[self.points.len() - 1]
+    }
+
+    fn displacement(&self) -> f64 {
+        let (x, y) = self.end();
+        x.hypot(y)
+    }
+
+    /// 把轨迹量化为方向序列，短于 `min_segment` 的抖动并入下一段
+    fn directions(&self, min_segment: f64) -> Vec<Direction> {
+        let mut directions = Vec::new();
+        let mut anchor = self.points[0];
+        for &(x, y) in &self.points[1..] {
+            let (dx, dy) = (x - anchor.0, y - anchor.1);
+            if dx.hypot(dy) < min_segment {
+                continue;
+            }
+            if let Some(direction) = Direction::of(dx, dy) {
+                if directions.last() != Some(&direction) {
+                    directions.push(direction);
+                }
+            }
+            anchor = (x, y);
+        }
+        directions
+    }
+
+    /// 绕轨迹中心转过的角度（弧度，顺时针为正）和平均半径
+    fn sweep(&self) -> (f64, f64) {
+        let count = self.points.len() as f64;
+        let (sx, sy) = self
+            .points
+            .iter()
+            .fold((0.0, 0.0), |(sx, sy), &(x, y)| (sx + x, sy + y));
+        let center = (sx / count, sy / count);
+        let radius = self
+            .points
+            .iter()
+            .map(|&(x, y)| (x - center.0).hypot(y - center.1))
+            .sum::<f64>()
+            / count;
+
+        let mut total = 0.0;
+        let mut previous: Option<f64> = None;
+        for &(x, y) in &self.points {
+            let angle = (y - center.1).atan2(x - center.0);
+            if let Some(previous) = previous {
+                let mut delta = angle - previous;
+                if delta > PI {
+                    delta -= 2.0 * PI;
+                } else if delta < -PI {
+                    delta += 2.0 * PI;
+                }
+                total += delta;
+            }
+            previous = Some(angle);
+        }
+        (total, radius)
+    }
+
+    fn matches(&self, pattern: &GesturePattern, config: &RecognizerConfig) -> bool {
+        if pattern.stroke_trigger() != Some(self.trigger)
+            || self.last_move.saturating_duration_since(self.started) > config.max_duration
+        {
+            return false;
+        }
+        let min_segment = config.min_segment as f64;
+        match pattern {
+            GesturePattern::Drag {
+                direction,
+                min_distance,
+                ..
+            } => {
+                let (x, y) = self.end();
+                let (along, across) = match direction {
+                    Direction::Left => (-x, y),
+                    Direction::Right => (x, y),
+                    Direction::Up => (-y, x),
+                    Direction::Down => (y, x),
+                };
+                along >= *min_distance as f64 && across.abs() <= along / 2.0
+            }
+            GesturePattern::Strokes { directions, .. } => {
+                !directions.is_empty() && self.directions(min_segment) == *directions
+            }
+            GesturePattern::Circle { clockwise, .. } => {
+                let (sweep, radius) = self.sweep();
+                // 转过约一整圈，半径足够大，终点回到起点附近
+                sweep.abs() >= 1.75 * PI
+                    && radius >= min_segment
+                    && self.displacement() <= radius
+                    && clockwise.is_none_or(|clockwise| clockwise == (sweep > 0.0))
+            }
+            GesturePattern::MultiClick { .. } => false,
+        }
+    }
+}
+
+/// 手势注册编号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GestureId(u64);
+
+/// 手势识别事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GestureEvent {
+    /// 注册编号
+    pub id: GestureId,
+    /// 识别出的手势模板
+    pub pattern: GesturePattern,
+    /// 识别时刻
+    pub timestamp: Instant,
+}
+
+type Callback = Arc<Mutex<dyn FnMut(&GestureEvent) + Send>>;
+
+/// 识别后的动作
+enum Action {
+    Callback(Callback),
+    Channel(Sender<GestureEvent>),
+}
+
+impl Action {
+    fn share(&self) -> Action {
+        match self {
+            Action::Callback(callback) => Action::Callback(callback.clone()),
+            Action::Channel(sender) => Action::Channel(sender.clone()),
+        }
+    }
+}
+
+/// 一个按键的点击计数
+#[derive(Debug, Clone, Copy)]
+struct Clicks {
+    count: u32,
+    released_at: Instant,
+}
+
+/// 模板表和识别状态
+#[derive(Default)]
+struct Registry {
+    config: RecognizerConfig,
+    next_id: u64,
+    bindings: BTreeMap<GestureId, (GesturePattern, Action)>,
+    /// 按下的按键及其轨迹
+    held: BTreeMap<MouseButton, Stroke>,
+    /// 没有按键时的轨迹
+    free: Option<Stroke>,
+    clicks: BTreeMap<MouseButton, Clicks>,
+}
+
+impl Registry {
+    fn handle_event(&mut self, event: &InputEvent) -> Vec<(Action, GestureEvent)> {
+        let now = event.timestamp;
+        let mut fired = self.tick(now);
+        match event.kind {
+            EventKind::ButtonDown(button) => {
+                self.free = None;
+                self.held.insert(button, Stroke::new(Some(button), now));
+            }
+            EventKind::ButtonUp(button) => {
+                if let Some(stroke) = self.held.remove(&button) {
+                    if stroke.displacement() < self.config.min_segment as f64 {
+                        fired.extend(self.clicked(button, stroke.started, now));
+                    } else {
+                        self.clicks.remove(&button);
+                        fired.extend(self.recognize(&stroke, now));
+                    }
+                }
+            }
+            EventKind::Moved { dx, dy } => {
+                if self.held.is_empty() {
+                    self.free
+                        .get_or_insert_with(|| Stroke::new(None, now))
+                        .push(dx, dy, now);
+                }
+                for stroke in self.held.values_mut() {
+                    stroke.push(dx, dy, now);
+                }
+            }
+            _ => {}
+        }
+        fired
+    }
+
+    /// 结束已停止移动的无按键轨迹
+    fn tick(&mut self, now: Instant) -> Vec<(Action, GestureEvent)> {
+        let timeout = self.config.stroke_timeout;
+        match self.free.take() {
+            Some(stroke) if now.saturating_duration_since(stroke.last_move) >= timeout => {
+                self.recognize(&stroke, now)
+            }
+            other => {
+                self.free = other;
+                Vec::new()
+            }
+        }
+    }
+
+    fn clicked(
+        &mut self,
+        button: MouseButton,
+        pressed_at: Instant,
+        now: Instant,
+    ) -> Vec<(Action, GestureEvent)> {
+        let interval = self.config.click_interval;
+        let count = match self.clicks.get(&button) {
+            Some(previous)
+                if pressed_at.saturating_duration_since(previous.released_at) <= interval =>
+            {
+                previous.count + 1
+            }
+            _ => 1,
+        };
+        self.clicks.insert(
+            button,
+            Clicks {
+                count,
+                released_at: now,
+            },
+        );
+        self.fire(now, |pattern| {
+            *pattern == GesturePattern::MultiClick { button, count }
+        })
+    }
+
+    fn recognize(&self, stroke: &Stroke, now: Instant) -> Vec<(Action, GestureEvent)> {
+        self.fire(now, |pattern| stroke.matches(pattern, &self.config))
+    }
+
+    fn fire(
+        &self,
+        now: Instant,
+        matches: impl Fn(&GesturePattern) -> bool,
+    ) -> Vec<(Action, GestureEvent)> {
+        self.bindings
+            .iter()
+            .filter(|(_, (pattern, _))| matches(pattern))
+            .map(|(id, (pattern, action))| {
+                let event = GestureEvent {
+                    id: *id,
+                    pattern: pattern.clone(),
+                    timestamp: now,
+                };
+                (action.share(), event)
+            })
+            .collect()
+    }
+}
+
+/// 在不持有模板表锁的情况下执行动作，回调中可以注册或注销手势
+fn dispatch(fired: Vec<(Action, GestureEvent)>) {
+    for (action, event) in fired {
+        match action {
+            Action::Callback(callback) => {
+                let mut callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+                callback(&event);
+            }
+            Action::Channel(sender) => {
+                let _ = sender.send(event);
+            }
+        }
+    }
+}
+
+/// 识别器与监听线程共享的状态
+struct Shared {
+    backend: Arc<dyn Backend>,
+    registry: Mutex<Registry>,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Registry> {
+        self.registry.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn handle_event(&self, event: &InputEvent) {
+        let fired = self.lock().handle_event(event);
+        dispatch(fired);
+    }
+
+    fn tick(&self, now: Instant) {
+        let fired = self.lock().tick(now);
+        dispatch(fired);
+    }
+}
+
+/// 物理鼠标手势识别器
+///
+/// 可以通过 [`GestureRecognizer::start`] 在后台线程中自动监听，
+/// 也可以把 [`InputMonitor`] 的事件手动交给 [`GestureRecognizer::handle_event`]。
+pub struct GestureRecognizer {
+    shared: Arc<Shared>,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl GestureRecognizer {
+    /// 在指定后端上创建识别器
+    pub fn new(backend: Arc<dyn Backend>) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                backend,
+                registry: Mutex::new(Registry::default()),
+            }),
+            running: Arc::new(AtomicBool::new(false)),
+            handle: None,
+        }
+    }
+
+    /// 设置识别参数
+    pub fn with_config(self, config: RecognizerConfig) -> Self {
+        self.shared.lock().config = config;
+        self
+    }
+
+    fn register(&self, pattern: GesturePattern, action: Action) -> KmboxResult<GestureId> {
+        match &pattern {
+            GesturePattern::MultiClick { count: 0, .. } => {
+                return Err(KmboxError::ParameterError("点击次数不能为0".to_string()));
+            }
+            GesturePattern::Strokes { directions, .. } if directions.is_empty() => {
+                return Err(KmboxError::ParameterError("划动方向不能为空".to_string()));
+            }
+            _ => {}
+        }
+        let mut registry = self.shared.lock();
+        registry.next_id += 1;
+        let id = GestureId(registry.next_id);
+        registry.bindings.insert(id, (pattern, action));
+        Ok(id)
+    }
+
+    /// 注册手势回调
+    ///
+    /// 回调在监听线程（或调用 [`GestureRecognizer::handle_event`] 的线程）中执行。
+    pub fn on<F>(&self, pattern: GesturePattern, callback: F) -> KmboxResult<GestureId>
+    where
+        F: FnMut(&GestureEvent) + Send + 'static,
+    {
+        let callback: Callback = Arc::new(Mutex::new(callback));
+        self.register(pattern, Action::Callback(callback))
+    }
+
+    /// 注册手势并通过通道接收识别事件
+    pub fn subscribe(&self, pattern: GesturePattern) -> KmboxResult<Receiver<GestureEvent>> {
+        let (sender, receiver) = mpsc::channel();
+        self.register(pattern, Action::Channel(sender))?;
+        Ok(receiver)
+    }
+
+    /// 注销手势
+    pub fn unregister(&self, id: GestureId) -> KmboxResult<()> {
+        if self.shared.lock().bindings.remove(&id).is_none() {
+            return Err(KmboxError::ParameterError(format!("手势不存在: {:?}", id)));
+        }
+        Ok(())
+    }
+
+    /// 处理一个物理输入事件，只关心鼠标按键和移动
+    pub fn handle_event(&self, event: &InputEvent) {
+        self.shared.handle_event(event);
+    }
+
+    /// 检查无按键轨迹是否结束，`now` 为当前时刻
+    pub fn tick(&self, now: Instant) {
+        self.shared.tick(now);
+    }
+
+    /// 启动后台监听线程
+    pub fn start(&mut self, config: MonitorConfig) -> KmboxResult<()> {
+        if self.handle.is_some() {
+            return Err(KmboxError::SystemError("手势监听已经启动".to_string()));
+        }
+
+        let interval = config.interval;
+        let monitor = InputMonitor::start(self.shared.backend.clone(), config)?;
+        let shared = self.shared.clone();
+        let running = self.running.clone();
+        running.store(true, Ordering::Release);
+
+        let handle = thread::Builder::new()
+            .name("kmbox-gesture".to_string())
+            .spawn(move || {
+                while running.load(Ordering::Acquire) {
+                    match monitor.recv_timeout(interval) {
+                        Some(event) => shared.handle_event(&event),
+                        None => shared.tick(Instant::now()),
+                    }
+                }
+            })
+            .map_err(|e| KmboxError::io("启动手势监听线程", e))?;
+        self.handle = Some(handle);
+        Ok(())
+    }
+
+    /// 停止后台监听线程
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for GestureRecognizer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::SimulatedBackend;
+
+    /// 按时间顺序产生事件的测试输入
+    struct Input<'a> {
+        recognizer: &'a GestureRecognizer,
+        now: Instant,
+    }
+
+    impl<'a> Input<'a> {
+        fn new(recognizer: &'a GestureRecognizer) -> Self {
+            Self {
+                recognizer,
+                now: Instant::now(),
+            }
+        }
+
+        fn send(&mut self, kind: EventKind, after_ms: u64) {
+            self.now += Duration::from_millis(after_ms);
+            self.recognizer.handle_event(&InputEvent {
+                kind,
+                timestamp: self.now,
+            });
+        }
+
+        fn moves(&mut self, points: impl IntoIterator<Item = (i32, i32)>) {
+            for (dx, dy) in points {
+                self.send(EventKind::Moved { dx, dy }, 5);
+            }
+        }
+
+        fn click(&mut self, button: MouseButton, after_ms: u64) {
+            self.send(EventKind::ButtonDown(button), after_ms);
+            self.send(EventKind::ButtonUp(button), 40);
+        }
+    }
+
+    fn recognizer() -> GestureRecognizer {
+        GestureRecognizer::new(Arc::new(SimulatedBackend::new()))
+    }
+
+    #[test]
+    fn test_drag_with_trigger() {
+        let recognizer = recognizer();
+        let left = recognizer
+            .subscribe(GesturePattern::Drag {
+                trigger: MouseButton::Side1,
+                direction: Direction::Left,
+                min_distance: 100,
+            })
+            .unwrap();
+        let mut input = Input::new(&recognizer);
+
+        input.send(EventKind::ButtonDown(MouseButton::Side1), 0);
+        input.moves(std::iter::repeat_n((-30, 4), 5));
+        input.send(EventKind::ButtonUp(MouseButton::Side1), 10);
+        assert!(left.try_recv().is_ok());
+
+        // 距离不足、方向不对或触发键不对都不识别
+        input.send(EventKind::ButtonDown(MouseButton::Side1), 100);
+        input.moves([(-40, 0), (-40, 0)]);
+        input.send(EventKind::ButtonUp(MouseButton::Side1), 10);
+        input.send(EventKind::ButtonDown(MouseButton::Side1), 100);
+        input.moves([(-60, -60), (-60, -60)]);
+        input.send(EventKind::ButtonUp(MouseButton::Side1), 10);
+        input.send(EventKind::ButtonDown(MouseButton::Side2), 100);
+        input.moves([(-100, 0), (-100, 0)]);
+        input.send(EventKind::ButtonUp(MouseButton::Side2), 10);
+        assert!(left.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_multi_click_within_interval() {
+        let recognizer = recognizer().with_config(
+            RecognizerConfig::default().with_click_interval(Duration::from_millis(200)),
+        );
+        let double = recognizer
+            .subscribe(GesturePattern::MultiClick {
+                button: MouseButton::Side2,
+                count: 2,
+            })
+            .unwrap();
+        let mut input = Input::new(&recognizer);
+
+        input.click(MouseButton::Side2, 0);
+        assert!(double.try_recv().is_err());
+        input.click(MouseButton::Side2, 150);
+        assert!(double.try_recv().is_ok());
+
+        // 间隔过长重新计数，其他按键的点击不计入
+        input.click(MouseButton::Side2, 500);
+        input.click(MouseButton::Left, 50);
+        input.click(MouseButton::Side2, 300);
+        assert!(double.try_recv().is_err());
+        input.click(MouseButton::Side2, 100);
+        assert!(double.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_free_circle_and_strokes() {
+        let recognizer = recognizer();
+        let circles = Arc::new(Mutex::new(Vec::new()));
+        let seen = circles.clone();
+        recognizer
+            .on(
+                GesturePattern::Circle {
+                    trigger: None,
+                    clockwise: Some(true),
+                },
+                move |event| seen.lock().unwrap().push(event.id),
+            )
+            .unwrap();
+        let l_shape = recognizer
+            .subscribe(GesturePattern::Strokes {
+                trigger: None,
+                directions: vec![Direction::Down, Direction::Right],
+            })
+            .unwrap();
+        let mut input = Input::new(&recognizer);
+
+        // 屏幕坐标下顺时针画圈：右、下、左、上
+        let points: Vec<(i32, i32)> = (0..=24)
+            .map(|step| {
+                let angle = step as f64 / 24.0 * 2.0 * PI;
+                ((angle.cos() * 100.0) as i32, (angle.sin() * 100.0) as i32)
+            })
+            .collect();
+        input.moves(
+            points
+                .windows(2)
+                .map(|w| (w[1].0 - w[0].0, w[1].1 - w[0].1)),
+        );
+        assert!(circles.lock().unwrap().is_empty());
+        recognizer.tick(input.now + Duration::from_millis(300));
+        assert_eq!(circles.lock().unwrap().len(), 1);
+
+        input.now += Duration::from_millis(300);
+        input.moves([(0, 40), (2, 40), (40, 0), (40, -3)]);
+        input.send(EventKind::Wheel(1), 300);
+        assert!(l_shape.try_recv().is_ok());
+        assert_eq!(circles.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_register_validation() {
+        let recognizer = recognizer();
+        assert!(recognizer
+            .subscribe(GesturePattern::MultiClick {
+                button: MouseButton::Left,
+                count: 0
+            })
+            .is_err());
+        assert!(recognizer
+            .subscribe(GesturePattern::Strokes {
+                trigger: None,
+                directions: Vec::new()
+            })
+            .is_err());
+        let id = recognizer
+            .on(
+                GesturePattern::Circle {
+                    trigger: Some(MouseButton::Right),
+                    clockwise: None,
+                },
+                |_| {},
+            )
+            .unwrap();
+        recognizer.unregister(id).unwrap();
+        assert!(recognizer.unregister(id).is_err());
+    }
+}
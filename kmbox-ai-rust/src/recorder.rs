@@ -0,0 +1,753 @@
+//! 输入录制与回放模块
+//!
+//! [`Recorder`] 通过 [`InputMonitor`] 采样物理键鼠，把事件连同相对开始时刻的偏移保存为 [`Recording`]；
+//! [`Replayer`] 再按原有节奏通过 `mouse_move`、`mouse_button`、`mouse_wheel`、
+//! `key_down`/`key_up` 把录制内容发送给主机，支持调速、循环和热键启停。
+//!
+//! 设备只提供物理鼠标最近一次移动的值，每个 `move`/`wheel` 事件对应该值的一次变化（见 [`crate::monitor`]），
+//! 回放时每个事件只发送一次。
+//!
+//! ## 文件格式
+//!
+//! 录制文件为 UTF-8 编码的 JSON Lines，第一行是文件头，之后每行一个事件：
+//!
+//! ```text
+//! {"format":"kmbox-recording","version":1}
+//! {"t":0,"type":"button_down","button":"left"}
+//! {"t":12000,"type":"move","dx":3,"dy":-2}
+//! {"t":15000,"type":"wheel","delta":-1}
+//! {"t":20000,"type":"key_down","key":4}
+//! {"t":80000,"type":"key_up","key":4}
+//! {"t":95000,"type":"button_up","button":"left"}
+//! ```
+//!
+//! - `t`：相对录制开始的时间，单位微秒，不递减
+//! - `type`：`button_down`/`button_up`（`button` 为 `left`、`right`、`middle`、`side1`、`side2`），
+//!   `move`（相对位移 `dx`、`dy`），`wheel`（`delta`），`key_down`/`key_up`（`key` 为 HID 键值）
+//! - 空行会被忽略；版本号高于 [`FORMAT_VERSION`] 的文件拒绝读取
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use kmbox_ai_rust::backend::default_backend;
+//! use kmbox_ai_rust::monitor::MonitorConfig;
+//! use kmbox_ai_rust::recorder::{Recorder, Recording, Repeat, Replayer};
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let recorder = Recorder::start(default_backend(), MonitorConfig::default())?;
+//! std::thread::sleep(Duration::from_secs(5));
+//! recorder.stop().save("session.jsonl")?;
+//!
+//! let recording = Recording::load("session.jsonl")?;
+//! Replayer::new(default_backend(), recording)
+//!     .with_speed(2.0)?
+//!     .with_repeat(Repeat::Times(3))
+//!     .play()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::{check_result, KmboxError, KmboxResult, Operation};
+use crate::failsafe::HeldTracker;
+use crate::hotkey::{Hotkey, HotkeyId, HotkeyManager, HotkeyTrigger};
+use crate::kmbox_ai::MouseButton;
+use crate::monitor::{EventKind, InputEvent, InputMonitor, MonitorConfig};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 录制文件格式名称
+pub const FORMAT_NAME: &str = "kmbox-recording";
+
+/// 当前录制文件格式版本
+pub const FORMAT_VERSION: u32 = 1;
+
+/// 等待下一个事件时检查停止标志的间隔
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 录制的单个事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordedEvent {
+    /// 相对录制开始的时间
+    pub offset: Duration,
+    /// 事件类型
+    pub kind: EventKind,
+}
+
+/// 文件头
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+}
+
+/// 文件中的一行事件
+#[derive(Debug, Serialize, Deserialize)]
+struct EventLine {
+    t: u64,
+    #[serde(flatten)]
+    event: EventRepr,
+}
+
+/// 事件在文件中的表示
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum EventRepr {
+    ButtonDown { button: MouseButton },
+    ButtonUp { button: MouseButton },
+    Move { dx: i32, dy: i32 },
+    Wheel { delta: i32 },
+    KeyDown { key: i16 },
+    KeyUp { key: i16 },
+}
+
+impl From<EventKind> for EventRepr {
+    fn from(kind: EventKind) -> Self {
+        match kind {
+            EventKind::ButtonDown(button) => EventRepr::ButtonDown { button },
+            EventKind::ButtonUp(button) => EventRepr::ButtonUp { button },
+            EventKind::Moved { dx, dy } => EventRepr::Move { dx, dy },
+            EventKind::Wheel(delta) => EventRepr::Wheel { delta },
+            EventKind::KeyDown(key) => EventRepr::KeyDown { key },
+            EventKind::KeyUp(key) => EventRepr::KeyUp { key },
+        }
+    }
+}
+
+impl From<EventRepr> for EventKind {
+    fn from(repr: EventRepr) -> Self {
+        match repr {
+            EventRepr::ButtonDown { button } => EventKind::ButtonDown(button),
+            EventRepr::ButtonUp { button } => EventKind::ButtonUp(button),
+            EventRepr::Move { dx, dy } => EventKind::Moved { dx, dy },
+            EventRepr::Wheel { delta } => EventKind::Wheel(delta),
+            EventRepr::KeyDown { key } => EventKind::KeyDown(key),
+            EventRepr::KeyUp { key } => EventKind::KeyUp(key),
+        }
+    }
+}
+
+/// 一段录制内容
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recording {
+    events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    /// 使用事件列表创建录制内容，事件按时间排序
+    pub fn new(mut events: Vec<RecordedEvent>) -> Self {
+        events.sort_by_key(|event| event.offset);
+        Self { events }
+    }
+
+    /// 使用监听到的事件创建录制内容，`start` 为录制开始时刻
+    pub fn from_events(start: Instant, events: impl IntoIterator<Item = InputEvent>) -> Self {
+        Self::new(
+            events
+                .into_iter()
+                .map(|event| RecordedEvent {
+                    offset: event.timestamp.saturating_duration_since(start),
+                    kind: event.kind,
+                })
+                .collect(),
+        )
+    }
+
+    /// 所有事件
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// 是否没有任何事件
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// 最后一个事件的时间
+    pub fn duration(&self) -> Duration {
+        self.events
+            .last()
+            .map_or(Duration::ZERO, |event| event.offset)
+    }
+
+    /// 写出为 JSON Lines 格式
+    pub fn write_to<W: Write>(&self, mut writer: W) -> KmboxResult<()> {
+        let header = Header {
+            format: FORMAT_NAME.to_string(),
+            version: FORMAT_VERSION,
+        };
+        write_line(&mut writer, &header)?;
+        for event in &self.events {
+            let line = EventLine {
+                t: event.offset.as_micros() as u64,
+                event: event.kind.into(),
+            };
+            write_line(&mut writer, &line)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// 从 JSON Lines 格式读取
+    pub fn read_from<R: BufRead>(reader: R) -> KmboxResult<Self> {
+        let mut lines = reader
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line))
+            .filter(|(_, line)| !matches!(line, Ok(text) if text.trim().is_empty()));
+
+        let Some((number, line)) = lines.next() else {
+            return Err(KmboxError::ParameterError("录制文件为空".to_string()));
+        };
+        let header: Header = parse_line(number, &line?)?;
+        if header.format != FORMAT_NAME {
+            return Err(KmboxError::ParameterError(format!(
+                "第 {} 行: 不是录制文件: {}",
+                number, header.format
+            )));
+        }
+        if header.version > FORMAT_VERSION {
+            return Err(KmboxError::ParameterError(format!(
+                "第 {} 行: 不支持的录制文件版本: {}",
+                number, header.version
+            )));
+        }
+
+        let mut events = Vec::new();
+        let mut last = 0;
+        for (number, line) in lines {
+            let line: EventLine = parse_line(number, &line?)?;
+            if line.t < last {
+                return Err(KmboxError::ParameterError(format!(
+                    "第 {} 行: 事件时间不能早于上一个事件",
+                    number
+                )));
+            }
+            last = line.t;
+            events.push(RecordedEvent {
+                offset: Duration::from_micros(line.t),
+                kind: line.event.into(),
+            });
+        }
+        Ok(Self { events })
+    }
+
+    /// 保存到文件
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> KmboxResult<()> {
+        self.write_to(BufWriter::new(File::create(path)?))
+    }
+
+    /// 从文件加载
+    pub fn load<P: AsRef<Path>>(path: P) -> KmboxResult<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+fn write_line<W: Write, T: Serialize>(writer: &mut W, value: &T) -> KmboxResult<()> {
+    serde_json::to_writer(&mut *writer, value)
+        .map_err(|e| KmboxError::SystemError(format!("写入录制文件失败: {}", e)))?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+fn parse_line<T: for<'de> Deserialize<'de>>(number: usize, line: &str) -> KmboxResult<T> {
+    serde_json::from_str(line)
+        .map_err(|e| KmboxError::ParameterError(format!("第 {} 行: {}", number, e)))
+}
+
+/// 物理键鼠录制器
+///
+/// 创建后立即开始录制，调用 [`Recorder::stop`] 结束并取得录制内容。
+pub struct Recorder {
+    monitor: InputMonitor,
+    start: Instant,
+}
+
+impl Recorder {
+    /// 在指定后端上开始录制
+    pub fn start(backend: Arc<dyn Backend>, config: MonitorConfig) -> KmboxResult<Self> {
+        let start = Instant::now();
+        let monitor = InputMonitor::start(backend, config)?;
+        Ok(Self { monitor, start })
+    }
+
+    /// 停止录制并返回录制内容
+    pub fn stop(mut self) -> Recording {
+        self.monitor.stop();
+        let events: Vec<InputEvent> = std::iter::from_fn(|| self.monitor.try_recv()).collect();
+        Recording::from_events(self.start, events)
+    }
+}
+
+/// 回放次数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeat {
+    /// 回放指定次数
+    Times(u32),
+    /// 一直循环，直到被停止；录制内容的时长必须大于 0
+    Forever,
+}
+
+/// 录制内容回放器
+#[derive(Clone)]
+pub struct Replayer {
+    backend: Arc<dyn Backend>,
+    recording: Arc<Recording>,
+    speed: f64,
+    repeat: Repeat,
+}
+
+impl Replayer {
+    /// 在指定后端上回放录制内容，默认原速回放一次
+    pub fn new(backend: Arc<dyn Backend>, recording: Recording) -> Self {
+        Self {
+            backend,
+            recording: Arc::new(recording),
+            speed: 1.0,
+            repeat: Repeat::Times(1),
+        }
+    }
+
+    /// 设置回放速度倍率，2.0 表示两倍速
+    pub fn with_speed(mut self, speed: f64) -> KmboxResult<Self> {
+        if !speed.is_finite() || speed <= 0.0 {
+            return Err(KmboxError::ParameterError(format!(
+                "回放速度必须为正数: {}",
+                speed
+            )));
+        }
+        self.speed = speed;
+        Ok(self)
+    }
+
+    /// 设置回放次数
+    pub fn with_repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// 按当前速度计算每个事件相对本轮开始的发送时间
+    pub fn schedule(&self) -> Vec<(Duration, EventKind)> {
+        self.recording
+            .events()
+            .iter()
+            .map(|event| (event.offset.div_f64(self.speed), event.kind))
+            .collect()
+    }
+
+    /// 检查回放参数
+    ///
+    /// 所有事件都在同一时刻的录制内容无限循环时不会等待，回放线程会一直空转发送报告。
+    fn validate(&self) -> KmboxResult<()> {
+        if self.repeat == Repeat::Forever
+            && !self.recording.is_empty()
+            && self.recording.duration().is_zero()
+        {
+            return Err(KmboxError::ParameterError(
+                "时长为0的录制内容不能无限循环回放".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 在当前线程回放，完成后返回
+    pub fn play(&self) -> KmboxResult<()> {
+        self.validate()?;
+        self.run(&AtomicBool::new(false))
+    }
+
+    /// 在后台线程回放
+    pub fn spawn(&self) -> KmboxResult<Playback> {
+        self.validate()?;
+        let replayer = self.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        let handle = thread::Builder::new()
+            .name("kmbox-replay".to_string())
+            .spawn(move || replayer.run(&flag))
+            .map_err(|e| KmboxError::io("启动回放线程", e))?;
+        Ok(Playback {
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// 注册启停热键：按下时开始回放，回放过程中再次按下则停止
+    pub fn bind_hotkey(&self, manager: &HotkeyManager, hotkey: Hotkey) -> KmboxResult<HotkeyId> {
+        self.validate()?;
+        let replayer = self.clone();
+        let current: Mutex<Option<Playback>> = Mutex::new(None);
+        manager.on(hotkey, HotkeyTrigger::Press, move |_| {
+            let mut current = current.lock().unwrap_or_else(|e| e.into_inner());
+            match current.take() {
+                Some(mut playback) if !playback.is_finished() => playback.stop(),
+                _ => *current = replayer.spawn().ok(),
+            }
+        })
+    }
+
+    /// 回放直到完成或 `stop` 被设置，结束时释放仍处于按下状态的按键
+    fn run(&self, stop: &AtomicBool) -> KmboxResult<()> {
+        let schedule = self.schedule();
+        let held = HeldTracker::new(self.backend.clone());
+        let result = self.run_rounds(&schedule, &held, stop);
+        held.release_all();
+        result
+    }
+
+    fn run_rounds(
+        &self,
+        schedule: &[(Duration, EventKind)],
+        held: &HeldTracker,
+        stop: &AtomicBool,
+    ) -> KmboxResult<()> {
+        let mut round = 0;
+        while match self.repeat {
+            Repeat::Times(times) => round < times,
+            Repeat::Forever => !schedule.is_empty(),
+        } {
+            let start = Instant::now();
+            for &(at, kind) in schedule {
+                if !wait_until(start + at, stop) {
+                    return Ok(());
+                }
+                self.apply(kind, held)?;
+            }
+            round += 1;
+        }
+        Ok(())
+    }
+
+    /// 把一个事件发送给主机
+    fn apply(&self, kind: EventKind, held: &HeldTracker) -> KmboxResult<()> {
+        let backend = self.backend.as_ref();
+        match kind {
+            EventKind::ButtonDown(button) | EventKind::ButtonUp(button) => {
+                let down = matches!(kind, EventKind::ButtonDown(_));
+                check_result(backend.mouse_button(button, down), Operation::MouseButton)?;
+                held.set_button(button, down);
+            }
+            EventKind::Moved { dx, dy } => move_relative(backend, dx, dy)?,
+            EventKind::Wheel(delta) => {
+                check_result(backend.mouse_wheel(delta), Operation::MouseWheel)?
+            }
+            EventKind::KeyDown(key) => {
+                check_result(backend.key_down(key as i32), Operation::KeyDown)?;
+                held.set_key(key as i32, true);
+            }
+            EventKind::KeyUp(key) => {
+                check_result(backend.key_up(key as i32), Operation::KeyUp)?;
+                held.set_key(key as i32, false);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 相对移动，超出 `i16` 范围的位移拆分为多次发送
+fn move_relative(backend: &dyn Backend, mut dx: i32, mut dy: i32) -> KmboxResult<()> {
+    loop {
+        let step_x = dx.clamp(i16::MIN as i32, i16::MAX as i32);
+        let step_y = dy.clamp(i16::MIN as i32, i16::MAX as i32);
+        let result = backend.mouse_move(step_x as i16, step_y as i16);
+        check_result(result, Operation::MouseMove)?;
+        dx -= step_x;
+        dy -= step_y;
+        if dx == 0 && dy == 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// 等待到指定时刻，期间被停止时返回 `false`
+pub(crate) fn wait_until(deadline: Instant, stop: &AtomicBool) -> bool {
+    loop {
+        if stop.load(Ordering::Acquire) {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        thread::sleep((deadline - now).min(STOP_POLL_INTERVAL));
+    }
+}
+
+/// 后台回放句柄，销毁时停止回放
+pub struct Playback {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<KmboxResult<()>>>,
+}
+
+impl Playback {
+    /// 回放是否已经结束
+    pub fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// 停止回放并等待回放线程退出
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+
+    /// 等待回放结束并返回回放结果
+    pub fn wait(mut self) -> KmboxResult<()> {
+        match self.handle.take() {
+            Some(handle) => handle
+                .join()
+                .map_err(|_| KmboxError::SystemError("回放线程异常退出".to_string()))?,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Playback {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
+
+    fn event(millis: u64, kind: EventKind) -> RecordedEvent {
+        RecordedEvent {
+            offset: Duration::from_millis(millis),
+            kind,
+        }
+    }
+
+    fn sample() -> Recording {
+        Recording::new(vec![
+            event(0, EventKind::ButtonDown(MouseButton::Left)),
+            event(2, EventKind::Moved { dx: 3, dy: -2 }),
+            event(3, EventKind::Wheel(-1)),
+            event(4, EventKind::KeyDown(4)),
+            event(6, EventKind::KeyUp(4)),
+            event(8, EventKind::ButtonUp(MouseButton::Side1)),
+        ])
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let recording = sample();
+        let mut buffer = Vec::new();
+        recording.write_to(&mut buffer).unwrap();
+
+        let text = String::from_utf8(buffer.clone()).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(
+            lines.next(),
+            Some(r#"{"format":"kmbox-recording","version":1}"#)
+        );
+        assert_eq!(
+            lines.next(),
+            Some(r#"{"t":0,"type":"button_down","button":"left"}"#)
+        );
+
+        assert_eq!(Recording::read_from(&buffer[..]).unwrap(), recording);
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        let error = |text: &str| {
+            Recording::read_from(text.as_bytes())
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(error("").contains("为空"));
+        assert!(error(r#"{"format":"kmbox-recording","version":2}"#).contains("版本"));
+        let text = "{\"format\":\"kmbox-recording\",\"version\":1}\n\n{\"t\":1,\"type\":\"jump\"}";
+        assert!(error(text).contains("第 3 行"));
+        let text = "{\"format\":\"kmbox-recording\",\"version\":1}\n\
+                    {\"t\":5,\"type\":\"wheel\",\"delta\":1}\n\
+                    {\"t\":4,\"type\":\"wheel\",\"delta\":1}";
+        assert!(error(text).contains("第 3 行"));
+    }
+
+    #[test]
+    fn test_schedule_speed_and_repeat() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let replayer = Replayer::new(sim.clone(), sample())
+            .with_speed(4.0)
+            .unwrap()
+            .with_repeat(Repeat::Times(2));
+        assert_eq!(replayer.schedule()[4].0, Duration::from_micros(1500));
+        assert!(Replayer::new(sim.clone(), sample())
+            .with_speed(0.0)
+            .is_err());
+
+        replayer.play().unwrap();
+        let reports = sim.reports();
+        assert_eq!(reports[..6], reports[6..12]);
+        assert_eq!(reports[1], HidReport::Move { x: 3, y: -2 });
+        // 录制中没有松开的左键在回放结束时被释放
+        assert_eq!(
+            reports[12..],
+            [HidReport::Button {
+                button: MouseButton::Left,
+                down: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_record_and_replay_round_trip() {
+        let source = Arc::new(SimulatedBackend::new());
+        let config = MonitorConfig::default()
+            .with_interval(Duration::from_millis(1))
+            .with_keys([4]);
+        let recorder = Recorder::start(source.clone(), config).unwrap();
+        let settle = || thread::sleep(Duration::from_millis(20));
+        source.press_physical_button(MouseButton::Right);
+        settle();
+        source.press_physical_key(4);
+        settle();
+        source.release_physical_key(4);
+        settle();
+        source.release_physical_button(MouseButton::Right);
+        settle();
+        let recording = recorder.stop();
+
+        let kinds: Vec<EventKind> = recording.events().iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                EventKind::ButtonDown(MouseButton::Right),
+                EventKind::KeyDown(4),
+                EventKind::KeyUp(4),
+                EventKind::ButtonUp(MouseButton::Right),
+            ]
+        );
+
+        let target = Arc::new(SimulatedBackend::new());
+        Replayer::new(target.clone(), recording)
+            .with_speed(10.0)
+            .unwrap()
+            .play()
+            .unwrap();
+        assert_eq!(
+            target.reports(),
+            vec![
+                HidReport::Button {
+                    button: MouseButton::Right,
+                    down: true
+                },
+                HidReport::KeyDown(4),
+                HidReport::KeyUp(4),
+                HidReport::Button {
+                    button: MouseButton::Right,
+                    down: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_record_and_replay_motion() {
+        let source = Arc::new(SimulatedBackend::new());
+        // 录制开始前的最近一次移动不会被录制
+        source.move_physical(100, 100);
+        let config = MonitorConfig::default()
+            .with_interval(Duration::from_millis(1))
+            .with_keys([]);
+        let recorder = Recorder::start(source.clone(), config).unwrap();
+        let settle = || thread::sleep(Duration::from_millis(20));
+        settle();
+        source.move_physical(3, -2);
+        settle();
+        source.scroll_physical(-1);
+        settle();
+        source.move_physical(10, 0);
+        settle();
+        let recording = recorder.stop();
+
+        // 设备在两次移动之间一直返回最近一次的值，每次移动只录制一次
+        let kinds: Vec<EventKind> = recording.events().iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                EventKind::Moved { dx: 3, dy: -2 },
+                EventKind::Wheel(-1),
+                EventKind::Moved { dx: 10, dy: 0 },
+            ]
+        );
+
+        let target = Arc::new(SimulatedBackend::new());
+        Replayer::new(target.clone(), recording)
+            .with_speed(10.0)
+            .unwrap()
+            .play()
+            .unwrap();
+        assert_eq!(
+            target.reports(),
+            vec![
+                HidReport::Move { x: 3, y: -2 },
+                HidReport::Wheel(-1),
+                HidReport::Move { x: 10, y: 0 },
+            ]
+        );
+        let state = target.state();
+        assert_eq!((state.cursor.x, state.cursor.y, state.wheel), (13, -2, -1));
+    }
+
+    #[test]
+    fn test_forever_requires_nonzero_duration() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let instant = Recording::new(vec![
+            event(0, EventKind::KeyDown(4)),
+            event(0, EventKind::KeyUp(4)),
+        ]);
+        let replayer = Replayer::new(sim.clone(), instant).with_repeat(Repeat::Forever);
+        assert!(matches!(
+            replayer.play(),
+            Err(KmboxError::ParameterError(_))
+        ));
+        assert!(replayer.spawn().is_err());
+        assert!(sim.reports().is_empty());
+
+        // 回放一次或空录制不受影响
+        let replayer = replayer.with_repeat(Repeat::Times(1));
+        replayer.play().unwrap();
+        assert_eq!(sim.reports().len(), 2);
+        Replayer::new(sim.clone(), Recording::new(Vec::new()))
+            .with_repeat(Repeat::Forever)
+            .play()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_stopped_playback_releases_held_inputs() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let recording = Recording::new(vec![
+            event(0, EventKind::KeyDown(4)),
+            event(60_000, EventKind::KeyUp(4)),
+        ]);
+        let mut playback = Replayer::new(sim.clone(), recording)
+            .with_repeat(Repeat::Forever)
+            .spawn()
+            .unwrap();
+        while sim.reports().is_empty() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        playback.stop();
+        assert!(playback.is_finished());
+        assert_eq!(
+            sim.reports(),
+            vec![HidReport::KeyDown(4), HidReport::KeyUp(4)]
+        );
+    }
+}
@@ -0,0 +1,761 @@
+//! HID 报告模块
+//!
+//! 鼠标综合报告对应头文件中的 `kmAI_mouse_all(int button, int x, int y, int wheel)`：
+//! [`MouseButtons`] 是按键位掩码，[`MouseReport`] 把按键、位移和滚轮组合成一个报告一次发出。
+//! 按下按键和移动在同一个报告中发送，主机不会先看到按下再看到移动，适合拖拽操作。
+//!
+//! 键盘启动报告（boot report）共 8 字节：修饰键位掩码 [`Modifiers`]、保留字节和 6 个按键槽。
+//! [`KeyboardReport`] 描述某一时刻按下的全部按键，两个报告之间的差异即为需要发送的按下和释放。
+//! 厂商库只能逐个发送按键事件，键盘报告由 [`Keyboard::send_report`](crate::keyboard::Keyboard::send_report)
+//! 拆成一串按下和释放依次发送（修饰键先按后放），不像鼠标报告那样一次发出。
+//! 同时按下超过 6 个普通按键时，6 个按键槽都填入 `ERRORROLLOVER`，与真实键盘一致；
+//! 报告本身仍记录全部按下的按键，释放和比较不受溢出影响。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::kmbox_ai::{KmboxAI, MouseButton};
+//! use kmbox_ai_rust::keyboard::Key;
+//! use kmbox_ai_rust::report::{KeyboardReport, MouseButtons, MouseReport};
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let kmbox = KmboxAI::new()?;
+//!
+//! // 按住左键的同时移动，然后松开
+//! kmbox.send_mouse_report(&MouseReport::new().with_button(MouseButton::Left).with_move(40, 0))?;
+//! kmbox.send_mouse_report(&MouseReport::new())?;
+//!
+//! let buttons = MouseButtons::LEFT | MouseButtons::SIDE1;
+//! assert!(buttons.contains(MouseButton::Side1));
+//!
+//! // Ctrl+Alt+Del 的启动报告
+//! let report = KeyboardReport::from_keys(&[Key::LeftControl, Key::LeftAlt, Key::Delete])?;
+//! assert_eq!(report.to_bytes(), [0x05, 0, 0x4c, 0, 0, 0, 0, 0]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{KmboxError, KmboxResult};
+use crate::keyboard::{constants, Key};
+use crate::kmbox_ai::{MouseButton, MousePosition, MouseState};
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitOrAssign, Not, Sub};
+
+/// 鼠标按键位掩码
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MouseButtons(u8);
+
+impl MouseButtons {
+    /// 没有按键
+    pub const NONE: MouseButtons = MouseButtons(0);
+    /// 左键
+    pub const LEFT: MouseButtons = MouseButtons(1);
+    /// 右键
+    pub const RIGHT: MouseButtons = MouseButtons(1 << 1);
+    /// 中键
+    pub const MIDDLE: MouseButtons = MouseButtons(1 << 2);
+    /// 侧键1
+    pub const SIDE1: MouseButtons = MouseButtons(1 << 3);
+    /// 侧键2
+    pub const SIDE2: MouseButtons = MouseButtons(1 << 4);
+    /// 所有按键
+    pub const ALL: MouseButtons = MouseButtons(0b1_1111);
+
+    /// 按键位掩码，与 `kmAI_mouse_all` 的 `button` 参数一致
+    pub fn bits(self) -> i32 {
+        self.0 as i32
+    }
+
+    /// 从位掩码创建，包含未定义的位时返回 `None`
+    pub fn from_bits(bits: i32) -> Option<Self> {
+        if bits & !Self::ALL.bits() == 0 {
+            Some(Self(bits as u8))
+        } else {
+            None
+        }
+    }
+
+    /// 从位掩码创建，忽略未定义的位
+    pub fn from_bits_truncate(bits: i32) -> Self {
+        Self((bits & Self::ALL.bits()) as u8)
+    }
+
+    /// 是否没有按键
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// 是否包含指定按键
+    pub fn contains(self, button: MouseButton) -> bool {
+        self.0 & Self::from(button).0 != 0
+    }
+
+    /// 加入按键
+    pub fn insert(&mut self, button: MouseButton) {
+        self.0 |= Self::from(button).0;
+    }
+
+    /// 移除按键
+    pub fn remove(&mut self, button: MouseButton) {
+        self.0 &= !Self::from(button).0;
+    }
+
+    /// 设置按键是否按下
+    pub fn set(&mut self, button: MouseButton, down: bool) {
+        if down {
+            self.insert(button);
+        } else {
+            self.remove(button);
+        }
+    }
+
+    /// 依次列出包含的按键
+    pub fn iter(self) -> impl Iterator<Item = MouseButton> {
+        MouseButton::ALL
+            .into_iter()
+            .filter(move |&button| self.contains(button))
+    }
+}
+
+impl From<MouseButton> for MouseButtons {
+    fn from(button: MouseButton) -> Self {
+        Self(button.mask_bit() as u8)
+    }
+}
+
+impl FromIterator<MouseButton> for MouseButtons {
+    fn from_iter<I: IntoIterator<Item = MouseButton>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::NONE, |buttons, button| buttons | button.into())
+    }
+}
+
+impl BitOr for MouseButtons {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for MouseButtons {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for MouseButtons {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl Sub for MouseButtons {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 & !rhs.0)
+    }
+}
+
+impl Not for MouseButtons {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0 & Self::ALL.0)
+    }
+}
+
+impl fmt::Debug for MouseButtons {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// 鼠标综合报告
+///
+/// `buttons` 是发送后保持按下的按键集合，不在集合中的按键会被释放。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MouseReport {
+    /// 按下的按键
+    pub buttons: MouseButtons,
+    /// X 方向相对位移
+    pub x: i32,
+    /// Y 方向相对位移
+    pub y: i32,
+    /// 滚轮
+    pub wheel: i32,
+}
+
+impl MouseReport {
+    /// 创建空报告：不按任何按键、不移动
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置按下的按键集合
+    pub fn with_buttons(mut self, buttons: MouseButtons) -> Self {
+        self.buttons = buttons;
+        self
+    }
+
+    /// 增加一个按下的按键
+    pub fn with_button(mut self, button: MouseButton) -> Self {
+        self.buttons.insert(button);
+        self
+    }
+
+    /// 设置相对位移
+    pub fn with_move(mut self, x: i32, y: i32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// 设置滚轮
+    pub fn with_wheel(mut self, wheel: i32) -> Self {
+        self.wheel = wheel;
+        self
+    }
+
+    /// `kmAI_mouse_all` 的四个参数：按键位掩码、X、Y、滚轮
+    pub fn to_args(&self) -> (i32, i32, i32, i32) {
+        (self.buttons.bits(), self.x, self.y, self.wheel)
+    }
+}
+
+impl MouseState {
+    /// 按下的按键集合
+    pub fn buttons(&self) -> MouseButtons {
+        MouseButton::ALL
+            .into_iter()
+            .filter(|&button| self.is_pressed(button))
+            .collect()
+    }
+}
+
+impl From<&MouseState> for MouseReport {
+    /// 以鼠标状态的按键、位置和滚轮生成报告
+    fn from(state: &MouseState) -> Self {
+        Self {
+            buttons: state.buttons(),
+            x: state.position.x,
+            y: state.position.y,
+            wheel: state.wheel,
+        }
+    }
+}
+
+impl From<MouseState> for MouseReport {
+    fn from(state: MouseState) -> Self {
+        Self::from(&state)
+    }
+}
+
+impl From<MouseReport> for MouseState {
+    fn from(report: MouseReport) -> Self {
+        let buttons = report.buttons;
+        Self {
+            position: MousePosition {
+                x: report.x,
+                y: report.y,
+            },
+            left: buttons.contains(MouseButton::Left),
+            right: buttons.contains(MouseButton::Right),
+            middle: buttons.contains(MouseButton::Middle),
+            side1: buttons.contains(MouseButton::Side1),
+            side2: buttons.contains(MouseButton::Side2),
+            wheel: report.wheel,
+        }
+    }
+}
+
+/// 修饰键按位排列：左 Ctrl、Shift、Alt、GUI，右 Ctrl、Shift、Alt、GUI
+const MODIFIER_KEYS: [Key; 8] = [
+    Key::LeftControl,
+    Key::LeftShift,
+    Key::LeftAlt,
+    Key::LeftGui,
+    Key::RightControl,
+    Key::RightShift,
+    Key::RightAlt,
+    Key::RightGui,
+];
+
+/// 键盘修饰键位掩码，即启动报告的第一个字节
+///
+/// 第 n 位对应键值 `KEY_LEFTCONTROL + n`（0xE0..=0xE7）。
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    /// 没有修饰键
+    pub const NONE: Modifiers = Modifiers(0);
+    /// 左 Ctrl
+    pub const LEFT_CTRL: Modifiers = Modifiers(1);
+    /// 左 Shift
+    pub const LEFT_SHIFT: Modifiers = Modifiers(1 << 1);
+    /// 左 Alt
+    pub const LEFT_ALT: Modifiers = Modifiers(1 << 2);
+    /// 左 GUI（Win/Command）
+    pub const LEFT_GUI: Modifiers = Modifiers(1 << 3);
+    /// 右 Ctrl
+    pub const RIGHT_CTRL: Modifiers = Modifiers(1 << 4);
+    /// 右 Shift
+    pub const RIGHT_SHIFT: Modifiers = Modifiers(1 << 5);
+    /// 右 Alt
+    pub const RIGHT_ALT: Modifiers = Modifiers(1 << 6);
+    /// 右 GUI
+    pub const RIGHT_GUI: Modifiers = Modifiers(1 << 7);
+
+    /// 修饰键位掩码
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// 从位掩码创建
+    pub fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// 按键对应的修饰键，不是修饰键时返回 `None`
+    pub fn from_key(key: &Key) -> Option<Self> {
+        let usage = key.hex_value();
+        if (constants::KEY_LEFTCONTROL..=constants::KEY_RIGHT_GUI).contains(&usage)
+            && !matches!(key, Key::Custom(_))
+        {
+            Some(Self(1 << (usage - constants::KEY_LEFTCONTROL)))
+        } else {
+            None
+        }
+    }
+
+    /// 是否没有修饰键
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// 是否包含 `other` 中的所有修饰键
+    pub fn contains(self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// 依次列出包含的修饰键
+    pub fn keys(self) -> impl Iterator<Item = Key> {
+        MODIFIER_KEYS
+            .into_iter()
+            .enumerate()
+            .filter(move |(bit, _)| self.0 & (1 << bit) != 0)
+            .map(|(_, key)| key)
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for Modifiers {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl Sub for Modifiers {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 & !rhs.0)
+    }
+}
+
+impl Not for Modifiers {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+impl fmt::Debug for Modifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.keys().map(|key| key.string_name().to_string()))
+            .finish()
+    }
+}
+
+/// 启动报告的按键槽数
+pub const BOOT_REPORT_KEYS: usize = 6;
+
+const ROLLOVER: u8 = constants::KEY_ERRORROLLOVER as u8;
+
+/// 可以放入按键槽的键值，保留键值和自定义键返回错误
+fn slot_usage(key: &Key) -> KmboxResult<u8> {
+    let usage = key.hex_value();
+    if matches!(key, Key::Custom(_)) || usage <= constants::KEY_ERRORUNDEFINED || usage > 0xff {
+        return Err(KmboxError::ParameterError(format!(
+            "按键无法放入启动报告: {}",
+            key.string_name()
+        )));
+    }
+    Ok(usage as u8)
+}
+
+/// 键盘启动报告
+///
+/// 报告记录实际按下的普通按键，按按下顺序排列，可以超过 6 个；
+/// 8 字节报告中的按键槽由它生成，超过 6 个时为溢出报告。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct KeyboardReport {
+    /// 修饰键
+    pub modifiers: Modifiers,
+    /// 按下的普通按键
+    held: Vec<u8>,
+    /// 由溢出的 8 字节报告解析而来，不知道按下了哪些普通按键
+    unknown_rollover: bool,
+}
+/// 两个键盘报告之间的差异
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyboardReportDiff {
+    /// 需要释放的按键，普通按键在前、修饰键在后
+    pub released: Vec<Key>,
+    /// 需要按下的按键，修饰键在前、普通按键在后
+    pub pressed: Vec<Key>,
+}
+
+impl KeyboardReportDiff {
+    /// 两个报告是否相同
+    pub fn is_empty(&self) -> bool {
+        self.released.is_empty() && self.pressed.is_empty()
+    }
+}
+
+impl KeyboardReport {
+    /// 创建空报告：没有按下任何按键
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 由同时按下的按键生成报告，普通按键超过 6 个时为溢出报告
+    pub fn from_keys<'a>(keys: impl IntoIterator<Item = &'a Key>) -> KmboxResult<Self> {
+        let mut report = Self::new();
+        for key in keys {
+            report.press(key)?;
+        }
+        Ok(report)
+    }
+
+    /// 从 8 字节报告解析
+    ///
+    /// 溢出报告不包含按下的普通按键，解析结果只有修饰键，[`is_rollover`](Self::is_rollover) 为 `true`。
+    pub fn from_bytes(bytes: [u8; 8]) -> Self {
+        let slots = &bytes[2..];
+        let unknown_rollover = slots.iter().all(|&usage| usage == ROLLOVER);
+        let held = if unknown_rollover {
+            Vec::new()
+        } else {
+            slots.iter().copied().filter(|&usage| usage != 0).collect()
+        };
+        Self {
+            modifiers: Modifiers::from_bits(bytes[0]),
+            held,
+            unknown_rollover,
+        }
+    }
+
+    /// 8 字节报告：修饰键、保留字节、6 个按键槽
+    pub fn to_bytes(&self) -> [u8; 8] {
+        let mut bytes = [0; 8];
+        bytes[0] = self.modifiers.bits();
+        bytes[2..].copy_from_slice(&self.keys());
+        bytes
+    }
+
+    /// 6 个按键槽：按下顺序排列，空槽为 0；溢出时都为 `ERRORROLLOVER`
+    pub fn keys(&self) -> [u8; BOOT_REPORT_KEYS] {
+        let mut keys = [0; BOOT_REPORT_KEYS];
+        if self.is_rollover() {
+            keys = [ROLLOVER; BOOT_REPORT_KEYS];
+        } else {
+            keys[..self.held.len()].copy_from_slice(&self.held);
+        }
+        keys
+    }
+
+    /// 设置修饰键
+    pub fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// 增加一个按下的按键
+    pub fn with_key(mut self, key: &Key) -> KmboxResult<Self> {
+        self.press(key)?;
+        Ok(self)
+    }
+
+    /// 是否处于溢出状态（同时按下的普通按键超过 6 个）
+    pub fn is_rollover(&self) -> bool {
+        self.unknown_rollover || self.held.len() > BOOT_REPORT_KEYS
+    }
+
+    /// 从溢出的 8 字节报告解析而来，不知道按下了哪些普通按键
+    pub fn is_unknown_rollover(&self) -> bool {
+        self.unknown_rollover
+    }
+
+    /// 是否没有按下任何按键
+    pub fn is_empty(&self) -> bool {
+        self.modifiers.is_empty() && self.held.is_empty() && !self.unknown_rollover
+    }
+
+    /// 是否包含指定按键，溢出状态下仍按实际按下的按键判断
+    pub fn contains(&self, key: &Key) -> bool {
+        match Modifiers::from_key(key) {
+            Some(modifier) => self.modifiers.contains(modifier),
+            None => slot_usage(key).is_ok_and(|usage| self.held.contains(&usage)),
+        }
+    }
+
+    /// 按下按键
+    ///
+    /// 同时按下的普通按键超过 6 个时报告进入溢出状态，返回 `Ok(false)`；
+    /// 按键仍会记录，释放到 6 个以内后恢复正常报告。
+    pub fn press(&mut self, key: &Key) -> KmboxResult<bool> {
+        if let Some(modifier) = Modifiers::from_key(key) {
+            self.modifiers |= modifier;
+            return Ok(true);
+        }
+        let usage = slot_usage(key)?;
+        if !self.held.contains(&usage) {
+            self.held.push(usage);
+        }
+        Ok(!self.is_rollover())
+    }
+
+    /// 释放按键，后面的按键依次前移
+    pub fn release(&mut self, key: &Key) {
+        if let Some(modifier) = Modifiers::from_key(key) {
+            self.modifiers = self.modifiers - modifier;
+            return;
+        }
+        if let Ok(usage) = slot_usage(key) {
+            self.held.retain(|&held| held != usage);
+        }
+    }
+
+    /// 按下的按键：修饰键在前，普通按键按按下顺序，溢出状态下也包含全部普通按键
+    ///
+    /// 从溢出的 8 字节报告解析时只有修饰键。
+    pub fn pressed(&self) -> Vec<Key> {
+        let mut keys: Vec<Key> = self.modifiers.keys().collect();
+        keys.extend(
+            self.held
+                .iter()
+                .filter_map(|&usage| Key::try_from(usage).ok()),
+        );
+        keys
+    }
+
+    /// 从本报告切换到 `next` 需要释放和按下的按键
+    ///
+    /// 按实际按下的按键比较；`next` 由溢出的 8 字节报告解析而来时与主机的处理一致，
+    /// 普通按键保持不变，只比较修饰键。
+    pub fn diff(&self, next: &KeyboardReport) -> KeyboardReportDiff {
+        let next = if next.unknown_rollover {
+            KeyboardReport {
+                modifiers: next.modifiers,
+                ..self.clone()
+            }
+        } else {
+            next.clone()
+        };
+        let current = self.pressed();
+        let target = next.pressed();
+
+        let mut released: Vec<Key> = current
+            .iter()
+            .filter(|key| !target.contains(key))
+            .cloned()
+            .collect();
+        // 先释放普通按键，再释放修饰键
+        released.sort_by_key(|key| Modifiers::from_key(key).is_some());
+        let pressed = target
+            .iter()
+            .filter(|key| !current.contains(key))
+            .cloned()
+            .collect();
+        KeyboardReportDiff { released, pressed }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
+    use crate::kmbox_ai::KmboxAI;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_button_set_operations() {
+        let mut buttons = MouseButtons::LEFT | MouseButtons::MIDDLE;
+        assert_eq!(buttons.bits(), 0b101);
+        assert!(buttons.contains(MouseButton::Middle));
+        assert!(!buttons.contains(MouseButton::Right));
+
+        buttons.set(MouseButton::Side2, true);
+        buttons.remove(MouseButton::Left);
+        assert_eq!(
+            buttons.iter().collect::<Vec<_>>(),
+            [MouseButton::Middle, MouseButton::Side2]
+        );
+        assert_eq!(
+            !buttons,
+            MouseButtons::LEFT | MouseButtons::RIGHT | MouseButtons::SIDE1
+        );
+        assert_eq!(buttons - MouseButtons::MIDDLE, MouseButtons::SIDE2);
+        assert_eq!(format!("{:?}", buttons), "{Middle, Side2}");
+
+        assert_eq!(MouseButtons::from_bits(0b1_1111), Some(MouseButtons::ALL));
+        assert_eq!(MouseButtons::from_bits(1 << 5), None);
+        assert_eq!(
+            MouseButtons::from_bits_truncate(0b10_0010),
+            MouseButtons::RIGHT
+        );
+    }
+
+    #[test]
+    fn test_state_conversions() {
+        let report = MouseReport::new()
+            .with_buttons(MouseButtons::RIGHT | MouseButtons::SIDE1)
+            .with_move(3, -4)
+            .with_wheel(1);
+        assert_eq!(report.to_args(), (0b1010, 3, -4, 1));
+
+        let state = MouseState::from(report);
+        assert!(state.right && state.side1 && !state.left);
+        assert_eq!(state.position, MousePosition { x: 3, y: -4 });
+        assert_eq!(state.buttons(), report.buttons);
+        assert_eq!(MouseReport::from(&state), report);
+    }
+
+    #[test]
+    fn test_send_report_is_atomic() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+
+        let drag = MouseReport::new()
+            .with_button(MouseButton::Left)
+            .with_move(10, 5);
+        kmbox.send_mouse_report(&drag).unwrap();
+        assert_eq!(
+            sim.reports(),
+            [HidReport::MouseAll {
+                buttons: 1,
+                x: 10,
+                y: 5,
+                wheel: 0
+            }]
+        );
+        assert_eq!(kmbox.held_inputs().buttons.len(), 1);
+
+        kmbox.send_mouse_report(&MouseReport::new()).unwrap();
+        assert!(kmbox.held_inputs().is_empty());
+        assert!(sim.state().mouse_buttons.is_empty());
+    }
+
+    #[test]
+    fn test_keyboard_report_bytes() {
+        let report =
+            KeyboardReport::from_keys(&[Key::LeftControl, Key::LeftAlt, Key::Delete]).unwrap();
+        assert_eq!(report.modifiers, Modifiers::LEFT_CTRL | Modifiers::LEFT_ALT);
+        assert_eq!(report.to_bytes(), [0x05, 0, 0x4c, 0, 0, 0, 0, 0]);
+        assert_eq!(KeyboardReport::from_bytes(report.to_bytes()), report);
+        assert_eq!(
+            report.pressed(),
+            [Key::LeftControl, Key::LeftAlt, Key::Delete]
+        );
+        assert_eq!(
+            format!("{:?}", report.modifiers),
+            r#"{"LEFTCTRL", "LEFTALT"}"#
+        );
+        assert_eq!(
+            Modifiers::from_key(&Key::RightGui),
+            Some(Modifiers::RIGHT_GUI)
+        );
+        assert_eq!(Modifiers::from_key(&Key::A), None);
+        assert!(KeyboardReport::new().with_key(&Key::None).is_err());
+        assert!(KeyboardReport::new()
+            .with_key(&Key::Custom("X".to_string()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_keyboard_report_rollover() {
+        let keys = [Key::A, Key::B, Key::C, Key::D, Key::E, Key::F];
+        let mut report = KeyboardReport::from_keys(&keys).unwrap();
+        assert!(!report.is_rollover());
+        report.release(&Key::B);
+        assert_eq!(report.keys(), [4, 6, 7, 8, 9, 0]);
+        assert!(report.press(&Key::B).unwrap());
+
+        assert!(!report.press(&Key::G).unwrap());
+        assert!(report.is_rollover());
+        assert_eq!(report.keys(), [1; BOOT_REPORT_KEYS]);
+        assert!(report.press(&Key::LeftShift).unwrap());
+        // 溢出状态下仍记录实际按下的按键
+        assert_eq!(report.pressed().len(), 8);
+        assert!(report.contains(&Key::A));
+
+        // 释放到 6 个以内后恢复正常报告
+        report.release(&Key::A);
+        assert!(!report.is_rollover());
+        assert_eq!(report.keys(), [6, 7, 8, 9, 5, 10]);
+
+        // 溢出的 8 字节报告不知道按下了哪些普通按键
+        let parsed = KeyboardReport::from_bytes([0x02, 0, 1, 1, 1, 1, 1, 1]);
+        assert!(parsed.is_rollover() && parsed.is_unknown_rollover());
+        assert_eq!(parsed.pressed(), [Key::LeftShift]);
+        assert_eq!(parsed.to_bytes(), [0x02, 0, 1, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_keyboard_report_diff() {
+        let before = KeyboardReport::from_keys(&[Key::LeftShift, Key::A, Key::B]).unwrap();
+        let after = KeyboardReport::from_keys(&[Key::LeftControl, Key::B, Key::C]).unwrap();
+        let diff = before.diff(&after);
+        assert_eq!(diff.released, [Key::A, Key::LeftShift]);
+        assert_eq!(diff.pressed, [Key::LeftControl, Key::C]);
+        assert!(after.diff(&after).is_empty());
+
+        // 溢出报告按实际按下的按键比较
+        let keys = [Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G];
+        let rollover = KeyboardReport::from_keys(&keys).unwrap();
+        let diff = before.diff(&rollover);
+        assert_eq!(diff.released, [Key::LeftShift]);
+        assert_eq!(diff.pressed, [Key::C, Key::D, Key::E, Key::F, Key::G]);
+        let diff = rollover.diff(&KeyboardReport::new());
+        assert_eq!(diff.released, keys);
+        assert!(diff.pressed.is_empty());
+
+        // 按键未知的溢出报告只改变修饰键
+        let parsed = KeyboardReport::from_bytes(rollover.to_bytes());
+        let diff = before.diff(&parsed);
+        assert_eq!(diff.released, [Key::LeftShift]);
+        assert!(diff.pressed.is_empty());
+    }
+}
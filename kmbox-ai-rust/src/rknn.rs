@@ -3,232 +3,12 @@
 //! 提供基于RKNN（Rockchip Neural Network）的神经网络推理功能
 //! 完整封装了kmbox_rknn_api.h.rs中的所有RKNN相关函数
 
-use crate::error::{check_result, KmboxError, KmboxResult};
+use crate::backend::{default_backend, Backend};
+use crate::error::{check_result, KmboxError, KmboxResult, Operation};
+use crate::ffi::rknn::{self, rknn_context, rknn_input, rknn_input_output_num, rknn_output};
 use std::ffi::CString;
-
-// 导入RKNN相关的C函数
-unsafe extern "C" {
-    // RKNN上下文管理
-    #[link_name = "\u{1}_Z8rknn_initPKcPvPj"]
-    fn rknn_init(
-        model_path: *const std::os::raw::c_char,
-        context: *mut *mut std::os::raw::c_void,
-        size: *mut std::os::raw::c_uint,
-    ) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z15rknn_dup_contextPv"]
-    #[allow(dead_code)]
-    fn rknn_dup_context(context: *mut std::os::raw::c_void) -> *mut std::os::raw::c_void;
-
-    #[link_name = "\u{1}_Z13rknn_destroyPv"]
-    fn rknn_destroy(context: *mut std::os::raw::c_void) -> std::os::raw::c_int;
-
-    // RKNN查询和配置
-    #[link_name = "\u{1}_Z9rknn_queryPvjPv"]
-    #[allow(dead_code)]
-    fn rknn_query(
-        context: *mut std::os::raw::c_void,
-        query_type: std::os::raw::c_uint,
-        info: *mut std::os::raw::c_void,
-    ) -> std::os::raw::c_int;
-
-    // RKNN输入设置
-    #[link_name = "\u{1}_Z14rknn_inputs_setPvP15rknn_inputs"]
-    fn rknn_inputs_set(
-        context: *mut std::os::raw::c_void,
-        inputs: *const RknnInputsT,
-    ) -> std::os::raw::c_int;
-
-    // RKNN运行控制
-    #[link_name = "\u{1}_Z7rknn_runPvP18rknn_run_extend"]
-    fn rknn_run(
-        context: *mut std::os::raw::c_void,
-        extend: *mut RknnRunExtendT,
-    ) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z9rknn_waitPvP18rknn_run_extend"]
-    fn rknn_wait(
-        context: *mut std::os::raw::c_void,
-        extend: *mut RknnRunExtendT,
-    ) -> std::os::raw::c_int;
-
-    // RKNN输出获取
-    #[link_name = "\u{1}_Z16rknn_outputs_getPvP16rknn_outputs"]
-    fn rknn_outputs_get(
-        context: *mut std::os::raw::c_void,
-        outputs: *mut RknnOutputsT,
-    ) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z19rknn_outputs_releasePvP16rknn_outputs"]
-    fn rknn_outputs_release(
-        context: *mut std::os::raw::c_void,
-        outputs: *mut RknnOutputsT,
-    ) -> std::os::raw::c_int;
-
-    // RKNN内存管理
-    #[link_name = "\u{1}_Z20rknn_create_mem_from_physPvjPv"]
-    #[allow(dead_code)]
-    fn rknn_create_mem_from_phys(
-        context: *mut std::os::raw::c_void,
-        phys_addr: std::os::raw::c_uint,
-        virt_addr: *mut std::os::raw::c_void,
-    ) -> *mut RknnTensorMemT;
-
-    #[link_name = "\u{1}_Z18rknn_create_mem_from_fdPvi"]
-    #[allow(dead_code)]
-    fn rknn_create_mem_from_fd(
-        context: *mut std::os::raw::c_void,
-        fd: std::os::raw::c_int,
-    ) -> *mut RknnTensorMemT;
-
-    #[link_name = "\u{1}_Z22rknn_create_mem_from_mb_blkPvPv"]
-    #[allow(dead_code)]
-    fn rknn_create_mem_from_mb_blk(
-        context: *mut std::os::raw::c_void,
-        mb_blk: *mut std::os::raw::c_void,
-    ) -> *mut RknnTensorMemT;
-
-    #[link_name = "\u{1}_Z13rknn_create_memPvj"]
-    #[allow(dead_code)]
-    fn rknn_create_mem(
-        context: *mut std::os::raw::c_void,
-        size: std::os::raw::c_uint,
-    ) -> *mut RknnTensorMemT;
-
-    #[link_name = "\u{1}_Z14rknn_create_mem2Pvmm"]
-    #[allow(dead_code)]
-    fn rknn_create_mem2(
-        context: *mut std::os::raw::c_void,
-        size: u64,
-        alloc_flags: u64,
-    ) -> *mut RknnTensorMemT;
-
-    #[link_name = "\u{1}_Z14rknn_destroy_memPvP15rknn_tensor_mem"]
-    #[allow(dead_code)]
-    fn rknn_destroy_mem(
-        context: *mut std::os::raw::c_void,
-        mem: *mut RknnTensorMemT,
-    ) -> std::os::raw::c_int;
-
-    // RKNN内存设置
-    #[link_name = "\u{1}_Z17rknn_set_weight_memPvP15rknn_tensor_mem"]
-    #[allow(dead_code)]
-    fn rknn_set_weight_mem(
-        context: *mut std::os::raw::c_void,
-        mem: *mut RknnTensorMemT,
-    ) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z19rknn_set_internal_memPvP15rknn_tensor_mem"]
-    #[allow(dead_code)]
-    fn rknn_set_internal_mem(
-        context: *mut std::os::raw::c_void,
-        mem: *mut RknnTensorMemT,
-    ) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z14rknn_set_io_memPvP15rknn_tensor_mem"]
-    #[allow(dead_code)]
-    fn rknn_set_io_mem(
-        context: *mut std::os::raw::c_void,
-        mem: *mut RknnTensorMemT,
-    ) -> std::os::raw::c_int;
-
-    // RKNN输入形状设置
-    #[link_name = "\u{1}_Z18rknn_set_input_shapePvjPj"]
-    #[allow(dead_code)]
-    fn rknn_set_input_shape(
-        context: *mut std::os::raw::c_void,
-        index: std::os::raw::c_uint,
-        shape: *const std::os::raw::c_uint,
-    ) -> std::os::raw::c_int;
-
-    #[link_name = "\u{1}_Z19rknn_set_input_shapesPvPj"]
-    #[allow(dead_code)]
-    fn rknn_set_input_shapes(
-        context: *mut std::os::raw::c_void,
-        shapes: *const std::os::raw::c_uint,
-    ) -> std::os::raw::c_int;
-
-    // RKNN内存同步
-    #[link_name = "\u{1}_Z12rknn_mem_syncPvP15rknn_tensor_memj"]
-    #[allow(dead_code)]
-    fn rknn_mem_sync(
-        context: *mut std::os::raw::c_void,
-        mem: *mut RknnTensorMemT,
-        sync_type: std::os::raw::c_uint,
-    ) -> std::os::raw::c_int;
-}
-
-// 导入C结构体定义
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct RknnContextT {
-    _unused: [u8; 0],
-}
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct RknnTensorMemT {
-    _unused: [u8; 0],
-}
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct RknnInputsT {
-    pub n_inputs: std::os::raw::c_uint,
-    pub inputs: *mut RknnInputT,
-}
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct RknnInputT {
-    pub index: std::os::raw::c_uint,
-    pub buf: *mut std::os::raw::c_void,
-    pub size: std::os::raw::c_uint,
-    pub pass_through: std::os::raw::c_uint,
-    pub type_: std::os::raw::c_uint,
-    pub fmt: std::os::raw::c_uint,
-}
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct RknnOutputsT {
-    pub n_outputs: std::os::raw::c_uint,
-    pub outputs: *mut RknnOutputT,
-}
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct RknnOutputT {
-    pub want_float: std::os::raw::c_uint,
-    pub is_prealloc: std::os::raw::c_uint,
-    pub buf: *mut std::os::raw::c_void,
-    pub size: std::os::raw::c_uint,
-}
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct RknnRunExtendT {
-    pub is_input: std::os::raw::c_uint,
-    pub is_output: std::os::raw::c_uint,
-    pub input_attr: *mut RknnTensorAttrT,
-    pub output_attr: *mut RknnTensorAttrT,
-}
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct RknnTensorAttrT {
-    pub index: std::os::raw::c_uint,
-    pub n_dims: std::os::raw::c_uint,
-    pub dims: [std::os::raw::c_uint; 4],
-    pub fmt: std::os::raw::c_uint,
-    pub type_: std::os::raw::c_uint,
-    pub size: std::os::raw::c_uint,
-    pub w_stride: std::os::raw::c_uint,
-    pub h_stride: std::os::raw::c_uint,
-    pub c_stride: std::os::raw::c_uint,
-    pub scale: f32,
-    pub zp: std::os::raw::c_int,
-}
+use std::marker::PhantomData;
+use std::sync::Arc;
 
 /// RKNN模型信息
 #[derive(Debug, Clone)]
@@ -270,6 +50,22 @@ pub enum RknnTensorType {
     Float64,
 }
 
+impl RknnTensorType {
+    /// 对应的 `rknn_tensor_type`，RKNN 不支持 64 位浮点数
+    fn to_rknn(self) -> Option<rknn::rknn_tensor_type> {
+        Some(match self {
+            RknnTensorType::Int8 => rknn::_rknn_tensor_type_RKNN_TENSOR_INT8,
+            RknnTensorType::Uint8 => rknn::_rknn_tensor_type_RKNN_TENSOR_UINT8,
+            RknnTensorType::Int16 => rknn::_rknn_tensor_type_RKNN_TENSOR_INT16,
+            RknnTensorType::Uint16 => rknn::_rknn_tensor_type_RKNN_TENSOR_UINT16,
+            RknnTensorType::Int32 => rknn::_rknn_tensor_type_RKNN_TENSOR_INT32,
+            RknnTensorType::Uint32 => rknn::_rknn_tensor_type_RKNN_TENSOR_UINT32,
+            RknnTensorType::Float32 => rknn::_rknn_tensor_type_RKNN_TENSOR_FLOAT32,
+            RknnTensorType::Float64 => return None,
+        })
+    }
+}
+
 /// RKNN张量
 #[derive(Debug, Clone)]
 pub struct RknnTensor {
@@ -315,21 +111,34 @@ pub struct RknnInferenceResult {
 }
 
 /// RKNN上下文
+///
+/// 厂商库没有说明上下文能否跨线程使用，上下文不实现 `Send`，只在创建它的线程上使用；
+/// 需要在后台推理时用 [`InferenceWorker`](crate::asynchronous::InferenceWorker) 在推理线程上创建。
 pub struct RknnContext {
+    backend: Arc<dyn Backend>,
     initialized: bool,
     model_path: String,
     model_info: Option<RknnModelInfo>,
-    context: Option<*mut std::os::raw::c_void>,
+    context: Option<rknn_context>,
+    /// 上下文句柄是整数，用裸指针标记去掉自动实现的 `Send`/`Sync`
+    _not_send: PhantomData<*mut ()>,
 }
 
 impl RknnContext {
     /// 创建新的RKNN上下文
     pub fn new() -> KmboxResult<Self> {
+        Self::with_backend(default_backend())
+    }
+
+    /// 使用指定后端创建RKNN上下文
+    pub fn with_backend(backend: Arc<dyn Backend>) -> KmboxResult<Self> {
         Ok(Self {
+            backend,
             initialized: true,
             model_path: String::new(),
             model_info: None,
             context: None,
+            _not_send: PhantomData,
         })
     }
 
@@ -344,20 +153,14 @@ impl RknnContext {
         let path_cstr = CString::new(model_path)
             .map_err(|e| KmboxError::ParameterError(format!("无效的模型路径: {}", e)))?;
 
-        let mut context_ptr: *mut std::os::raw::c_void = std::ptr::null_mut();
-        let mut size: std::os::raw::c_uint = 0;
-
-        let result = unsafe { rknn_init(path_cstr.as_ptr(), &mut context_ptr, &mut size) };
+        let mut context: rknn_context = 0;
+        let result = self.backend.rknn_init(&mut context, &path_cstr);
 
-        check_result(result, "加载RKNN模型")?;
+        check_result(result, Operation::RknnLoadModel)?;
 
-        if context_ptr.is_null() {
-            return Err(KmboxError::ModelError(
-                "模型加载失败：返回空指针".to_string(),
-            ));
-        }
-
-        self.context = Some(context_ptr);
+        // 替换之前加载的模型
+        self.destroy();
+        self.context = Some(context);
         self.model_path = model_path.to_string();
 
         // 获取模型信息
@@ -368,16 +171,23 @@ impl RknnContext {
 
     /// 更新模型信息
     fn update_model_info(&mut self) -> KmboxResult<()> {
-        if let Some(_context) = self.context {
-            // 这里可以调用rknn_query来获取模型信息
-            // 暂时使用默认值
+        if let Some(context) = self.context {
+            let mut io_num = rknn_input_output_num {
+                n_input: 0,
+                n_output: 0,
+            };
+            let result = self.backend.rknn_query_io_num(context, &mut io_num);
+            check_result(result, Operation::RknnQuery)?;
+
+            // 张量形状和类型需要逐个查询 RKNN_QUERY_INPUT_ATTR/RKNN_QUERY_OUTPUT_ATTR，暂未实现
             self.model_info = Some(RknnModelInfo {
-                input_count: 1,
-                output_count: 1,
-                input_shapes: vec![vec![1, 3, 224, 224]], // 默认输入形状
-                output_shapes: vec![vec![1, 1000]],       // 默认输出形状
-                input_types: vec![RknnTensorType::Float32],
-                output_types: vec![RknnTensorType::Float32],
+                input_count: io_num.n_input,
+                output_count: io_num.n_output,
+                input_shapes: vec![Vec::new(); io_num.n_input as usize],
+                output_shapes: vec![Vec::new(); io_num.n_output as usize],
+                input_types: vec![RknnTensorType::Float32; io_num.n_input as usize],
+                // 获取输出时要求运行时转换为浮点数
+                output_types: vec![RknnTensorType::Float32; io_num.n_output as usize],
                 model_size: 0, // 需要从实际查询中获取
             });
         }
@@ -411,27 +221,31 @@ impl RknnContext {
             }
 
             // 创建C输入结构
-            let mut c_inputs: Vec<RknnInputT> = inputs
+            let mut c_inputs = inputs
                 .iter()
                 .enumerate()
-                .map(|(i, tensor)| RknnInputT {
-                    index: i as std::os::raw::c_uint,
-                    buf: tensor.data.as_ptr() as *mut std::os::raw::c_void,
-                    size: tensor.data.len() as std::os::raw::c_uint,
-                    pass_through: 0,
-                    type_: tensor.tensor_type as std::os::raw::c_uint,
-                    fmt: 0, // 默认格式
+                .map(|(i, tensor)| {
+                    let type_ = tensor.tensor_type.to_rknn().ok_or_else(|| {
+                        KmboxError::ParameterError(format!(
+                            "RKNN不支持的张量类型: {:?}",
+                            tensor.tensor_type
+                        ))
+                    })?;
+                    Ok(rknn_input {
+                        index: i as u32,
+                        buf: tensor.data.as_ptr() as *mut std::os::raw::c_void,
+                        size: tensor.data.len() as u32,
+                        pass_through: 0,
+                        type_,
+                        fmt: rknn::_rknn_tensor_format_RKNN_TENSOR_NCHW,
+                    })
                 })
-                .collect();
-
-            let inputs_c = RknnInputsT {
-                n_inputs: c_inputs.len() as std::os::raw::c_uint,
-                inputs: c_inputs.as_mut_ptr(),
-            };
+                .collect::<KmboxResult<Vec<_>>>()?;
 
-            let result = unsafe { rknn_inputs_set(context, &inputs_c) };
+            // 运行时只读取输入数据，`inputs` 在调用期间一直有效
+            let result = unsafe { self.backend.rknn_inputs_set(context, &mut c_inputs) };
 
-            check_result(result, "设置RKNN输入")
+            check_result(result, Operation::RknnSetInputs)
         } else {
             Err(KmboxError::ModelError("模型未加载".to_string()))
         }
@@ -449,14 +263,14 @@ impl RknnContext {
             let start_time = std::time::Instant::now();
 
             // 运行推理
-            let result = unsafe { rknn_run(context, std::ptr::null_mut()) };
+            let result = self.backend.rknn_run(context);
 
-            check_result(result, "运行RKNN推理")?;
+            check_result(result, Operation::RknnRun)?;
 
             // 等待推理完成
-            let wait_result = unsafe { rknn_wait(context, std::ptr::null_mut()) };
+            let wait_result = self.backend.rknn_wait(context);
 
-            check_result(wait_result, "等待RKNN推理完成")?;
+            check_result(wait_result, Operation::RknnWait)?;
 
             let inference_time = start_time.elapsed().as_millis() as u64;
 
@@ -480,54 +294,49 @@ impl RknnContext {
             ));
         }
 
-        if let Some(context) = self.context {
-            let mut outputs_c = RknnOutputsT {
-                n_outputs: 0,
-                outputs: std::ptr::null_mut(),
-            };
-
-            let result = unsafe { rknn_outputs_get(context, &mut outputs_c) };
+        let (Some(context), Some(info)) = (self.context, self.model_info.as_ref()) else {
+            return Err(KmboxError::ModelError("模型未加载".to_string()));
+        };
+
+        let mut outputs_c: Vec<rknn_output> = (0..info.output_count)
+            .map(|index| rknn_output {
+                want_float: 1,
+                is_prealloc: 0,
+                index,
+                buf: std::ptr::null_mut(),
+                size: 0,
+            })
+            .collect();
 
-            check_result(result, "获取RKNN输出")?;
+        // 输出缓冲区由运行时分配
+        let result = unsafe { self.backend.rknn_outputs_get(context, &mut outputs_c) };
 
-            let mut outputs = Vec::new();
+        check_result(result, Operation::RknnGetOutputs)?;
 
-            if !outputs_c.outputs.is_null() && outputs_c.n_outputs > 0 {
-                let outputs_slice = unsafe {
-                    std::slice::from_raw_parts(outputs_c.outputs, outputs_c.n_outputs as usize)
+        let outputs = outputs_c
+            .iter()
+            .enumerate()
+            .filter(|(_, output)| !output.buf.is_null() && output.size > 0)
+            .map(|(i, output)| {
+                let data = unsafe {
+                    std::slice::from_raw_parts(output.buf as *const u8, output.size as usize)
+                        .to_vec()
                 };
+                RknnTensor::new(
+                    data,
+                    vec![output.size],       // 简化形状
+                    RknnTensorType::Float32, // want_float 要求运行时输出浮点数
+                    format!("output_{}", i),
+                )
+            })
+            .collect();
 
-                for (i, output) in outputs_slice.iter().enumerate() {
-                    if !output.buf.is_null() && output.size > 0 {
-                        let data = unsafe {
-                            std::slice::from_raw_parts(
-                                output.buf as *const u8,
-                                output.size as usize,
-                            )
-                            .to_vec()
-                        };
-
-                        let tensor = RknnTensor::new(
-                            data,
-                            vec![output.size],       // 简化形状
-                            RknnTensorType::Float32, // 默认类型
-                            format!("output_{}", i),
-                        );
-
-                        outputs.push(tensor);
-                    }
-                }
-
-                // 释放输出
-                unsafe {
-                    rknn_outputs_release(context, &mut outputs_c);
-                }
-            }
-
-            Ok(outputs)
-        } else {
-            Err(KmboxError::ModelError("模型未加载".to_string()))
+        // 释放输出
+        unsafe {
+            self.backend.rknn_outputs_release(context, &mut outputs_c);
         }
+
+        Ok(outputs)
     }
 
     /// 设置推理参数
@@ -543,6 +352,14 @@ impl RknnContext {
             "设置推理参数功能尚未实现".to_string(),
         ))
     }
+
+    /// 销毁已加载的模型上下文
+    fn destroy(&mut self) {
+        if let Some(context) = self.context.take() {
+            let _ = self.backend.rknn_destroy(context);
+        }
+        self.model_info = None;
+    }
 }
 
 /// RKNN推理参数
@@ -614,11 +431,7 @@ impl RknnModel {
 
 impl Drop for RknnContext {
     fn drop(&mut self) {
-        if let Some(context) = self.context {
-            unsafe {
-                let _ = rknn_destroy(context);
-            }
-        }
+        self.destroy();
     }
 }
 
@@ -631,6 +444,8 @@ impl Drop for RknnModel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::SimulatedBackend;
+    use crate::error::ErrorCode;
 
     #[test]
     fn test_rknn_tensor_creation() {
@@ -663,6 +478,69 @@ mod tests {
         assert!(context.is_ok());
     }
 
+    #[test]
+    fn test_rknn_load_model() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let mut context = RknnContext::with_backend(sim.clone()).unwrap();
+        assert!(context.get_model_info().is_err());
+
+        let error = context.load_model("missing.rknn").unwrap_err();
+        assert_eq!(error.error_code(), Some(ErrorCode::ModelInvalid));
+
+        sim.add_model("model.rknn");
+        context.load_model("model.rknn").unwrap();
+        let info = context.get_model_info().unwrap();
+        assert_eq!((info.input_count, info.output_count), (1, 1));
+        assert_eq!(sim.state().rknn_contexts.len(), 1);
+
+        // 重新加载时释放之前的上下文
+        context.load_model("model.rknn").unwrap();
+        assert_eq!(sim.state().rknn_contexts.len(), 1);
+
+        drop(context);
+        assert!(sim.state().rknn_contexts.is_empty());
+    }
+
+    #[test]
+    fn test_rknn_inference() {
+        let sim = Arc::new(SimulatedBackend::new());
+        sim.add_model("model.rknn");
+        let mut context = RknnContext::with_backend(sim.clone()).unwrap();
+        context.load_model("model.rknn").unwrap();
+
+        // 没有设置输入时推理失败
+        let error = context.run_inference().unwrap_err();
+        assert_eq!(error.operation(), Some("rknn_run"));
+        assert_eq!(error.error_code(), Some(ErrorCode::InputInvalid));
+
+        let input = RknnTensor::new(
+            vec![1, 2, 3, 4],
+            vec![1, 4],
+            RknnTensorType::Uint8,
+            "input".to_string(),
+        );
+        context.set_inputs(vec![input]).unwrap();
+        let result = context.run_inference().unwrap();
+        assert_eq!(result.outputs.len(), 1);
+        assert_eq!(result.outputs[0].data, vec![1, 2, 3, 4]);
+        assert_eq!(result.outputs[0].name, "output_0");
+
+        let unsupported = RknnTensor::new(
+            vec![0; 8],
+            vec![1],
+            RknnTensorType::Float64,
+            "input".to_string(),
+        );
+        assert!(matches!(
+            context.set_inputs(vec![unsupported]),
+            Err(KmboxError::ParameterError(_))
+        ));
+
+        sim.inject_fault(-3);
+        let error = context.run_inference().unwrap_err();
+        assert_eq!(error.error_code(), Some(ErrorCode::DeviceUnavailable));
+    }
+
     #[test]
     fn test_rknn_model_creation() {
         let model = RknnModel::new("model.rknn");
@@ -0,0 +1,582 @@
+//! HID 输出调度模块
+//!
+//! 在紧密循环中调用 `mouse_move` 会占满 USB 链路并增加延迟。
+//! [`OutputScheduler`] 位于调用方和后端之间，按固定间隔最多发送一个报告：
+//!
+//! - 两个按键边沿之间的相对位移合并为一个报告，滚轮刻度也合并为一个报告
+//! - 鼠标按键和键盘按键的按下、释放严格按提交顺序发送，之前提交的位移总是先于边沿发出
+//! - 超出 `short` 范围的合并位移拆成多个报告
+//!
+//! 合并规则由不依赖线程和系统时钟的 [`ReportCoalescer`] 实现，调用方传入当前时刻。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::kmbox_ai::{KmboxAI, MouseButton};
+//! use std::time::Duration;
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let kmbox = KmboxAI::new()?;
+//! let output = kmbox.output_scheduler(Duration::from_millis(1))?;
+//! for _ in 0..10_000 {
+//!     output.mouse_move(1, 0)?;
+//! }
+//! output.mouse_button(MouseButton::Left, true)?;
+//! output.mouse_button(MouseButton::Left, false)?;
+//! output.flush();
+//! println!("{:?}", output.stats());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::{check_result, KmboxError, KmboxResult, Operation};
+use crate::failsafe::HeldTracker;
+use crate::handle::to_short;
+use crate::kmbox_ai::MouseButton;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 发往后端的报告
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputReport {
+    /// 鼠标相对移动
+    Move { dx: i32, dy: i32 },
+    /// 鼠标滚轮
+    Wheel(i32),
+    /// 鼠标按键按下或释放
+    Button { button: MouseButton, down: bool },
+    /// 键盘按键按下或释放（HID 键值）
+    Key { vkey: i32, down: bool },
+}
+
+impl OutputReport {
+    /// 是否为按键边沿；边沿不参与合并，合并也不会跨越边沿
+    pub fn is_edge(&self) -> bool {
+        matches!(self, OutputReport::Button { .. } | OutputReport::Key { .. })
+    }
+
+    /// 通过后端发送报告
+    pub fn send(&self, backend: &dyn Backend) -> KmboxResult<()> {
+        match *self {
+            OutputReport::Move { dx, dy } => {
+                let (x, y) = (to_short(dx)?, to_short(dy)?);
+                check_result(backend.mouse_move(x, y), Operation::MouseMove)
+            }
+            OutputReport::Wheel(delta) => {
+                check_result(backend.mouse_wheel(delta), Operation::MouseWheel)
+            }
+            OutputReport::Button { button, down } => {
+                check_result(backend.mouse_button(button, down), Operation::MouseButton)
+            }
+            OutputReport::Key { vkey, down: true } => {
+                check_result(backend.key_down(vkey), Operation::KeyDown)
+            }
+            OutputReport::Key { vkey, down: false } => {
+                check_result(backend.key_up(vkey), Operation::KeyUp)
+            }
+        }
+    }
+}
+
+/// 调度统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SchedulerStats {
+    /// 提交的输出数量
+    pub submitted: u64,
+    /// 发给后端的报告数量
+    pub sent: u64,
+    /// 合并到已排队报告中的输出数量
+    pub merged: u64,
+    /// 未送达的报告数量（清空队列或后端返回错误）
+    pub dropped: u64,
+    /// 当前排队的报告数量
+    pub pending: usize,
+}
+
+/// 报告合并与限速
+///
+/// 不启动线程也不读取系统时钟，由调用方传入当前时刻，便于测试和嵌入其他调度循环。
+#[derive(Debug)]
+pub struct ReportCoalescer {
+    interval: Duration,
+    queue: VecDeque<OutputReport>,
+    next_send: Option<Instant>,
+    stats: SchedulerStats,
+}
+
+impl ReportCoalescer {
+    /// 创建合并器，`interval` 为两个报告之间的最小间隔
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            queue: VecDeque::new(),
+            next_send: None,
+            stats: SchedulerStats::default(),
+        }
+    }
+
+    /// 报告间隔
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// 排队的报告数量
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// 队列是否为空
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// 统计信息
+    pub fn stats(&self) -> SchedulerStats {
+        SchedulerStats {
+            pending: self.queue.len(),
+            ..self.stats
+        }
+    }
+
+    /// 提交一个输出
+    ///
+    /// 位移和滚轮合并到最后一个边沿之后的同类报告中，边沿总是追加到队尾。
+    pub fn push(&mut self, report: OutputReport) {
+        self.stats.submitted += 1;
+        if !report.is_edge() {
+            let segment = self
+                .queue
+                .iter()
+                .rposition(OutputReport::is_edge)
+                .map_or(0, |index| index + 1);
+            for queued in self.queue.range_mut(segment..) {
+                match (queued, report) {
+                    (
+                        OutputReport::Move { dx, dy },
+                        OutputReport::Move {
+                            dx: add_x,
+                            dy: add_y,
+                        },
+                    ) => {
+                        *dx = dx.saturating_add(add_x);
+                        *dy = dy.saturating_add(add_y);
+                    }
+                    (OutputReport::Wheel(delta), OutputReport::Wheel(add)) => {
+                        *delta = delta.saturating_add(add);
+                    }
+                    _ => continue,
+                }
+                self.stats.merged += 1;
+                return;
+            }
+        }
+        self.queue.push_back(report);
+    }
+
+    /// 下一个报告可以发送的时刻，队列为空时返回 `None`
+    pub fn next_deadline(&self, now: Instant) -> Option<Instant> {
+        if self.queue.is_empty() {
+            return None;
+        }
+        Some(self.next_send.map_or(now, |next| next.max(now)))
+    }
+
+    /// 到达发送时刻时取出下一个报告
+    ///
+    /// 超出 `short` 范围的位移只取出范围内的部分，余量留在队首等待下一个间隔。
+    pub fn poll(&mut self, now: Instant) -> Option<OutputReport> {
+        if self.next_send.is_some_and(|next| now < next) {
+            return None;
+        }
+        let report = match self.queue.front_mut()? {
+            OutputReport::Move { dx, dy } => {
+                let limit = |value: i32| value.clamp(i16::MIN as i32, i16::MAX as i32);
+                let (step_x, step_y) = (limit(*dx), limit(*dy));
+                *dx -= step_x;
+                *dy -= step_y;
+                if *dx == 0 && *dy == 0 {
+                    self.queue.pop_front();
+                }
+                OutputReport::Move {
+                    dx: step_x,
+                    dy: step_y,
+                }
+            }
+            _ => self.queue.pop_front()?,
+        };
+        self.stats.sent += 1;
+        self.next_send = Some(now + self.interval);
+        Some(report)
+    }
+
+    /// 记录一个发送失败的报告
+    pub fn record_failure(&mut self) {
+        self.stats.dropped += 1;
+    }
+
+    /// 丢弃所有排队的报告，返回丢弃的数量
+    pub fn clear(&mut self) -> usize {
+        let dropped = self.queue.len();
+        self.queue.clear();
+        self.stats.dropped += dropped as u64;
+        dropped
+    }
+}
+
+struct State {
+    coalescer: ReportCoalescer,
+    /// 已从队列取出、正在发送的报告
+    sending: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    /// 有新输出或需要停止时通知发送线程
+    wake: Condvar,
+    /// 队列清空时通知等待 `flush` 的线程
+    drained: Condvar,
+    running: AtomicBool,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 后台发送线程
+///
+/// 通过 [`OutputScheduler`] 按下的按键在调度器销毁时释放。
+pub struct OutputScheduler {
+    shared: Arc<Shared>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl OutputScheduler {
+    /// 在指定后端上启动发送线程
+    pub fn start(backend: Arc<dyn Backend>, interval: Duration) -> KmboxResult<Self> {
+        if interval.is_zero() {
+            return Err(KmboxError::ParameterError("报告间隔不能为0".to_string()));
+        }
+
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                coalescer: ReportCoalescer::new(interval),
+                sending: false,
+            }),
+            wake: Condvar::new(),
+            drained: Condvar::new(),
+            running: AtomicBool::new(true),
+        });
+        let worker = shared.clone();
+        let handle = thread::Builder::new()
+            .name("kmbox-output".to_string())
+            .spawn(move || run_sender(&worker, backend))
+            .map_err(|e| KmboxError::io("启动发送线程", e))?;
+
+        Ok(Self {
+            shared,
+            handle: Some(handle),
+        })
+    }
+
+    /// 提交一个输出，不等待发送
+    pub fn submit(&self, report: OutputReport) -> KmboxResult<()> {
+        if !self.shared.running.load(Ordering::Acquire) {
+            return Err(KmboxError::SystemError("发送线程已停止".to_string()));
+        }
+        self.shared.lock().coalescer.push(report);
+        self.shared.wake.notify_one();
+        Ok(())
+    }
+
+    /// 鼠标相对移动
+    pub fn mouse_move(&self, dx: i32, dy: i32) -> KmboxResult<()> {
+        self.submit(OutputReport::Move { dx, dy })
+    }
+
+    /// 鼠标滚轮
+    pub fn mouse_wheel(&self, delta: i32) -> KmboxResult<()> {
+        self.submit(OutputReport::Wheel(delta))
+    }
+
+    /// 鼠标按键按下或释放
+    pub fn mouse_button(&self, button: MouseButton, down: bool) -> KmboxResult<()> {
+        self.submit(OutputReport::Button { button, down })
+    }
+
+    /// 键盘按键按下或释放
+    pub fn key(&self, vkey: i32, down: bool) -> KmboxResult<()> {
+        self.submit(OutputReport::Key { vkey, down })
+    }
+
+    /// 等待所有已提交的输出发送完毕
+    pub fn flush(&self) {
+        let mut state = self.shared.lock();
+        while (state.sending || !state.coalescer.is_empty())
+            && self.handle.as_ref().is_some_and(|h| !h.is_finished())
+        {
+            state = self
+                .shared
+                .drained
+                .wait_timeout(state, Duration::from_millis(10))
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    /// 丢弃尚未发送的输出，返回丢弃的数量
+    pub fn clear(&self) -> usize {
+        let dropped = self.shared.lock().coalescer.clear();
+        self.shared.drained.notify_all();
+        dropped
+    }
+
+    /// 统计信息
+    pub fn stats(&self) -> SchedulerStats {
+        self.shared.lock().coalescer.stats()
+    }
+
+    /// 发送完已提交的输出后停止发送线程
+    pub fn stop(&mut self) {
+        self.shared.running.store(false, Ordering::Release);
+        self.shared.wake.notify_one();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for OutputScheduler {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn run_sender(shared: &Shared, backend: Arc<dyn Backend>) {
+    let held = HeldTracker::new(backend.clone());
+    let mut state = shared.lock();
+    loop {
+        let now = Instant::now();
+        let Some(deadline) = state.coalescer.next_deadline(now) else {
+            shared.drained.notify_all();
+            if !shared.running.load(Ordering::Acquire) {
+                break;
+            }
+            state = shared.wake.wait(state).unwrap_or_else(|e| e.into_inner());
+            continue;
+        };
+        if deadline > now {
+            state = shared
+                .wake
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+            continue;
+        }
+        let Some(report) = state.coalescer.poll(now) else {
+            continue;
+        };
+
+        // 发送期间不持有锁，调用方可以继续提交
+        state.sending = true;
+        drop(state);
+        let result = report.send(backend.as_ref());
+        if result.is_ok() {
+            match report {
+                OutputReport::Button { button, down } => held.set_button(button, down),
+                OutputReport::Key { vkey, down } => held.set_key(vkey, down),
+                _ => {}
+            }
+        }
+        state = shared.lock();
+        state.sending = false;
+        if result.is_err() {
+            state.coalescer.record_failure();
+        }
+    }
+    drop(state);
+
+    if !held.release_all().is_empty() {
+        backend.key_init();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
+
+    /// 手动推进的时钟
+    struct FakeClock {
+        now: Instant,
+    }
+
+    impl FakeClock {
+        fn new() -> Self {
+            Self {
+                now: Instant::now(),
+            }
+        }
+
+        fn advance(&mut self, duration: Duration) -> Instant {
+            self.now += duration;
+            self.now
+        }
+    }
+
+    const INTERVAL: Duration = Duration::from_millis(4);
+
+    fn moved(dx: i32, dy: i32) -> OutputReport {
+        OutputReport::Move { dx, dy }
+    }
+
+    fn button(down: bool) -> OutputReport {
+        OutputReport::Button {
+            button: MouseButton::Left,
+            down,
+        }
+    }
+
+    #[test]
+    fn test_moves_coalesce_per_interval() {
+        let mut clock = FakeClock::new();
+        let mut coalescer = ReportCoalescer::new(INTERVAL);
+
+        for _ in 0..10 {
+            coalescer.push(moved(1, -2));
+            coalescer.push(OutputReport::Wheel(1));
+        }
+        assert_eq!(coalescer.len(), 2);
+        assert_eq!(coalescer.poll(clock.now), Some(moved(10, -20)));
+
+        // 间隔未到时不发送，期间提交的位移继续合并
+        coalescer.push(moved(5, 0));
+        assert_eq!(coalescer.poll(clock.advance(INTERVAL / 2)), None);
+        assert_eq!(
+            coalescer.next_deadline(clock.now),
+            Some(clock.now + INTERVAL / 2)
+        );
+        assert_eq!(
+            coalescer.poll(clock.advance(INTERVAL / 2)),
+            Some(OutputReport::Wheel(10))
+        );
+        assert_eq!(coalescer.poll(clock.advance(INTERVAL)), Some(moved(5, 0)));
+        assert_eq!(coalescer.poll(clock.advance(INTERVAL)), None);
+        assert_eq!(coalescer.next_deadline(clock.now), None);
+
+        let stats = coalescer.stats();
+        assert_eq!((stats.submitted, stats.sent, stats.merged), (21, 3, 18));
+    }
+
+    #[test]
+    fn test_edges_keep_order() {
+        let mut clock = FakeClock::new();
+        let mut coalescer = ReportCoalescer::new(INTERVAL);
+
+        coalescer.push(moved(1, 0));
+        coalescer.push(moved(1, 0));
+        coalescer.push(button(true));
+        coalescer.push(moved(0, 3));
+        coalescer.push(button(false));
+        coalescer.push(OutputReport::Key {
+            vkey: 4,
+            down: true,
+        });
+        coalescer.push(moved(0, 1));
+        coalescer.push(moved(0, 1));
+
+        let mut sent = Vec::new();
+        while !coalescer.is_empty() {
+            sent.extend(coalescer.poll(clock.now));
+            clock.advance(INTERVAL);
+        }
+        assert_eq!(
+            sent,
+            [
+                moved(2, 0),
+                button(true),
+                moved(0, 3),
+                button(false),
+                OutputReport::Key {
+                    vkey: 4,
+                    down: true
+                },
+                moved(0, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_large_move_is_split() {
+        let mut clock = FakeClock::new();
+        let mut coalescer = ReportCoalescer::new(INTERVAL);
+
+        coalescer.push(moved(30_000, -1));
+        coalescer.push(moved(30_000, 0));
+        assert_eq!(coalescer.poll(clock.now), Some(moved(32_767, -1)));
+        assert_eq!(coalescer.len(), 1);
+        assert_eq!(
+            coalescer.poll(clock.advance(INTERVAL)),
+            Some(moved(27_233, 0))
+        );
+        assert!(coalescer.is_empty());
+
+        coalescer.push(button(true));
+        coalescer.push(moved(1, 1));
+        assert_eq!(coalescer.clear(), 2);
+        let stats = coalescer.stats();
+        assert_eq!((stats.sent, stats.dropped, stats.pending), (2, 2, 0));
+    }
+
+    #[test]
+    fn test_scheduler_sends_and_releases() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let mut output = OutputScheduler::start(sim.clone(), Duration::from_millis(1)).unwrap();
+        assert!(OutputScheduler::start(sim.clone(), Duration::ZERO).is_err());
+
+        for _ in 0..100 {
+            output.mouse_move(1, 0).unwrap();
+        }
+        output.mouse_button(MouseButton::Right, true).unwrap();
+        output.flush();
+
+        let reports = sim.reports();
+        let total: i32 = reports
+            .iter()
+            .map(|report| match report {
+                HidReport::Move { x, .. } => *x,
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(total, 100);
+        assert!(reports.len() < 101);
+        assert_eq!(
+            reports.last(),
+            Some(&HidReport::Button {
+                button: MouseButton::Right,
+                down: true
+            })
+        );
+        let stats = output.stats();
+        assert_eq!(stats.submitted, 101);
+        assert_eq!(stats.sent + stats.merged, 101);
+
+        output.stop();
+        assert!(output.mouse_move(1, 0).is_err());
+        assert_eq!(
+            sim.reports()[reports.len()..],
+            [
+                HidReport::Button {
+                    button: MouseButton::Right,
+                    down: false
+                },
+                HidReport::KeyInit,
+            ]
+        );
+    }
+}
@@ -0,0 +1,724 @@
+//! 宏脚本模块
+//!
+//! 用简单的文本脚本描述重复的键鼠操作，不需要重新编译程序。
+//! 脚本由 [`Script::parse`] 解析，出错时报告行号；[`Executor`] 通过 [`KmboxAI`] 和 [`Keyboard`] 执行。
+//!
+//! ## 语法
+//!
+//! 每行一条命令，`#` 之后为注释，命令名不区分大小写：
+//!
+//! | 命令 | 说明 |
+//! |------|------|
+//! | `move X Y` | 鼠标相对移动 |
+//! | `wheel N` | 鼠标滚轮 |
+//! | `click BUTTON [次数]` | 点击鼠标按键，`BUTTON` 为 `left`、`right`、`middle`、`side1`、`side2` |
+//! | `hold BUTTON` / `release BUTTON` | 按下 / 释放鼠标按键 |
+//! | `key KEY [毫秒]` | 点击键盘按键，默认按住 50 毫秒，`KEY` 为按键名称或别名，如 `ENTER`、`LEFTCTRL`、`ESC` |
+//! | `keydown KEY` / `keyup KEY` | 按下 / 释放键盘按键 |
+//! | `type "文本"` | 输入字符串，支持 `\"`、`\\`、`\n` 转义 |
+//! | `wait 毫秒` | 等待 |
+//! | `repeat [N]` ... `end` | 循环 N 次，省略 N 时一直循环 |
+//! | `if [not] key KEY` / `if [not] button BUTTON` ... [`else` ...] `end` | 根据物理键鼠当前状态执行 |
+//! | `label NAME` / `goto NAME` | 定义标签 / 跳转到标签 |
+//! | `stop` | 结束脚本 |
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::{Keyboard, KmboxAI};
+//! use kmbox_ai_rust::script::{Executor, Script};
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let script = Script::parse(
+//!     r#"
+//!     move 100 -50
+//!     click left
+//!     wait 200
+//!     type "hello"
+//!     key ENTER
+//!     "#,
+//! )?;
+//! let kmbox = KmboxAI::new()?;
+//! let keyboard = Keyboard::new()?;
+//! Executor::new(&kmbox, &keyboard).run(&script)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{KmboxError, KmboxResult};
+use crate::keyboard::{Key, Keyboard};
+use crate::kmbox_ai::{KmboxAI, MouseAction, MouseButton};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// `key` 命令默认按住的时间（毫秒）
+const DEFAULT_KEY_DURATION_MS: u32 = 50;
+
+/// 等待时检查停止标志的间隔
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 一条键鼠操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    Move { x: i32, y: i32 },
+    Wheel(i32),
+    Click { button: MouseButton, count: u32 },
+    Hold(MouseButton),
+    Release(MouseButton),
+    Key { key: Key, duration_ms: u32 },
+    KeyDown(Key),
+    KeyUp(Key),
+    Type(String),
+    Wait(Duration),
+}
+
+/// 条件判断的对象
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Key(Key),
+    Button(MouseButton),
+}
+
+/// 解析后的指令
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Instruction {
+    Command(Command),
+    /// 无条件跳转
+    Jump(usize),
+    /// 条件（`negate` 取反后）不成立时跳转
+    JumpUnless {
+        condition: Condition,
+        negate: bool,
+        target: usize,
+    },
+    /// 初始化循环计数，`None` 表示无限循环
+    LoopInit {
+        slot: usize,
+        count: Option<u32>,
+    },
+    /// 循环计数用完时跳出循环，否则计数减一
+    LoopTest {
+        slot: usize,
+        end: usize,
+    },
+    Stop,
+}
+
+/// 尚未闭合的代码块
+enum Block {
+    Repeat { line: usize, test: usize },
+    If { line: usize, branch: usize },
+    Else { line: usize, jump: usize },
+}
+
+/// 解析完成的脚本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Script {
+    /// 指令及其所在行号
+    instructions: Vec<(usize, Instruction)>,
+    /// 循环计数槽数量
+    loops: usize,
+}
+
+impl Script {
+    /// 解析脚本文本
+    pub fn parse(source: &str) -> KmboxResult<Self> {
+        Parser::default().parse(source)
+    }
+
+    /// 从文件加载脚本
+    pub fn load<P: AsRef<Path>>(path: P) -> KmboxResult<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// 指令数量
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    /// 脚本是否为空
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+}
+
+impl FromStr for Script {
+    type Err = KmboxError;
+
+    fn from_str(source: &str) -> KmboxResult<Self> {
+        Self::parse(source)
+    }
+}
+
+fn line_error(line: usize, message: impl std::fmt::Display) -> KmboxError {
+    KmboxError::ParameterError(format!("第 {} 行: {}", line, message))
+}
+
+/// 把一行拆分为单词，双引号内的内容作为一个单词，`#` 之后为注释
+fn tokenize(line: usize, text: &str) -> KmboxResult<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '#' {
+            break;
+        } else if c == '"' {
+            chars.next();
+            let mut token = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => token.push('\n'),
+                        Some(escaped @ ('"' | '\\')) => token.push(escaped),
+                        Some(other) => {
+                            return Err(line_error(line, format!("未知的转义字符: \\{}", other)))
+                        }
+                        None => return Err(line_error(line, "字符串没有结束引号")),
+                    },
+                    Some(other) => token.push(other),
+                    None => return Err(line_error(line, "字符串没有结束引号")),
+                }
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '#' {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_button(line: usize, name: &str) -> KmboxResult<MouseButton> {
+    match name.to_ascii_lowercase().as_str() {
+        "left" => Ok(MouseButton::Left),
+        "right" => Ok(MouseButton::Right),
+        "middle" => Ok(MouseButton::Middle),
+        "side1" => Ok(MouseButton::Side1),
+        "side2" => Ok(MouseButton::Side2),
+        _ => Err(line_error(line, format!("未知的鼠标按键: {}", name))),
+    }
+}
+
+fn parse_key(line: usize, name: &str) -> KmboxResult<Key> {
+    name.parse::<Key>()
+        .map_err(|_| line_error(line, format!("未知的按键: {}", name)))
+}
+
+fn parse_number<T: FromStr>(line: usize, text: &str) -> KmboxResult<T> {
+    text.parse()
+        .map_err(|_| line_error(line, format!("无效的数字: {}", text)))
+}
+
+/// 脚本解析器
+#[derive(Default)]
+struct Parser {
+    instructions: Vec<(usize, Instruction)>,
+    blocks: Vec<Block>,
+    labels: BTreeMap<String, usize>,
+    /// 待回填的跳转：指令位置、标签名、行号
+    gotos: Vec<(usize, String, usize)>,
+    loops: usize,
+}
+
+impl Parser {
+    fn emit(&mut self, line: usize, instruction: Instruction) -> usize {
+        self.instructions.push((line, instruction));
+        self.instructions.len() - 1
+    }
+
+    /// 回填跳转目标
+    fn patch(&mut self, index: usize, to: usize) {
+        match &mut self.instructions[index].1 {
+            Instruction::Jump(target)
+            | Instruction::JumpUnless { target, .. }
+            | Instruction::LoopTest { end: target, .. } => *target = to,
+            _ => unreachable!("只有跳转指令需要回填"),
+        }
+    }
+
+    fn parse(mut self, source: &str) -> KmboxResult<Script> {
+        for (index, text) in source.lines().enumerate() {
+            let line = index + 1;
+            let tokens = tokenize(line, text)?;
+            let Some((name, args)) = tokens.split_first() else {
+                continue;
+            };
+            self.parse_line(line, &name.to_ascii_lowercase(), args)?;
+        }
+
+        if let Some(block) = self.blocks.last() {
+            let (line, name) = match block {
+                Block::Repeat { line, .. } => (*line, "repeat"),
+                Block::If { line, .. } | Block::Else { line, .. } => (*line, "if"),
+            };
+            return Err(line_error(line, format!("{} 没有对应的 end", name)));
+        }
+
+        for (index, label, line) in std::mem::take(&mut self.gotos) {
+            let Some(&target) = self.labels.get(&label) else {
+                return Err(line_error(line, format!("标签不存在: {}", label)));
+            };
+            self.patch(index, target);
+        }
+
+        Ok(Script {
+            instructions: self.instructions,
+            loops: self.loops,
+        })
+    }
+
+    fn parse_line(&mut self, line: usize, name: &str, args: &[String]) -> KmboxResult<()> {
+        let arity = |min: usize, max: usize| {
+            if args.len() < min || args.len() > max {
+                let expected = if min == max {
+                    format!("{}", min)
+                } else {
+                    format!("{}~{}", min, max)
+                };
+                Err(line_error(
+                    line,
+                    format!(
+                        "{} 需要 {} 个参数，实际为 {} 个",
+                        name,
+                        expected,
+                        args.len()
+                    ),
+                ))
+            } else {
+                Ok(())
+            }
+        };
+
+        let command = match name {
+            "move" => {
+                arity(2, 2)?;
+                Command::Move {
+                    x: parse_number(line, &args[0])?,
+                    y: parse_number(line, &args[1])?,
+                }
+            }
+            "wheel" => {
+                arity(1, 1)?;
+                Command::Wheel(parse_number(line, &args[0])?)
+            }
+            "click" => {
+                arity(1, 2)?;
+                let count = match args.get(1) {
+                    Some(count) => parse_number(line, count)?,
+                    None => 1,
+                };
+                Command::Click {
+                    button: parse_button(line, &args[0])?,
+                    count,
+                }
+            }
+            "hold" => {
+                arity(1, 1)?;
+                Command::Hold(parse_button(line, &args[0])?)
+            }
+            "release" => {
+                arity(1, 1)?;
+                Command::Release(parse_button(line, &args[0])?)
+            }
+            "key" => {
+                arity(1, 2)?;
+                let duration_ms = match args.get(1) {
+                    Some(ms) => parse_number(line, ms)?,
+                    None => DEFAULT_KEY_DURATION_MS,
+                };
+                Command::Key {
+                    key: parse_key(line, &args[0])?,
+                    duration_ms,
+                }
+            }
+            "keydown" => {
+                arity(1, 1)?;
+                Command::KeyDown(parse_key(line, &args[0])?)
+            }
+            "keyup" => {
+                arity(1, 1)?;
+                Command::KeyUp(parse_key(line, &args[0])?)
+            }
+            "type" => {
+                arity(1, 1)?;
+                Command::Type(args[0].clone())
+            }
+            "wait" => {
+                arity(1, 1)?;
+                Command::Wait(Duration::from_millis(parse_number(line, &args[0])?))
+            }
+            _ => return self.parse_control(line, name, args, arity),
+        };
+        self.emit(line, Instruction::Command(command));
+        Ok(())
+    }
+
+    fn parse_control(
+        &mut self,
+        line: usize,
+        name: &str,
+        args: &[String],
+        arity: impl Fn(usize, usize) -> KmboxResult<()>,
+    ) -> KmboxResult<()> {
+        match name {
+            "repeat" => {
+                arity(0, 1)?;
+                let count = match args.first() {
+                    Some(count) => Some(parse_number(line, count)?),
+                    None => None,
+                };
+                let slot = self.loops;
+                self.loops += 1;
+                self.emit(line, Instruction::LoopInit { slot, count });
+                let test = self.emit(line, Instruction::LoopTest { slot, end: 0 });
+                self.blocks.push(Block::Repeat { line, test });
+            }
+            "if" => {
+                let (negate, args) = match args.first() {
+                    Some(first) if first.eq_ignore_ascii_case("not") => (true, &args[1..]),
+                    _ => (false, args),
+                };
+                if args.len() != 2 {
+                    return Err(line_error(
+                        line,
+                        "if 的格式为 if [not] key KEY 或 if [not] button BUTTON",
+                    ));
+                }
+                let condition = match args[0].to_ascii_lowercase().as_str() {
+                    "key" => Condition::Key(parse_key(line, &args[1])?),
+                    "button" => Condition::Button(parse_button(line, &args[1])?),
+                    other => return Err(line_error(line, format!("未知的条件: {}", other))),
+                };
+                let branch = self.emit(
+                    line,
+                    Instruction::JumpUnless {
+                        condition,
+                        negate,
+                        target: 0,
+                    },
+                );
+                self.blocks.push(Block::If { line, branch });
+            }
+            "else" => {
+                arity(0, 0)?;
+                let Some(Block::If {
+                    line: if_line,
+                    branch,
+                }) = self.blocks.pop()
+                else {
+                    return Err(line_error(line, "else 没有对应的 if"));
+                };
+                let jump = self.emit(line, Instruction::Jump(0));
+                self.patch(branch, self.instructions.len());
+                self.blocks.push(Block::Else {
+                    line: if_line,
+                    jump,
+                });
+            }
+            "end" => {
+                arity(0, 0)?;
+                match self.blocks.pop() {
+                    Some(Block::Repeat { test, .. }) => {
+                        self.emit(line, Instruction::Jump(test));
+                        self.patch(test, self.instructions.len());
+                    }
+                    Some(Block::If { branch: index, .. })
+                    | Some(Block::Else { jump: index, .. }) => {
+                        self.patch(index, self.instructions.len());
+                    }
+                    None => return Err(line_error(line, "end 没有对应的 repeat 或 if")),
+                }
+            }
+            "label" => {
+                arity(1, 1)?;
+                let label = args[0].clone();
+                if self.labels.contains_key(&label) {
+                    return Err(line_error(line, format!("标签重复定义: {}", label)));
+                }
+                self.labels.insert(label, self.instructions.len());
+            }
+            "goto" => {
+                arity(1, 1)?;
+                let index = self.emit(line, Instruction::Jump(0));
+                self.gotos.push((index, args[0].clone(), line));
+            }
+            "stop" => {
+                arity(0, 0)?;
+                self.emit(line, Instruction::Stop);
+            }
+            _ => return Err(line_error(line, format!("未知的命令: {}", name))),
+        }
+        Ok(())
+    }
+}
+
+/// 脚本执行器
+pub struct Executor<'a> {
+    kmbox: &'a KmboxAI,
+    keyboard: &'a Keyboard,
+    stop: Arc<AtomicBool>,
+}
+
+impl<'a> Executor<'a> {
+    /// 使用指定的控制器创建执行器
+    pub fn new(kmbox: &'a KmboxAI, keyboard: &'a Keyboard) -> Self {
+        Self {
+            kmbox,
+            keyboard,
+            stop: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 使用外部停止标志，标志被设置后脚本在当前命令完成后结束
+    pub fn with_stop_flag(mut self, stop: Arc<AtomicBool>) -> Self {
+        self.stop = stop;
+        self
+    }
+
+    /// 执行脚本
+    ///
+    /// 执行出错时返回的错误包含出错的行号；结束时（包括出错和被停止）释放脚本中按下但未释放的按键。
+    pub fn run(&self, script: &Script) -> KmboxResult<()> {
+        let result = self.execute(script);
+        let released = self.kmbox.held_inputs().is_empty() && self.keyboard.held_keys().is_empty();
+        if !released {
+            let _ = self.kmbox.release_all();
+            let _ = self.keyboard.release_all();
+        }
+        result
+    }
+
+    fn execute(&self, script: &Script) -> KmboxResult<()> {
+        let mut counters: Vec<Option<u32>> = vec![Some(0); script.loops];
+        let mut pc = 0;
+        while let Some((line, instruction)) = script.instructions.get(pc) {
+            if self.stopped() {
+                return Ok(());
+            }
+            pc += 1;
+            match instruction {
+                Instruction::Command(command) => self
+                    .command(command)
+                    .map_err(|e| e.with_context(&format!("第 {} 行", line)))?,
+                Instruction::Jump(target) => pc = *target,
+                Instruction::JumpUnless {
+                    condition,
+                    negate,
+                    target,
+                } => {
+                    let holds = self
+                        .condition(condition)
+                        .map_err(|e| e.with_context(&format!("第 {} 行", line)))?;
+                    if holds == *negate {
+                        pc = *target;
+                    }
+                }
+                Instruction::LoopInit { slot, count } => counters[*slot] = *count,
+                Instruction::LoopTest { slot, end } => match &mut counters[*slot] {
+                    Some(0) => pc = *end,
+                    Some(count) => *count -= 1,
+                    None => {}
+                },
+                Instruction::Stop => return Ok(()),
+            }
+        }
+        Ok(())
+    }
+
+    fn stopped(&self) -> bool {
+        self.stop.load(Ordering::Acquire)
+    }
+
+    fn condition(&self, condition: &Condition) -> KmboxResult<bool> {
+        match condition {
+            Condition::Key(key) => self.kmbox.monitor_keyboard(key.hex_value() as i16),
+            Condition::Button(button) => match button {
+                MouseButton::Left => self.kmbox.monitor_mouse_left(),
+                MouseButton::Right => self.kmbox.monitor_mouse_right(),
+                MouseButton::Middle => self.kmbox.monitor_mouse_middle(),
+                MouseButton::Side1 => self.kmbox.monitor_mouse_side1(),
+                MouseButton::Side2 => self.kmbox.monitor_mouse_side2(),
+            },
+        }
+    }
+
+    fn command(&self, command: &Command) -> KmboxResult<()> {
+        match command {
+            Command::Move { x, y } => self.kmbox.mouse_move(*x, *y),
+            Command::Wheel(wheel) => self.kmbox.mouse_wheel(*wheel),
+            Command::Click { button, count } => {
+                for _ in 0..*count {
+                    self.kmbox.mouse_button(*button, MouseAction::Click)?;
+                }
+                Ok(())
+            }
+            Command::Hold(button) => self.kmbox.mouse_button(*button, MouseAction::Press),
+            Command::Release(button) => self.kmbox.mouse_button(*button, MouseAction::Release),
+            Command::Key { key, duration_ms } => self.keyboard.click_key(key.clone(), *duration_ms),
+            Command::KeyDown(key) => self.keyboard.press_key(key.clone()),
+            Command::KeyUp(key) => self.keyboard.release_key(key.clone()),
+            Command::Type(text) => self.keyboard.type_string(text),
+            Command::Wait(duration) => {
+                self.wait(*duration);
+                Ok(())
+            }
+        }
+    }
+
+    /// 等待指定时间，期间被停止时提前返回
+    fn wait(&self, duration: Duration) {
+        let deadline = std::time::Instant::now() + duration;
+        while !self.stopped() {
+            let now = std::time::Instant::now();
+            if now >= deadline {
+                break;
+            }
+            std::thread::sleep((deadline - now).min(STOP_POLL_INTERVAL));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
+
+    fn run(source: &str) -> (Arc<SimulatedBackend>, KmboxResult<()>) {
+        let sim = Arc::new(SimulatedBackend::new());
+        let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+        let keyboard = Keyboard::with_backend(sim.clone()).unwrap();
+        let result =
+            Script::parse(source).and_then(|script| Executor::new(&kmbox, &keyboard).run(&script));
+        (sim, result)
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize(1, r#"type "a \"b\" # c"  # 注释"#).unwrap(),
+            vec!["type", "a \"b\" # c"]
+        );
+        assert!(tokenize(3, r#"type "abc"#)
+            .unwrap_err()
+            .to_string()
+            .contains("第 3 行"));
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let error = |source: &str| Script::parse(source).unwrap_err().to_string();
+        assert!(error("move 1 2\nclick up").contains("第 2 行: 未知的鼠标按键"));
+        assert!(error("\n\nmove 1").contains("第 3 行: move 需要 2 个参数"));
+        assert!(error("key NOT_A_KEY").contains("第 1 行: 未知的按键"));
+        assert!(error("repeat 2\nwait 1").contains("第 1 行: repeat 没有对应的 end"));
+        assert!(error("wait 1\nend").contains("第 2 行: end 没有对应的"));
+        assert!(error("goto nowhere").contains("第 1 行: 标签不存在"));
+        assert!(error("jump 3").contains("第 1 行: 未知的命令"));
+    }
+
+    #[test]
+    fn test_commands() {
+        let (sim, result) = run(r#"
+            # 注释和空行会被忽略
+            MOVE 10 -5
+            hold left
+            release left
+            wheel -1
+            keydown LEFTSHIFT
+            key A 10
+            keyup leftshift
+            type "hi"
+        "#);
+        result.unwrap();
+        let shift = Key::LeftShift.hex_value() as i32;
+        let a = Key::A.hex_value() as i32;
+        assert_eq!(
+            sim.reports(),
+            vec![
+                HidReport::Move { x: 10, y: -5 },
+                HidReport::Button {
+                    button: MouseButton::Left,
+                    down: true
+                },
+                HidReport::Button {
+                    button: MouseButton::Left,
+                    down: false
+                },
+                HidReport::Wheel(-1),
+                HidReport::KeyDown(shift),
+                HidReport::KeyDown(a),
+                HidReport::KeyUp(a),
+                HidReport::KeyUp(shift),
+                HidReport::Text("hi".to_string()),
+                // KmboxAI 和 Keyboard 销毁时各复位一次键盘
+                HidReport::KeyInit,
+                HidReport::KeyInit,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_loops_labels_and_conditions() {
+        let (sim, result) = run("
+            repeat 2
+                repeat 3
+                    wheel 1
+                end
+                move 1 0
+            end
+            if key ENTER
+                wheel 9
+            else
+                wheel 2
+            end
+            if not button right
+                goto done
+            end
+            wheel 9
+            label done
+            repeat
+                move 0 1
+                stop
+            end
+            wheel 9
+        ");
+        result.unwrap();
+        let mut expected = Vec::new();
+        for _ in 0..2 {
+            expected.extend(std::iter::repeat_n(HidReport::Wheel(1), 3));
+            expected.push(HidReport::Move { x: 1, y: 0 });
+        }
+        expected.push(HidReport::Wheel(2));
+        expected.push(HidReport::Move { x: 0, y: 1 });
+        expected.extend([HidReport::KeyInit, HidReport::KeyInit]);
+        assert_eq!(sim.reports(), expected);
+    }
+
+    #[test]
+    fn test_runtime_error_and_release() {
+        let (sim, result) = run("hold right\nkeydown A\nmove 40000 0");
+        let error = result.unwrap_err();
+        assert!(matches!(error, KmboxError::ParameterError(_)));
+        assert!(error.to_string().contains("第 3 行"));
+        // 出错时释放脚本中按下的按键
+        let reports = sim.reports();
+        assert!(reports.contains(&HidReport::Button {
+            button: MouseButton::Right,
+            down: false
+        }));
+        assert!(reports.contains(&HidReport::KeyUp(Key::A.hex_value() as i32)));
+    }
+}
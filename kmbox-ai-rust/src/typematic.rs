@@ -0,0 +1,377 @@
+//! 按键连发模块
+//!
+//! 模拟键盘的 typematic 连发：按下按键，经过首次延迟后按固定间隔重新报告按下，
+//! 最后释放。每次重新报告都是一次释放加按下，主机把它当作一次新的按键，
+//! 连发次数只由 [`RepeatConfig`] 和按住时长决定，不受主机自身连发设置的影响。
+//!
+//! 按住在后台线程中执行，[`Keyboard::hold_key`](crate::keyboard::Keyboard::hold_key)
+//! 立即返回 [`KeyHold`]，可以等待完成并取得连发次数，也可以随时取消。
+//! 按下和释放使用返回状态的 `kmAI_keydown`/`kmAI_keyup`，设备出错时连发停止、释放按键，
+//! 错误由 [`KeyHold::wait`] 返回。
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use kmbox_ai_rust::keyboard::{Key, Keyboard};
+//! use kmbox_ai_rust::typematic::RepeatConfig;
+//! use std::time::Duration;
+//!
+//! # fn main() -> kmbox_ai_rust::KmboxResult<()> {
+//! let keyboard = Keyboard::new()?;
+//! let repeat = RepeatConfig::new()
+//!     .with_delay(Duration::from_millis(500))
+//!     .with_rate(20)?;
+//!
+//! // 按住 1 秒：500ms 后每 50ms 重复一次，共 10 次
+//! assert_eq!(repeat.repeats_within(Duration::from_secs(1)), 10);
+//! let hold = keyboard.hold_key(Key::Backspace, Duration::from_secs(1), Some(repeat))?;
+//! let repeats = hold.wait()?;
+//! assert_eq!(repeats, 10);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::Backend;
+use crate::error::{check_result, KmboxError, KmboxResult, Operation};
+use crate::failsafe::HeldTracker;
+use crate::keyboard::{resolve_key, Key};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 连发参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepeatConfig {
+    delay: Duration,
+    interval: Duration,
+}
+
+impl Default for RepeatConfig {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(500),
+            interval: Duration::from_micros(33_333),
+        }
+    }
+}
+
+impl RepeatConfig {
+    /// 创建连发参数：首次延迟 500ms，每秒 30 次
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置按下到第一次连发的延迟
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// 设置相邻两次连发的间隔
+    pub fn with_interval(mut self, interval: Duration) -> KmboxResult<Self> {
+        if interval.is_zero() {
+            return Err(KmboxError::ParameterError("连发间隔不能为0".to_string()));
+        }
+        self.interval = interval;
+        Ok(self)
+    }
+
+    /// 设置每秒连发次数
+    pub fn with_rate(self, per_second: u32) -> KmboxResult<Self> {
+        if per_second == 0 {
+            return Err(KmboxError::ParameterError(
+                "每秒连发次数不能为0".to_string(),
+            ));
+        }
+        self.with_interval(Duration::from_secs(1) / per_second)
+    }
+
+    /// 首次延迟
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// 连发间隔
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// 按住 `duration` 期间每次连发相对按下的时刻
+    ///
+    /// 连发时刻为 `delay + k * interval`，只包含早于释放时刻的连发。
+    pub fn schedule(&self, duration: Duration) -> Vec<Duration> {
+        let mut times = Vec::new();
+        let mut at = self.delay;
+        while at < duration {
+            times.push(at);
+            at += self.interval;
+        }
+        times
+    }
+
+    /// 按住 `duration` 的连发次数
+    pub fn repeats_within(&self, duration: Duration) -> u32 {
+        self.schedule(duration).len() as u32
+    }
+}
+
+/// 后台执行的按键按住
+///
+/// 丢弃时取消按住并立即释放按键。
+pub struct KeyHold {
+    key: Key,
+    cancel: Option<Sender<()>>,
+    handle: Option<JoinHandle<KmboxResult<u32>>>,
+    repeats: Arc<AtomicU32>,
+}
+
+impl KeyHold {
+    /// 按住的按键
+    pub fn key(&self) -> &Key {
+        &self.key
+    }
+
+    /// 已经发生的连发次数
+    pub fn repeats(&self) -> u32 {
+        self.repeats.load(Ordering::Acquire)
+    }
+
+    /// 按键是否已经释放
+    pub fn is_finished(&self) -> bool {
+        self.handle
+            .as_ref()
+            .is_none_or(|handle| handle.is_finished())
+    }
+
+    /// 等待按住结束，返回连发次数
+    pub fn wait(mut self) -> KmboxResult<u32> {
+        self.join()
+    }
+
+    /// 取消按住，释放按键后返回已经发生的连发次数
+    pub fn cancel(mut self) -> KmboxResult<u32> {
+        self.cancel.take();
+        self.join()
+    }
+
+    fn join(&mut self) -> KmboxResult<u32> {
+        match self.handle.take() {
+            Some(handle) => handle
+                .join()
+                .map_err(|_| KmboxError::SystemError("按键连发线程异常退出".to_string()))?,
+            None => Ok(self.repeats()),
+        }
+    }
+}
+
+impl Drop for KeyHold {
+    fn drop(&mut self) {
+        self.cancel.take();
+        let _ = self.join();
+    }
+}
+
+/// 按计划连发，`cancelled` 收到取消（或发送端被丢弃）时提前结束
+fn repeat_key(
+    backend: &dyn Backend,
+    value: i32,
+    start: Instant,
+    schedule: Vec<Duration>,
+    duration: Duration,
+    cancelled: Receiver<()>,
+    counter: &AtomicU32,
+) -> KmboxResult<()> {
+    // 在截止时刻之前收到取消时返回 false
+    let wait_until = |deadline: Instant| {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        matches!(
+            cancelled.recv_timeout(timeout),
+            Err(RecvTimeoutError::Timeout)
+        )
+    };
+    for at in schedule {
+        if !wait_until(start + at) {
+            return Ok(());
+        }
+        check_result(backend.key_up(value), Operation::KeyUp)?;
+        check_result(backend.key_down(value), Operation::KeyDown)?;
+        counter.fetch_add(1, Ordering::AcqRel);
+    }
+    wait_until(start + duration);
+    Ok(())
+}
+
+/// 在后台线程中按住按键
+///
+/// 线程启动失败时立即释放按键并返回错误。
+pub(crate) fn spawn(
+    backend: Arc<dyn Backend>,
+    held: Arc<HeldTracker>,
+    key: Key,
+    duration: Duration,
+    repeat: Option<RepeatConfig>,
+) -> KmboxResult<KeyHold> {
+    let (value, _) = resolve_key(backend.as_ref(), &key)?;
+    let schedule = repeat.map_or_else(Vec::new, |repeat| repeat.schedule(duration));
+    let (cancel, cancelled) = mpsc::channel::<()>();
+    let repeats = Arc::new(AtomicU32::new(0));
+
+    check_result(backend.key_down(value), Operation::KeyDown)?;
+    // 按名称记录，与 `Keyboard::press_key` 按下的按键一起由 `held_keys` 报告
+    held.set_soft_key(&key, true);
+    let start = Instant::now();
+
+    let thread_backend = backend.clone();
+    let thread_held = held.clone();
+    let thread_key = key.clone();
+    let counter = repeats.clone();
+    let handle = thread::Builder::new()
+        .name("kmbox-typematic".to_string())
+        .spawn(move || {
+            let backend = thread_backend.as_ref();
+            let result = repeat_key(
+                backend, value, start, schedule, duration, cancelled, &counter,
+            );
+            let released = check_result(backend.key_up(value), Operation::KeyUp);
+            thread_held.set_soft_key(&thread_key, false);
+            result?;
+            released?;
+            Ok(counter.load(Ordering::Acquire))
+        });
+
+    match handle {
+        Ok(handle) => Ok(KeyHold {
+            key,
+            cancel: Some(cancel),
+            handle: Some(handle),
+            repeats,
+        }),
+        Err(e) => {
+            backend.key_up(value);
+            held.set_soft_key(&key, false);
+            Err(KmboxError::io("启动按键连发线程", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HidReport, SimulatedBackend};
+    use crate::keyboard::Keyboard;
+
+    fn ms(value: u64) -> Duration {
+        Duration::from_millis(value)
+    }
+
+    #[test]
+    fn test_schedule() {
+        let repeat = RepeatConfig::new()
+            .with_delay(ms(500))
+            .with_rate(20)
+            .unwrap();
+        assert_eq!(repeat.interval(), ms(50));
+        assert_eq!(repeat.repeats_within(ms(1000)), 10);
+        assert_eq!(repeat.repeats_within(ms(500)), 0);
+        assert_eq!(repeat.repeats_within(ms(501)), 1);
+        assert_eq!(repeat.schedule(ms(620)), [ms(500), ms(550), ms(600)]);
+
+        assert!(RepeatConfig::new().with_rate(0).is_err());
+        assert!(RepeatConfig::new().with_interval(Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_hold_with_repeat() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let keyboard = Keyboard::with_backend(sim.clone()).unwrap();
+        let repeat = RepeatConfig::new()
+            .with_delay(ms(20))
+            .with_interval(ms(10))
+            .unwrap();
+
+        let start = Instant::now();
+        let hold = keyboard.hold_key(Key::A, ms(60), Some(repeat)).unwrap();
+        assert_eq!(keyboard.held_keys(), [Key::A]);
+        assert_eq!(hold.wait().unwrap(), 4);
+        assert!(start.elapsed() >= ms(60));
+        assert!(keyboard.held_keys().is_empty());
+
+        let a = Key::A.hex_value() as i32;
+        let mut expected = vec![HidReport::KeyDown(a)];
+        for _ in 0..4 {
+            expected.push(HidReport::KeyUp(a));
+            expected.push(HidReport::KeyDown(a));
+        }
+        expected.push(HidReport::KeyUp(a));
+        assert_eq!(sim.reports(), expected);
+    }
+
+    #[test]
+    fn test_hold_without_repeat() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let keyboard = Keyboard::with_backend(sim.clone()).unwrap();
+
+        let hold = keyboard.hold_key(Key::Space, ms(20), None).unwrap();
+        assert_eq!(hold.wait().unwrap(), 0);
+        let space = Key::Space.hex_value() as i32;
+        assert_eq!(
+            sim.reports(),
+            [HidReport::KeyDown(space), HidReport::KeyUp(space)]
+        );
+    }
+
+    #[test]
+    fn test_cancel_releases_key() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let keyboard = Keyboard::with_backend(sim.clone()).unwrap();
+
+        let start = Instant::now();
+        let hold = keyboard
+            .hold_key(
+                Key::Backspace,
+                Duration::from_secs(10),
+                Some(RepeatConfig::new()),
+            )
+            .unwrap();
+        assert!(!hold.is_finished());
+        assert_eq!(hold.cancel().unwrap(), 0);
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(keyboard.held_keys().is_empty());
+
+        // 丢弃句柄同样会释放按键
+        let hold = keyboard
+            .hold_key(Key::Enter, Duration::from_secs(10), None)
+            .unwrap();
+        drop(hold);
+        assert!(keyboard.held_keys().is_empty());
+        let enter = Key::Enter.hex_value() as i32;
+        assert_eq!(sim.reports().last(), Some(&HidReport::KeyUp(enter)));
+    }
+
+    #[test]
+    fn test_device_errors_reported() {
+        let sim = Arc::new(SimulatedBackend::new());
+        let keyboard = Keyboard::with_backend(sim.clone()).unwrap();
+
+        // 按下失败时不启动线程，也不记录按住
+        sim.inject_fault(-4);
+        let err = keyboard.hold_key(Key::A, ms(20), None).err().unwrap();
+        assert_eq!(err.code(), Some(-4));
+        assert!(keyboard.held_keys().is_empty());
+        sim.clear_fault();
+
+        // 连发过程中出错时停止连发并返回错误
+        let repeat = RepeatConfig::new()
+            .with_delay(ms(20))
+            .with_interval(ms(10))
+            .unwrap();
+        let hold = keyboard.hold_key(Key::A, ms(100), Some(repeat)).unwrap();
+        sim.inject_fault(-4);
+        let err = hold.wait().unwrap_err();
+        assert_eq!(err.code(), Some(-4));
+        assert_eq!(err.operation(), Some("key_up"));
+        assert!(keyboard.held_keys().is_empty());
+    }
+}
@@ -3,46 +3,9 @@
 //! 提供图像捕获、处理和格式转换功能
 //! 完整包装了common.h.rs中的所有图像相关结构体和函数
 
+use crate::backend::{default_backend, Backend};
 use crate::error::{KmboxError, KmboxResult};
-
-// 导入图像处理相关的C函数
-unsafe extern "C" {
-    // 图像捕获函数
-    #[link_name = "\u{1}_Z15capture_screen_v"]
-    fn capture_screen_v() -> *mut ImageBufferT;
-
-    // 图像加载和保存函数
-    #[link_name = "\u{1}_Z12load_image_vPKc"]
-    fn load_image_v(path: *const std::os::raw::c_char) -> *mut ImageBufferT;
-
-    #[link_name = "\u{1}_Z12save_image_vPKcP15image_buffer_t"]
-    fn save_image_v(
-        path: *const std::os::raw::c_char,
-        buffer: *const ImageBufferT,
-    ) -> std::os::raw::c_int;
-
-    // 图像格式转换函数
-    #[link_name = "\u{1}_Z18convert_image_formatP15image_buffer_tS0_"]
-    fn convert_image_format(
-        src: *const ImageBufferT,
-        dst: *mut ImageBufferT,
-    ) -> std::os::raw::c_int;
-
-    // 图像裁剪和缩放函数
-    #[link_name = "\u{1}_Z12crop_image_vP15image_buffer_tPK11image_rect_t"]
-    fn crop_image_v(src: *const ImageBufferT, rect: *const ImageRectT) -> *mut ImageBufferT;
-
-    #[link_name = "\u{1}_Z13resize_image_vP15image_buffer_tii"]
-    fn resize_image_v(
-        src: *const ImageBufferT,
-        new_width: std::os::raw::c_int,
-        new_height: std::os::raw::c_int,
-    ) -> *mut ImageBufferT;
-
-    // 内存管理函数
-    #[link_name = "\u{1}_Z15release_image_vP15image_buffer_t"]
-    fn release_image_v(buffer: *mut ImageBufferT);
-}
+use std::sync::Arc;
 
 // 导入C结构体定义
 #[repr(C)]
@@ -143,6 +106,7 @@ impl ImageRect {
 }
 
 /// 图像数据结构
+#[derive(Debug, Clone)]
 pub struct Image {
     pub width: i32,
     pub height: i32,
@@ -164,16 +128,18 @@ impl Image {
 
     /// 计算图像数据大小
     fn calculate_size(width: i32, height: i32, format: ImageFormat) -> usize {
+        let (width, height) = (width.max(0) as usize, height.max(0) as usize);
         let pixel_size = match format {
             ImageFormat::Gray8 => 1,
             ImageFormat::Rgb888 | ImageFormat::Bgr888 => 3,
             ImageFormat::Rgba8888 => 4,
             ImageFormat::Yuv420spNv21 | ImageFormat::Yuv420spNv12 => {
-                // YUV420格式：Y分量 + UV分量
-                (width * height + width * height / 2) as usize
+                // YUV420格式：Y分量 + 每 2x2 像素共用一组 UV 分量
+                let uv = width.div_ceil(2) * height.div_ceil(2) * 2;
+                return width * height + uv;
             }
         };
-        ((width * height) as usize) * pixel_size
+        width * height * pixel_size
     }
 
     /// 获取图像数据
@@ -250,13 +216,22 @@ pub struct DetectionResult {
 
 /// 图像处理器
 pub struct ImageProcessor {
+    backend: Arc<dyn Backend>,
     initialized: bool,
 }
 
 impl ImageProcessor {
     /// 创建新的图像处理器
     pub fn new() -> KmboxResult<Self> {
-        Ok(Self { initialized: true })
+        Self::with_backend(default_backend())
+    }
+
+    /// 使用指定后端创建图像处理器
+    pub fn with_backend(backend: Arc<dyn Backend>) -> KmboxResult<Self> {
+        Ok(Self {
+            backend,
+            initialized: true,
+        })
     }
 
     /// 捕获屏幕图像
@@ -267,20 +242,9 @@ impl ImageProcessor {
             ));
         }
 
-        unsafe {
-            let buffer_ptr = capture_screen_v();
-            if buffer_ptr.is_null() {
-                return Err(KmboxError::ImageError("屏幕捕获失败".to_string()));
-            }
-
-            let buffer = *buffer_ptr;
-            let image = Image::from_c_buffer(&buffer);
-
-            // 释放C缓冲区
-            release_image_v(buffer_ptr);
-
-            Ok(image)
-        }
+        self.backend
+            .capture_screen()
+            .ok_or_else(|| KmboxError::ImageError("屏幕捕获失败".to_string()))
     }
 
     /// 从文件加载图像
@@ -294,23 +258,9 @@ impl ImageProcessor {
         let path_cstr = std::ffi::CString::new(path)
             .map_err(|e| KmboxError::ParameterError(format!("无效的文件路径: {}", e)))?;
 
-        unsafe {
-            let buffer_ptr = load_image_v(path_cstr.as_ptr());
-            if buffer_ptr.is_null() {
-                return Err(KmboxError::ImageError(format!(
-                    "无法加载图像文件: {}",
-                    path
-                )));
-            }
-
-            let buffer = *buffer_ptr;
-            let image = Image::from_c_buffer(&buffer);
-
-            // 释放C缓冲区
-            release_image_v(buffer_ptr);
-
-            Ok(image)
-        }
+        self.backend
+            .load_image(&path_cstr)
+            .ok_or_else(|| KmboxError::ImageError(format!("无法加载图像文件: {}", path)))
     }
 
     /// 保存图像到文件
@@ -324,16 +274,12 @@ impl ImageProcessor {
         let path_cstr = std::ffi::CString::new(path)
             .map_err(|e| KmboxError::ParameterError(format!("无效的文件路径: {}", e)))?;
 
-        let buffer = image.to_c_buffer();
-
-        unsafe {
-            let result = save_image_v(path_cstr.as_ptr(), &buffer);
-            if result != 0 {
-                return Err(KmboxError::ImageError(format!(
-                    "保存图像文件失败: {}",
-                    path
-                )));
-            }
+        let result = self.backend.save_image(&path_cstr, image);
+        if result != 0 {
+            return Err(KmboxError::ImageError(format!(
+                "保存图像文件失败: {}",
+                path
+            )));
         }
 
         Ok(())
@@ -347,27 +293,9 @@ impl ImageProcessor {
             ));
         }
 
-        let src_buffer = image.to_c_buffer();
-        let mut dst_buffer = ImageBufferT {
-            width: image.width,
-            height: image.height,
-            width_stride: image.width,
-            height_stride: image.height,
-            format: target_format.as_u32(),
-            virt_addr: std::ptr::null_mut(),
-            size: 0,
-            fd: -1,
-        };
-
-        unsafe {
-            let result = convert_image_format(&src_buffer, &mut dst_buffer);
-            if result != 0 {
-                return Err(KmboxError::ImageError("图像格式转换失败".to_string()));
-            }
-
-            let converted_image = Image::from_c_buffer(&dst_buffer);
-            Ok(converted_image)
-        }
+        self.backend
+            .convert_image_format(image, target_format)
+            .ok_or_else(|| KmboxError::ImageError("图像格式转换失败".to_string()))
     }
 
     /// 裁剪图像
@@ -378,28 +306,9 @@ impl ImageProcessor {
             ));
         }
 
-        let src_buffer = image.to_c_buffer();
-        let c_rect = ImageRectT {
-            left: rect.left,
-            top: rect.top,
-            right: rect.right,
-            bottom: rect.bottom,
-        };
-
-        unsafe {
-            let buffer_ptr = crop_image_v(&src_buffer, &c_rect);
-            if buffer_ptr.is_null() {
-                return Err(KmboxError::ImageError("图像裁剪失败".to_string()));
-            }
-
-            let buffer = *buffer_ptr;
-            let cropped_image = Image::from_c_buffer(&buffer);
-
-            // 释放C缓冲区
-            release_image_v(buffer_ptr);
-
-            Ok(cropped_image)
-        }
+        self.backend
+            .crop_image(image, rect)
+            .ok_or_else(|| KmboxError::ImageError("图像裁剪失败".to_string()))
     }
 
     /// 调整图像大小
@@ -410,22 +319,9 @@ impl ImageProcessor {
             ));
         }
 
-        let src_buffer = image.to_c_buffer();
-
-        unsafe {
-            let buffer_ptr = resize_image_v(&src_buffer, new_width, new_height);
-            if buffer_ptr.is_null() {
-                return Err(KmboxError::ImageError("图像缩放失败".to_string()));
-            }
-
-            let buffer = *buffer_ptr;
-            let resized_image = Image::from_c_buffer(&buffer);
-
-            // 释放C缓冲区
-            release_image_v(buffer_ptr);
-
-            Ok(resized_image)
-        }
+        self.backend
+            .resize_image(image, new_width, new_height)
+            .ok_or_else(|| KmboxError::ImageError("图像缩放失败".to_string()))
     }
 }
 
@@ -3,102 +3,11 @@
 //! 提供基于YOLO算法的目标检测功能
 //! 完整包装了kmboxYOLO.h.rs中的所有YOLO相关结构体和函数
 
-use crate::error::{KmboxError, KmboxResult};
-use crate::vision::{Image, ImageBufferT, ImageRect, ImageRectT};
-
-// 导入YOLO相关的C函数
-unsafe extern "C" {
-    // YOLO检测函数
-    #[link_name = "\u{1}_Z15yolo_detect_v_vP15image_buffer_tP20object_detected_list"]
-    fn yolo_detect_v_v(
-        src: *const ImageBufferT,
-        result: *mut ObjectDetectedList,
-    ) -> std::os::raw::c_int;
-
-    // YOLO初始化函数
-    #[link_name = "\u{1}_Z15yolo_init_v_vPKc"]
-    fn yolo_init_v_v(model_path: *const std::os::raw::c_char) -> *mut RknnContextT;
-
-    #[link_name = "\u{1}_Z15yolo_destroy_v_vP15rknn_context_t"]
-    fn yolo_destroy_v_v(ctx: *mut RknnContextT);
-
-    // 参数设置函数
-    #[link_name = "\u{1}_Z20yolo_set_nms_thresh_vf"]
-    fn yolo_set_nms_thresh_v(nms_thresh: f32);
-
-    #[link_name = "\u{1}_Z20yolo_set_box_thresh_vf"]
-    fn yolo_set_box_thresh_v(box_thresh: f32);
-}
-
-// 导入C结构体定义
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct RknnContextT {
-    pub rknn_ctx: RknnContext,
-    pub io_num: RknnInputOutputNum,
-    pub input_attrs: *mut RknnTensorAttr,
-    pub output_attrs: *mut RknnTensorAttr,
-    pub model_channel: std::os::raw::c_int,
-    pub model_width: std::os::raw::c_int,
-    pub model_height: std::os::raw::c_int,
-    pub is_quant: bool,
-    pub nms_thresh: f32,
-    pub box_thresh: f32,
-    pub obj_class_num: std::os::raw::c_int,
-    pub yolo_type: std::os::raw::c_int,
-    pub anchor: [[std::os::raw::c_int; 6usize]; 3usize],
-}
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct ObjectDetectResult {
-    pub box_: ImageRectT,
-    pub prop: f32,
-    pub cls_id: std::os::raw::c_int,
-}
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct ObjectDetectedList {
-    pub id: std::os::raw::c_int,
-    pub count: std::os::raw::c_int,
-    pub results: [ObjectDetectResult; 128usize],
-}
-
-// 类型别名
-pub type RknnContext = u64;
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct RknnInputOutputNum {
-    pub n_input: u32,
-    pub n_output: u32,
-}
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct RknnTensorAttr {
-    pub index: u32,
-    pub n_dims: u32,
-    pub dims: [u32; 16usize],
-    pub name: [std::os::raw::c_char; 256usize],
-    pub n_elems: u32,
-    pub size: u32,
-    pub fmt: RknnTensorFormat,
-    pub type_: RknnTensorType,
-    pub qnt_type: RknnTensorQntType,
-    pub fl: i8,
-    pub zp: i32,
-    pub scale: f32,
-    pub w_stride: u32,
-    pub size_with_stride: u32,
-    pub pass_through: u8,
-    pub h_stride: u32,
-}
-
-pub type RknnTensorFormat = std::os::raw::c_uint;
-pub type RknnTensorType = std::os::raw::c_uint;
-pub type RknnTensorQntType = std::os::raw::c_uint;
+use crate::backend::{default_backend, Backend};
+use crate::error::{KmboxError, KmboxResult, Operation};
+use crate::ffi::{object_detected_list, rknn_context_t};
+use crate::vision::{Image, ImageRect};
+use std::sync::Arc;
 
 /// 目标类型枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -419,34 +328,42 @@ pub struct DetectionResult {
 }
 
 /// YOLO检测器
+///
+/// 厂商库没有说明检测器能否跨线程使用，检测器不实现 `Send`，只在创建它的线程上使用；
+/// 需要在后台推理时用 [`InferenceWorker`](crate::asynchronous::InferenceWorker) 在推理线程上创建。
 pub struct YoloDetector {
+    backend: Arc<dyn Backend>,
     initialized: bool,
     #[allow(dead_code)]
     model_path: String,
-    context: Option<*mut RknnContextT>,
+    context: Option<*mut rknn_context_t>,
 }
 
 impl YoloDetector {
     /// 创建新的YOLO检测器
     pub fn new(model_path: &str) -> KmboxResult<Self> {
+        Self::with_backend(default_backend(), model_path)
+    }
+
+    /// 使用指定后端创建YOLO检测器
+    pub fn with_backend(backend: Arc<dyn Backend>, model_path: &str) -> KmboxResult<Self> {
         let path_cstr = std::ffi::CString::new(model_path)
             .map_err(|e| KmboxError::ParameterError(format!("无效的模型路径: {}", e)))?;
 
-        unsafe {
-            let context_ptr = yolo_init_v_v(path_cstr.as_ptr());
-            if context_ptr.is_null() {
-                return Err(KmboxError::ModelError(format!(
-                    "无法加载YOLO模型: {}",
-                    model_path
-                )));
-            }
-
-            Ok(Self {
-                initialized: true,
-                model_path: model_path.to_string(),
-                context: Some(context_ptr),
-            })
+        let context_ptr = backend.yolo_init(&path_cstr);
+        if context_ptr.is_null() {
+            return Err(KmboxError::ModelError(format!(
+                "无法加载YOLO模型: {}",
+                model_path
+            )));
         }
+
+        Ok(Self {
+            backend,
+            initialized: true,
+            model_path: model_path.to_string(),
+            context: Some(context_ptr),
+        })
     }
 
     /// 检测图像中的目标
@@ -457,47 +374,33 @@ impl YoloDetector {
             ));
         }
 
-        let buffer = image.to_c_buffer();
-        let mut result_list = ObjectDetectedList {
-            id: 0,
-            count: 0,
-            results: [ObjectDetectResult {
-                box_: ImageRectT {
-                    left: 0,
-                    top: 0,
-                    right: 0,
-                    bottom: 0,
-                },
-                prop: 0.0,
-                cls_id: 0,
-            }; 128],
-        };
-
-        unsafe {
-            let detect_result = yolo_detect_v_v(&buffer, &mut result_list);
-            if detect_result != 0 {
-                return Err(KmboxError::InferenceError("YOLO检测失败".to_string()));
-            }
+        // object_detected_list 只包含整数和浮点数，全零是合法的初始状态
+        let mut result_list: object_detected_list = unsafe { std::mem::zeroed() };
+
+        let detect_result = self.backend.yolo_detect(image, &mut result_list);
+        if detect_result != 0 {
+            return Err(KmboxError::call(Operation::YoloDetect, detect_result));
+        }
 
-            let mut objects = Vec::new();
-            for i in 0..result_list.count {
-                let result = result_list.results[i as usize];
-                let bbox = BoundingBox::new(
+        let count = (result_list.count.max(0) as usize).min(result_list.results.len());
+        let objects = result_list.results[..count]
+            .iter()
+            .map(|result| {
+                BoundingBox::new(
                     result.box_.left as f32 / image.width as f32,
                     result.box_.top as f32 / image.height as f32,
                     (result.box_.right - result.box_.left) as f32 / image.width as f32,
                     (result.box_.bottom - result.box_.top) as f32 / image.height as f32,
                     result.prop,
                     ObjectType::from_class_id(result.cls_id),
-                );
-                objects.push(bbox);
-            }
-
-            Ok(DetectionResult {
-                objects,
-                processing_time_ms: 0, // TODO: 获取实际处理时间
+                )
             })
-        }
+            .collect();
+
+        Ok(DetectionResult {
+            objects,
+            processing_time_ms: 0, // TODO: 获取实际处理时间
+        })
     }
 
     /// 设置检测阈值
@@ -508,9 +411,7 @@ impl YoloDetector {
             ));
         }
 
-        unsafe {
-            yolo_set_box_thresh_v(threshold);
-        }
+        self.backend.yolo_set_box_thresh(threshold);
         Ok(())
     }
 
@@ -535,9 +436,7 @@ impl YoloDetector {
             ));
         }
 
-        unsafe {
-            yolo_set_nms_thresh_v(threshold);
-        }
+        self.backend.yolo_set_nms_thresh(threshold);
         Ok(())
     }
 
@@ -559,7 +458,7 @@ impl Drop for YoloDetector {
     fn drop(&mut self) {
         if let Some(context_ptr) = self.context {
             unsafe {
-                yolo_destroy_v_v(context_ptr);
+                self.backend.yolo_destroy(context_ptr);
             }
         }
     }
@@ -568,6 +467,8 @@ impl Drop for YoloDetector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::{SimulatedBackend, SimulatedDetection};
+    use crate::vision::ImageFormat;
 
     #[test]
     fn test_bounding_box_creation() {
@@ -588,8 +489,46 @@ mod tests {
 
     #[test]
     fn test_yolo_detector_creation() {
-        let detector = YoloDetector::new("model.rknn");
-        assert!(detector.is_ok());
+        let sim = Arc::new(SimulatedBackend::new());
+        assert!(YoloDetector::with_backend(sim.clone(), "model.rknn").is_err());
+
+        sim.add_model("model.rknn");
+        let detector = YoloDetector::with_backend(sim.clone(), "model.rknn").unwrap();
+        assert_eq!(sim.state().yolo_contexts, 1);
+        assert_eq!(sim.state().loaded_models, vec!["model.rknn".to_string()]);
+
+        drop(detector);
+        assert_eq!(sim.state().yolo_contexts, 0);
+    }
+
+    #[test]
+    fn test_yolo_detect() {
+        let sim = Arc::new(SimulatedBackend::new());
+        sim.add_model("model.rknn");
+        sim.set_detections(vec![SimulatedDetection {
+            rect: ImageRect::new(10, 20, 30, 60),
+            confidence: 0.9,
+            class_id: 2,
+        }]);
+
+        let mut detector = YoloDetector::with_backend(sim.clone(), "model.rknn").unwrap();
+        detector.set_confidence_threshold(0.6).unwrap();
+        detector.set_nms_threshold(0.3).unwrap();
+        assert_eq!(sim.state().yolo_box_thresh, 0.6);
+        assert_eq!(sim.state().yolo_nms_thresh, 0.3);
+
+        let image = Image::new(100, 100, ImageFormat::Rgb888);
+        let result = detector.detect(&image).unwrap();
+        assert_eq!(result.objects.len(), 1);
+        let object = &result.objects[0];
+        assert_eq!(object.object_type, ObjectType::Car);
+        assert_eq!((object.x, object.y), (0.1, 0.2));
+        assert_eq!((object.width, object.height), (0.2, 0.4));
+        assert_eq!(object.confidence, 0.9);
+
+        sim.inject_fault(-1);
+        let error = detector.detect(&image).unwrap_err();
+        assert_eq!(error.operation(), Some("yolo_detect"));
     }
 
     #[test]
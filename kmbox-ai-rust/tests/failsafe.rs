@@ -0,0 +1,42 @@
+//! 通过已安装的 panic 钩子释放输入
+//!
+//! panic 钩子对整个进程生效，会释放所有实例按下的输入，
+//! 因此放在单独的集成测试进程中，不影响并行运行的单元测试。
+
+use kmbox_ai_rust::failsafe;
+use kmbox_ai_rust::keyboard::{Key, Keyboard};
+use kmbox_ai_rust::kmbox_ai::{KmboxAI, MouseAction, MouseButton};
+use kmbox_ai_rust::SimulatedBackend;
+use std::panic;
+use std::sync::Arc;
+
+#[test]
+fn test_panic_hook_releases_everything() {
+    failsafe::install_panic_hook();
+    // 重复安装不会叠加钩子
+    failsafe::install_panic_hook();
+
+    let sim = Arc::new(SimulatedBackend::new());
+    let kmbox = KmboxAI::with_backend(sim.clone()).unwrap();
+    let keyboard = Keyboard::with_backend(sim.clone()).unwrap();
+    kmbox
+        .mouse_button(MouseButton::Left, MouseAction::Press)
+        .unwrap();
+    kmbox.key_down(4).unwrap();
+    keyboard.press_key(Key::LeftControl).unwrap();
+    kmbox.mask_keyboard(5).unwrap();
+
+    let result = panic::catch_unwind(|| panic!("模拟崩溃"));
+    assert!(result.is_err());
+
+    // 实例仍然存活，释放由 panic 钩子完成
+    assert!(kmbox.held_inputs().is_empty());
+    assert!(keyboard.held_keys().is_empty());
+    let state = sim.state();
+    assert!(state.mouse_buttons.is_empty());
+    assert!(state.keys_down.is_empty());
+    assert!(state.keyboard_masks.is_empty());
+    assert!(state
+        .reports
+        .contains(&kmbox_ai_rust::backend::HidReport::KeyInit));
+}